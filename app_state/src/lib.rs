@@ -0,0 +1,79 @@
+//! State of the egui apps kept between sessions through eframe storage,
+//! and exported to a JSON file to share a particular configuration.
+
+use std::fs;
+use egui::{Color32, Ui};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// An app whose settings can be saved and restored.
+///
+/// Whatever is computed from the settings, like the nodes of a spline, is marked `#[serde(skip)]`
+/// and rebuilt by [`State::restore`].
+pub trait State: Serialize + DeserializeOwned + Default {
+	/// Rebuilds the skipped fields after the settings were loaded.
+	fn restore(&mut self) {}
+}
+
+/// The state saved by the previous session, or the default one.
+pub fn load<T: State>(cc: &eframe::CreationContext<'_>) -> T {
+	let mut state: T = cc.storage
+		.and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+		.unwrap_or_default();
+	state.restore();
+	state
+}
+
+/// Saves `state` for the next session; call it from [`eframe::App::save`].
+pub fn save<T: State>(storage: &mut dyn eframe::Storage, state: &T) {
+	eframe::set_value(storage, eframe::APP_KEY, state);
+}
+
+pub fn export<T: State>(path: &str, state: &T) -> Result<(), String> {
+	let json = serde_json::to_string_pretty(state).map_err(|error| error.to_string())?;
+	fs::write(path, json + "\n").map_err(|error| format!("{path}: {error}"))
+}
+
+pub fn import<T: State>(path: &str) -> Result<T, String> {
+	let json = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+	let mut state: T = serde_json::from_str(&json).map_err(|error| format!("{path}: {error}"))?;
+	state.restore();
+	Ok(state)
+}
+
+/// A path with "export" and "import" buttons; `state` is replaced on import.
+///
+/// The path is remembered in the egui memory, so it is saved with the session as well.
+pub fn file_ui<T: State>(ui: &mut Ui, state: &mut T) {
+	let id = ui.id().with("state file");
+	let mut path = ui.data_mut(|data| data.get_persisted::<String>(id)).unwrap_or_else(|| "state.json".to_string());
+	let mut result = ui.data_mut(|data| data.get_temp::<Result<String, String>>(id));
+
+	ui.horizontal(|ui| {
+		ui.label("state file");
+		ui.text_edit_singleline(&mut path);
+
+		if ui.button("export").clicked() {
+			result = Some(export(&path, state).map(|_| format!("exported to {path}")));
+		}
+		if ui.button("import").clicked() {
+			result = Some(import(&path).map(|imported| {
+				*state = imported;
+				format!("imported from {path}")
+			}));
+		}
+
+		match &result {
+			Some(Ok(message)) => { ui.label(message); }
+			Some(Err(error)) => { ui.colored_label(Color32::RED, error); }
+			None => {}
+		}
+	});
+
+	ui.data_mut(|data| {
+		data.insert_persisted(id, path);
+		if let Some(result) = result {
+			data.insert_temp(id, result);
+		}
+	});
+}
@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use clap::Parser;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use crate::input::Result;
+use crate::output::{Format, Output};
+
+/// An experiment file: a list of runs of the `nm` subcommands and where to put their results.
+///
+/// ```toml
+/// name = "Relaxation method"
+///
+/// [output]
+/// directory = "results"
+/// formats = ["table", "csv"]
+///
+/// [[runs]]
+/// name = "sor"
+/// problem = "linsys"
+/// method = "sor"
+/// parameters = { matrix = "system.txt", eps = 0.5e-8, omega = 1.5 }
+/// ```
+///
+/// The parameters are the options of `nm <problem> <method>` without the leading dashes,
+/// so `first_derivative = 1` stands for `--first-derivative 1` and `at = [0.1, 0.2]` for `--at 0.1,0.2`.
+/// Paths are relative to the experiment file. Run names become file names in the output directory,
+/// so they cannot contain path separators, and `manifest` and `experiment` are taken by the bundle itself.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Experiment {
+	name: String,
+	#[serde(default)]
+	output: Outputs,
+	runs: Vec<Run>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Outputs {
+	/// `<experiment>-results` next to the experiment file by default.
+	directory: Option<PathBuf>,
+	#[serde(default = "Outputs::default_formats")]
+	formats: Vec<Format>,
+	/// Digits after the point in the markdown and latex files.
+	#[serde(default = "Outputs::default_precision")]
+	precision: usize,
+}
+
+impl Outputs {
+	fn default_formats() -> Vec<Format> {
+		vec![Format::Json, Format::Csv]
+	}
+
+	fn default_precision() -> usize {
+		6
+	}
+}
+
+impl Default for Outputs {
+	fn default() -> Self {
+		Self { directory: None, formats: Self::default_formats(), precision: Self::default_precision() }
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Run {
+	/// `<problem>-<method>` by default.
+	name: Option<String>,
+	problem: String,
+	method: String,
+	#[serde(default)]
+	parameters: Map<String, Value>,
+}
+
+/// Options whose values are paths, resolved against the directory of the experiment file.
+const PATH_OPTIONS: [&str; 5] = ["matrix", "rhs", "out", "residual", "nodes"];
+
+impl Run {
+	/// The name, checked to be usable as a file name in the output directory.
+	fn name(&self) -> Result<String> {
+		let name = self.name.clone().unwrap_or_else(|| format!("{}-{}", self.problem, self.method));
+
+		let mut components = Path::new(&name).components();
+		let plain = matches!((components.next(), components.next()), (Some(Component::Normal(_)), None));
+		if !plain || name.contains(['/', '\\']) {
+			return Err(format!("the run name '{name}' must be a plain file name, without path separators or '..'").into());
+		}
+		if name == "manifest" || name == "experiment" {
+			return Err(format!("the run name '{name}' is reserved for the files of the bundle").into());
+		}
+
+		Ok(name)
+	}
+
+	/// The command line of the run, starting with `nm`, as written in the experiment.
+	fn arguments(&self) -> Result<Vec<String>> {
+		self.arguments_in(Path::new(""))
+	}
+
+	/// The command line of the run with the paths joined to `base`.
+	fn arguments_in(&self, base: &Path) -> Result<Vec<String>> {
+		let mut arguments = vec!["nm".to_string(), self.problem.clone(), self.method.clone()];
+
+		for (key, value) in &self.parameters {
+			let option = format!("--{}", key.replace('_', "-"));
+			let value = match value {
+				Value::Bool(true) => {
+					arguments.push(option);
+					continue;
+				}
+				Value::Bool(false) => continue,
+				Value::Array(values) => values.iter().map(scalar).collect::<Result<Vec<_>>>()?.join(","),
+				value if PATH_OPTIONS.contains(&key.as_str()) => base.join(scalar(value)?).display().to_string(),
+				value => scalar(value)?,
+			};
+			arguments.push(format!("{option}={value}"));
+		}
+
+		Ok(arguments)
+	}
+}
+
+fn scalar(value: &Value) -> Result<String> {
+	match value {
+		Value::Number(number) => Ok(number.to_string()),
+		Value::String(string) => Ok(string.clone()),
+		_ => Err(format!("unsupported parameter value {value}").into()),
+	}
+}
+
+/// Parses one run the same way as the command line.
+#[derive(Parser)]
+#[command(name = "nm")]
+struct Invocation {
+	#[command(subcommand)]
+	command: crate::Command,
+}
+
+fn extension(format: Format) -> &'static str {
+	match format {
+		Format::Table => "txt",
+		Format::Json => "json",
+		Format::Csv => "csv",
+		Format::Markdown => "md",
+		Format::Latex => "tex",
+	}
+}
+
+/// Runs every run of the experiment at `path` and writes the results bundle.
+///
+/// The bundle holds a copy of the experiment, one file per run and format and `manifest.json`
+/// with the command line of every run. Nothing in it depends on the time or machine of the run,
+/// so running the same experiment twice gives identical bundles.
+pub fn run(path: &Path, out: Option<&Path>) -> Result<Output> {
+	let source = fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+	let experiment: Experiment = match path.extension().and_then(|extension| extension.to_str()) {
+		Some("toml") => toml::from_str(&source).map_err(|error| format!("{}: {error}", path.display()))?,
+		Some("json") => serde_json::from_str(&source).map_err(|error| format!("{}: {error}", path.display()))?,
+		_ => return Err(format!("{}: expected a .toml or .json file", path.display()).into()),
+	};
+
+	let base = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+	let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+	let directory = match (out, &experiment.output.directory) {
+		(Some(out), _) => out.to_path_buf(),
+		(None, Some(directory)) => base.join(directory),
+		(None, None) => base.join(format!("{stem}-results")),
+	};
+
+	let mut names = HashSet::new();
+	let mut invocations = Vec::new();
+	for run in &experiment.runs {
+		let name = run.name()?;
+		if !names.insert(name.clone()) {
+			return Err(format!("two runs are named '{name}'").into());
+		}
+
+		let arguments = run.arguments()?;
+		let invocation = Invocation::try_parse_from(run.arguments_in(base)?).map_err(|error| format!("run '{name}': {error}"))?;
+		if let crate::Command::Run { .. } = invocation.command {
+			return Err(format!("run '{name}': experiments cannot run other experiments").into());
+		}
+		invocations.push((name, arguments, invocation.command));
+	}
+
+	fs::create_dir_all(&directory).map_err(|error| format!("{}: {error}", directory.display()))?;
+	let directory = directory.canonicalize()?;
+	let copy = format!("experiment.{}", path.extension().unwrap().to_string_lossy());
+	fs::write(directory.join(&copy), &source)?;
+
+	let results = invocations.iter().map(|(name, _, command)| {
+		crate::run(command).map_err(|error| format!("run '{name}': {error}").into())
+	}).collect::<Result<Vec<_>>>()?;
+
+	let mut output = Output::default()
+		.summary("experiment", experiment.name.clone())
+		.summary("directory", directory.display().to_string())
+		.columns(&["run", "files"]);
+	let mut manifest = Vec::new();
+
+	for ((name, arguments, _), result) in invocations.iter().zip(results) {
+		let files: Vec<String> = experiment.output.formats.iter().map(|&format| {
+			let file = format!("{name}.{}", extension(format));
+			fs::write(directory.join(&file), result.render(format, experiment.output.precision)).map(|_| file)
+		}).collect::<std::io::Result<_>>()?;
+
+		output.row(vec![name.clone().into(), files.join(" ").into()]);
+		manifest.push(json!({ "name": name, "command": arguments, "files": files }));
+	}
+
+	let manifest = json!({
+		"experiment": experiment.name,
+		"source": copy,
+		"nm": env!("CARGO_PKG_VERSION"),
+		"runs": manifest,
+	});
+	fs::write(directory.join("manifest.json"), serde_json::to_string_pretty(&manifest)? + "\n")?;
+
+	Ok(output)
+}
@@ -0,0 +1,100 @@
+use std::error::Error;
+use std::path::Path;
+use clap::Args;
+use nalgebra::{DMatrix, DVector};
+use numerics::expr::Expr;
+use numerics::interp::{random_nodes, uniform_nodes};
+use numerics::io;
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Parses a constant argument such as `-pi/4` or `1e-9`.
+pub fn number(source: &str) -> std::result::Result<f64, String> {
+	Expr::parse(source, &[]).map(|expr| expr.eval(&[])).map_err(|error| error.to_string())
+}
+
+/// Parses a function of `x`.
+pub fn function(source: &str) -> Result<impl Fn(f64) -> f64> {
+	let expr = Expr::parse(source, &["x"]).map_err(|error| format!("in '{source}': {error}"))?;
+	Ok(move |x| expr.eval(&[x]))
+}
+
+/// Parses a function of `x` and `y`.
+pub fn function_xy(source: &str) -> Result<impl Fn(f64, f64) -> f64> {
+	let expr = Expr::parse(source, &["x", "y"]).map_err(|error| format!("in '{source}': {error}"))?;
+	Ok(move |x, y| expr.eval(&[x, y]))
+}
+
+/// Reads `A` and `b` of a linear system from Matrix Market (`.mtx`) or CSV files.
+///
+/// Without `rhs` the matrix file is taken as the augmented matrix `[A | b]`.
+pub fn read_system(matrix: &Path, rhs: Option<&Path>) -> Result<(DMatrix<f64>, DVector<f64>)> {
+	io::read_system(matrix, rhs).map_err(|error| {
+		let path = match &error {
+			io::Error::WrongCount { .. } => rhs.unwrap_or(matrix),
+			_ => matrix,
+		};
+		format!("{}: {error}", path.display()).into()
+	})
+}
+
+/// Writes `vector` to `path`, as Matrix Market for `.mtx` and CSV otherwise.
+pub fn write_vector(path: &Path, vector: &DVector<f64>) -> Result<()> {
+	io::write_vector(path, vector).map_err(|error| format!("{}: {error}", path.display()).into())
+}
+
+/// Interpolation nodes, either read from a file or sampled from a function.
+#[derive(Args)]
+pub struct Nodes {
+	/// File with one `x y` pair per line.
+	#[arg(long, conflicts_with = "f")]
+	pub nodes: Option<std::path::PathBuf>,
+	/// Function to sample at `n` nodes on `[a, b]`, equally spaced unless --seed is given.
+	#[arg(long, requires_all = ["a", "b"])]
+	pub f: Option<String>,
+	#[arg(long, allow_hyphen_values = true, value_parser = number)]
+	pub a: Option<f64>,
+	#[arg(long, allow_hyphen_values = true, value_parser = number)]
+	pub b: Option<f64>,
+	#[arg(long, default_value_t = 5)]
+	pub n: usize,
+	/// Place the inner nodes at random, reproducibly for the same seed.
+	#[arg(long, requires = "f")]
+	pub seed: Option<u64>,
+	/// Points to evaluate at, separated by commas.
+	#[arg(long, value_delimiter = ',', allow_hyphen_values = true, value_parser = number, required = true)]
+	pub at: Vec<f64>,
+}
+
+impl Nodes {
+	pub fn points(&self) -> Result<Vec<[f64; 2]>> {
+		let points: Vec<[f64; 2]> = if let Some(path) = &self.nodes {
+			io::read_points(path).map_err(|error| format!("{}: {error}", path.display()))?
+		} else if let (Some(f), Some(a), Some(b)) = (&self.f, self.a, self.b) {
+			if self.n < 2 {
+				return Err("at least two nodes are needed".into());
+			}
+			let f = function(f)?;
+			match self.seed {
+				Some(seed) => random_nodes(f, a, b, self.n, seed),
+				None => uniform_nodes(f, a, b, self.n),
+			}
+		} else {
+			return Err("give either --nodes or --f with --a and --b".into());
+		};
+
+		if points.len() < 2 {
+			return Err("at least two nodes are needed".into());
+		}
+		if points.windows(2).any(|pair| pair[0][0] >= pair[1][0]) {
+			return Err("the nodes must be sorted by x without repetitions".into());
+		}
+
+		Ok(points)
+	}
+
+	/// The sampled function, to report the interpolation error.
+	pub fn original(&self) -> Result<Option<impl Fn(f64) -> f64>> {
+		self.f.as_deref().map(function).transpose()
+	}
+}
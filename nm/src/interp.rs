@@ -0,0 +1,69 @@
+use clap::Subcommand;
+use numerics::interp::{lagrange, nearest, newton, piecewise_lagrange};
+use crate::input::{Nodes, Result};
+use crate::output::Output;
+
+#[derive(Subcommand)]
+pub enum Command {
+	/// Lagrange polynomial through all nodes, or through the `nearest` ones.
+	Lagrange {
+		#[command(flatten)]
+		nodes: Nodes,
+		#[arg(long)]
+		nearest: Option<usize>,
+	},
+	/// Newton polynomial through all nodes.
+	Newton {
+		#[command(flatten)]
+		nodes: Nodes,
+	},
+	/// Piecewise Lagrange polynomials of the given degree.
+	Piecewise {
+		#[command(flatten)]
+		nodes: Nodes,
+		#[arg(long, default_value_t = 2)]
+		degree: usize,
+	},
+}
+
+pub fn run(command: &Command) -> Result<Output> {
+	let nodes = match command {
+		Command::Lagrange { nodes, .. } | Command::Newton { nodes } | Command::Piecewise { nodes, .. } => nodes,
+	};
+	let points = nodes.points()?;
+
+	let interpolant: Box<dyn Fn(f64) -> f64> = match command {
+		Command::Lagrange { nearest: Some(n), .. } => Box::new(move |x| lagrange(&nearest(&points, x, *n), x)),
+		Command::Lagrange { nearest: None, .. } => Box::new(move |x| lagrange(&points, x)),
+		Command::Newton { .. } => Box::new(move |x| newton(&points, x)),
+		Command::Piecewise { degree, .. } => {
+			if *degree == 0 || (points.len() - 1) % degree != 0 {
+				return Err(format!("{} intervals cannot be split into groups of {degree}", points.len() - 1).into());
+			}
+			Box::new(move |x| piecewise_lagrange(&points, x, degree + 1))
+		}
+	};
+
+	evaluate(nodes, interpolant)
+}
+
+/// Table of the interpolant at `--at`, with the error when the nodes come from `--f`.
+pub fn evaluate(nodes: &Nodes, interpolant: impl Fn(f64) -> f64) -> Result<Output> {
+	let original = nodes.original()?;
+
+	let mut output = match original {
+		Some(_) => Output::default().columns(&["x", "value", "f(x)", "error"]),
+		None => Output::default().columns(&["x", "value"]),
+	};
+
+	for &x in &nodes.at {
+		let value = interpolant(x);
+		let mut row = vec![x.into(), value.into()];
+		if let Some(f) = &original {
+			row.extend([f(x).into(), (value - f(x)).abs().into()]);
+		}
+		output.row(row);
+	}
+
+	Ok(output)
+}
@@ -0,0 +1,513 @@
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, Subcommand, ValueEnum};
+use nalgebra::DVector;
+use numerics::io;
+use numerics::linalg::{
+	bicgstab, conjugate_gradient, gauss, gmres, preconditioned_iteration, seidel_method, simple_iterations_method, sparse_jacobi,
+	sparse_sor, Cholesky, Csr, Diagnostics, Identity, Ilu0, IterativeSolution, Jacobi, LinearOperator, Lu, Pivoting,
+	Preconditioner, Qr, Solution, Ssor,
+};
+use crate::input::{read_system, write_vector, Result};
+use crate::output::Output;
+
+#[derive(Args)]
+pub struct System {
+	/// Matrix A, a Matrix Market file (.mtx) or one row per line; the augmented matrix [A | b] when --rhs is not given.
+	#[arg(long)]
+	matrix: PathBuf,
+	/// Right-hand side b, in either format.
+	#[arg(long)]
+	rhs: Option<PathBuf>,
+	#[arg(long, default_value_t = 0.5e-8)]
+	eps: f64,
+	/// Keep the matrix in the sparse CSR format, for large systems with few non-zero values.
+	#[arg(long)]
+	sparse: bool,
+	/// Iterations of the sparse and Krylov methods before they give up.
+	#[arg(long, default_value_t = 100_000)]
+	max_iterations: usize,
+	/// File to write the solution x to, as Matrix Market for .mtx and CSV otherwise.
+	#[arg(long)]
+	out: Option<PathBuf>,
+	/// File to write the residual b - Ax to.
+	#[arg(long)]
+	residual: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Method {
+	Jacobi,
+	Seidel,
+	Sor,
+	Cg,
+	Bicgstab,
+	Gmres,
+}
+
+/// A direct method for dense matrices.
+#[derive(Copy, Clone, ValueEnum)]
+pub enum DirectMethod {
+	/// Gaussian elimination (LU) with partial pivoting.
+	Partial,
+	/// Gaussian elimination (LU) with complete pivoting.
+	Complete,
+	/// Cholesky factorization, for symmetric positive definite matrices.
+	Cholesky,
+	/// Householder QR factorization.
+	Qr,
+}
+
+#[derive(Copy, Clone, PartialEq, ValueEnum)]
+pub enum Preconditioning {
+	None,
+	/// The diagonal of A.
+	Jacobi,
+	/// Symmetric relaxation with parameter --omega.
+	Ssor,
+	/// Incomplete LU factorization without fill-in.
+	Ilu0,
+}
+
+impl Preconditioning {
+	const ALL: [Preconditioning; 4] = [Preconditioning::None, Preconditioning::Jacobi, Preconditioning::Ssor, Preconditioning::Ilu0];
+
+	fn name(self) -> &'static str {
+		match self {
+			Preconditioning::None => "none",
+			Preconditioning::Jacobi => "jacobi",
+			Preconditioning::Ssor => "ssor",
+			Preconditioning::Ilu0 => "ilu0",
+		}
+	}
+
+	fn build(self, a: &Csr, omega: f64) -> Result<Box<dyn Preconditioner>> {
+		Ok(match self {
+			Preconditioning::None => Box::new(Identity),
+			Preconditioning::Jacobi => Box::new(Jacobi::new(a)?),
+			Preconditioning::Ssor => Box::new(Ssor::new(a, omega)?),
+			Preconditioning::Ilu0 => Box::new(Ilu0::new(a)?),
+		})
+	}
+}
+
+#[derive(Args)]
+pub struct KrylovOptions {
+	#[arg(long, value_enum, default_value_t = Preconditioning::None)]
+	preconditioner: Preconditioning,
+	/// Relaxation parameter of the SSOR preconditioner.
+	#[arg(long, default_value_t = 1.0)]
+	omega: f64,
+	/// Print the residual norm after every iteration instead of the solution.
+	#[arg(long)]
+	history: bool,
+}
+
+/// A Krylov method, which stops on the relative residual `‖b - Ax‖ / ‖b‖ ≤ eps`.
+#[derive(Copy, Clone)]
+enum Krylov {
+	ConjugateGradient,
+	Bicgstab,
+	Gmres { restart: usize },
+}
+
+impl Krylov {
+	fn name(self) -> &'static str {
+		match self {
+			Krylov::ConjugateGradient => "cg",
+			Krylov::Bicgstab => "bicgstab",
+			Krylov::Gmres { .. } => "gmres",
+		}
+	}
+
+	fn solve(
+		self,
+		a: &impl LinearOperator,
+		preconditioner: &impl Preconditioner,
+		b: &DVector<f64>,
+		eps: f64,
+		max_iterations: usize,
+	) -> Result<IterativeSolution> {
+		let b = b.as_slice();
+		Ok(match self {
+			Krylov::ConjugateGradient => conjugate_gradient(a, preconditioner, b, eps, max_iterations)?,
+			Krylov::Bicgstab => bicgstab(a, preconditioner, b, eps, max_iterations)?,
+			Krylov::Gmres { restart } => gmres(a, preconditioner, b, restart, eps, max_iterations)?,
+		})
+	}
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Generated {
+	/// The five-point Laplacian, symmetric positive definite.
+	Poisson,
+	/// The five-point scheme of -Δu + c ∂u/∂x, not symmetric.
+	ConvectionDiffusion,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+	/// Simple (Jacobi) iterations.
+	Jacobi {
+		#[command(flatten)]
+		system: System,
+	},
+	/// Seidel method.
+	Seidel {
+		#[command(flatten)]
+		system: System,
+	},
+	/// Relaxation method with parameter omega.
+	Sor {
+		#[command(flatten)]
+		system: System,
+		#[arg(long, default_value_t = 1.5)]
+		omega: f64,
+	},
+	/// Conjugate gradients, for symmetric positive definite matrices.
+	Cg {
+		#[command(flatten)]
+		system: System,
+		#[command(flatten)]
+		options: KrylovOptions,
+	},
+	/// Stabilized biconjugate gradients.
+	Bicgstab {
+		#[command(flatten)]
+		system: System,
+		#[command(flatten)]
+		options: KrylovOptions,
+	},
+	/// GMRES restarted every --restart iterations.
+	Gmres {
+		#[command(flatten)]
+		system: System,
+		#[arg(long, default_value_t = 30, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+		restart: usize,
+		#[command(flatten)]
+		options: KrylovOptions,
+	},
+	/// A direct method for dense systems, with the determinant and an estimate of the condition number.
+	Direct {
+		/// Matrix A as for the other methods.
+		#[arg(long)]
+		matrix: PathBuf,
+		#[arg(long)]
+		rhs: Option<PathBuf>,
+		#[arg(long, value_enum, default_value_t = DirectMethod::Partial)]
+		method: DirectMethod,
+		/// File to write the solution x to, as Matrix Market for .mtx and CSV otherwise.
+		#[arg(long)]
+		out: Option<PathBuf>,
+	},
+	/// Iterations of every method with and without preconditioning, on a system or a generated test matrix.
+	///
+	/// The stationary iteration x += M^-1 (b - Ax) is listed with each preconditioner, and conjugate
+	/// gradients only for symmetric matrices. All methods stop on the relative residual.
+	Compare {
+		/// Matrix A as for the other methods; a generated matrix with b = 1 when not given.
+		#[arg(long)]
+		matrix: Option<PathBuf>,
+		#[arg(long, requires = "matrix")]
+		rhs: Option<PathBuf>,
+		#[arg(long, value_enum, default_value_t = Generated::Poisson, conflicts_with = "matrix")]
+		generate: Generated,
+		/// Inner nodes along each side of the grid of the generated matrix.
+		#[arg(long, default_value_t = 32, conflicts_with = "matrix")]
+		m: usize,
+		/// Cell Péclet number c h / 2 of the convection-diffusion matrix.
+		#[arg(long, default_value_t = 0.5, allow_hyphen_values = true, conflicts_with = "matrix")]
+		peclet: f64,
+		#[arg(long, default_value_t = 1e-8)]
+		eps: f64,
+		#[arg(long, default_value_t = 10_000)]
+		max_iterations: usize,
+		/// Relaxation parameter of the SSOR preconditioner.
+		#[arg(long, default_value_t = 1.0)]
+		omega: f64,
+		#[arg(long, default_value_t = 30, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+		restart: usize,
+	},
+	/// The sparse system of -Δu = 1 on the unit square with u = 0 on the boundary, on m × m inner nodes.
+	Poisson {
+		#[arg(long, default_value_t = 100)]
+		m: usize,
+		#[arg(long, value_enum, default_value_t = Method::Sor)]
+		method: Method,
+		/// Relaxation parameter, by default the optimal 2 / (1 + sin(πh)).
+		#[arg(long)]
+		omega: Option<f64>,
+		/// Iterations between the restarts of GMRES.
+		#[arg(long, default_value_t = 30, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+		restart: usize,
+		/// Preconditioner of the Krylov methods, SSOR with --omega or 1.
+		#[arg(long, value_enum, default_value_t = Preconditioning::None)]
+		preconditioner: Preconditioning,
+		#[arg(long, default_value_t = 1e-8)]
+		eps: f64,
+		#[arg(long, default_value_t = 100_000)]
+		max_iterations: usize,
+	},
+}
+
+/// The sparse method, with `omega = None` for Jacobi iterations.
+fn solve_sparse(a: &Csr, b: &DVector<f64>, eps: f64, max_iterations: usize, omega: Option<f64>) -> Result<Solution> {
+	Ok(match omega {
+		None => sparse_jacobi(a, b.as_slice(), eps, max_iterations)?,
+		Some(omega) => sparse_sor(a, b.as_slice(), eps, max_iterations, omega)?,
+	})
+}
+
+pub fn run(command: &Command) -> Result<Output> {
+	let (system, omega) = match command {
+		Command::Jacobi { system } => (system, None),
+		Command::Seidel { system } => (system, Some(1.0)),
+		Command::Sor { system, omega } => (system, Some(*omega)),
+		Command::Cg { system, options } => return krylov(system, Krylov::ConjugateGradient, options),
+		Command::Bicgstab { system, options } => return krylov(system, Krylov::Bicgstab, options),
+		Command::Gmres { system, restart, options } => return krylov(system, Krylov::Gmres { restart: *restart }, options),
+		Command::Direct { matrix, rhs, method, out } => return direct(matrix, rhs.as_deref(), *method, out.as_deref()),
+		Command::Compare { matrix, rhs, generate, m, peclet, eps, max_iterations, omega, restart } => {
+			let (a, b, name) = match matrix {
+				Some(matrix) => {
+					let (a, b) = io::read_sparse_system(matrix, rhs.as_deref()).map_err(|error| format!("{}: {error}", matrix.display()))?;
+					(a, b, matrix.display().to_string())
+				}
+				None => {
+					let a = match generate {
+						Generated::Poisson => Csr::poisson_2d(*m),
+						Generated::ConvectionDiffusion => Csr::convection_diffusion_2d(*m, *peclet),
+					};
+					let b = DVector::from_element(a.nrows(), 1.0);
+					(a, b, generate.to_possible_value().unwrap().get_name().to_string())
+				}
+			};
+			return compare(&a, &b, name, *eps, *max_iterations, *omega, *restart);
+		}
+		Command::Poisson { m, method, omega, restart, preconditioner, eps, max_iterations } => {
+			return poisson(*m, *method, *omega, *restart, *preconditioner, *eps, *max_iterations);
+		}
+	};
+
+	let mut output = Output::default();
+	let mut direct = None;
+	let (solution, residual) = if system.sparse {
+		let (a, b) = io::read_sparse_system(&system.matrix, system.rhs.as_deref())
+			.map_err(|error| format!("{}: {error}", system.matrix.display()))?;
+		let solution = solve_sparse(&a, &b, system.eps, system.max_iterations, omega)?;
+		let residual = DVector::from_vec(a.residual(solution.x.as_slice(), b.as_slice()));
+		(solution, residual)
+	} else {
+		let (a, b) = read_system(&system.matrix, system.rhs.as_deref())?;
+		let diagnostics = Diagnostics::new(&a)?;
+		for warning in diagnostics.warnings() {
+			eprintln!("warning: {warning}");
+		}
+		output = diagnostics_rows(output, &diagnostics);
+
+		let solution = match omega {
+			None => simple_iterations_method(&a, &b, system.eps)?,
+			Some(omega) => seidel_method(&a, &b, system.eps, omega)?,
+		};
+		let residual = &b - &a * &solution.x;
+		direct = gauss(&a, &b, Pivoting::Partial).ok();
+		(solution, residual)
+	};
+
+	write_results(system, &solution.x, &residual)?;
+
+	let mut output = output
+		.summary("iterations", solution.iterations)
+		.summary("error", solution.error)
+		.summary("residual norm", residual.norm());
+	if let Some(direct) = direct {
+		output = output.summary("distance to lu", (&solution.x - direct).norm());
+	}
+	Ok(solution_rows(output, &solution.x))
+}
+
+/// Norms and spectral radius of the Jacobi iteration matrix of a dense system.
+fn diagnostics_rows(output: Output, diagnostics: &Diagnostics) -> Output {
+	output
+		.summary("norm inf", diagnostics.norm_inf)
+		.summary("norm 1", diagnostics.norm_1)
+		.summary("norm 2", diagnostics.norm_2)
+		.summary("frobenius norm", diagnostics.frobenius)
+		.summary("spectral radius", diagnostics.spectral_radius)
+		.summary("diagonal dominance", format!("{:?}", diagnostics.dominance).to_lowercase())
+		.summary("convergence guaranteed", diagnostics.is_guaranteed())
+}
+
+fn direct(matrix: &Path, rhs: Option<&Path>, method: DirectMethod, out: Option<&Path>) -> Result<Output> {
+	let (a, b) = read_system(matrix, rhs)?;
+	let (x, determinant, condition) = match method {
+		DirectMethod::Partial | DirectMethod::Complete => {
+			let pivoting = if matches!(method, DirectMethod::Partial) { Pivoting::Partial } else { Pivoting::Complete };
+			let lu = Lu::new(&a, pivoting)?;
+			(lu.solve(&b)?, lu.determinant(), lu.condition())
+		}
+		DirectMethod::Cholesky => {
+			let cholesky = Cholesky::new(&a)?;
+			(cholesky.solve(&b)?, cholesky.determinant(), cholesky.condition())
+		}
+		DirectMethod::Qr => {
+			let qr = Qr::new(&a)?;
+			(qr.solve(&b)?, qr.determinant(), qr.condition())
+		}
+	};
+	if let Some(path) = out {
+		write_vector(path, &x)?;
+	}
+
+	let output = Output::default()
+		.summary("determinant", determinant)
+		.summary("condition number", condition)
+		.summary("residual norm", (&b - &a * &x).norm());
+	Ok(solution_rows(output, &x))
+}
+
+fn write_results(system: &System, x: &DVector<f64>, residual: &DVector<f64>) -> Result<()> {
+	if let Some(path) = &system.out {
+		write_vector(path, x)?;
+	}
+	if let Some(path) = &system.residual {
+		write_vector(path, residual)?;
+	}
+	Ok(())
+}
+
+fn solution_rows(output: Output, x: &DVector<f64>) -> Output {
+	let mut output = output.columns(&["i", "x"]);
+	for (index, x) in x.iter().enumerate() {
+		output.row(vec![(index + 1).into(), (*x).into()]);
+	}
+	output
+}
+
+fn krylov(system: &System, method: Krylov, options: &KrylovOptions) -> Result<Output> {
+	let (solution, residual) = if system.sparse {
+		let (a, b) = io::read_sparse_system(&system.matrix, system.rhs.as_deref())
+			.map_err(|error| format!("{}: {error}", system.matrix.display()))?;
+		let preconditioner = options.preconditioner.build(&a, options.omega)?;
+		let solution = method.solve(&a, &preconditioner, &b, system.eps, system.max_iterations)?;
+		let residual = DVector::from_vec(a.residual(solution.x.as_slice(), b.as_slice()));
+		(solution, residual)
+	} else {
+		let (a, b) = read_system(&system.matrix, system.rhs.as_deref())?;
+		let preconditioner = options.preconditioner.build(&Csr::from_dense(&a), options.omega)?;
+		let solution = method.solve(&a, &preconditioner, &b, system.eps, system.max_iterations)?;
+		let residual = &b - &a * &solution.x;
+		(solution, residual)
+	};
+	write_results(system, &solution.x, &residual)?;
+
+	let output = Output::default()
+		.summary("iterations", solution.iterations)
+		.summary("stop", solution.stop.to_string())
+		.summary("residual norm", residual.norm());
+	if !options.history {
+		return Ok(solution_rows(output, &solution.x));
+	}
+
+	let mut output = output.columns(&["iteration", "residual"]);
+	for (iteration, residual) in solution.residuals.iter().enumerate() {
+		output.row(vec![iteration.into(), (*residual).into()]);
+	}
+	Ok(output)
+}
+
+/// Solves the model problem without printing its `m²` unknowns.
+///
+/// The stationary methods stop on the change of the approximation, the Krylov ones on the relative residual.
+fn poisson(
+	m: usize,
+	method: Method,
+	omega: Option<f64>,
+	restart: usize,
+	preconditioner: Preconditioning,
+	eps: f64,
+	max_iterations: usize,
+) -> Result<Output> {
+	if m == 0 {
+		return Err("the grid needs at least one inner node".into());
+	}
+	let h = 1.0 / (m + 1) as f64;
+	let a = Csr::poisson_2d(m);
+	let b = DVector::from_element(m * m, h * h);
+	let output = Output::default()
+		.summary("unknowns", a.nrows())
+		.summary("non-zero values", a.nnz());
+
+	let krylov = match method {
+		Method::Cg => Some(Krylov::ConjugateGradient),
+		Method::Bicgstab => Some(Krylov::Bicgstab),
+		Method::Gmres => Some(Krylov::Gmres { restart }),
+		Method::Jacobi | Method::Seidel | Method::Sor => None,
+	};
+	if let Some(krylov) = krylov {
+		let preconditioner = preconditioner.build(&a, omega.unwrap_or(1.0))?;
+		let solution = krylov.solve(&a, &preconditioner, &b, eps, max_iterations)?;
+		return Ok(output
+			.summary("iterations", solution.iterations)
+			.summary("stop", solution.stop.to_string())
+			.summary("residual norm", solution.residual())
+			.summary("max u", solution.x.max()));
+	}
+
+	let omega = match method {
+		Method::Seidel => Some(1.0),
+		Method::Sor => Some(omega.unwrap_or(2.0 / (1.0 + (PI * h).sin()))),
+		_ => None,
+	};
+	let solution = solve_sparse(&a, &b, eps, max_iterations, omega)?;
+	let residual = DVector::from_vec(a.residual(solution.x.as_slice(), b.as_slice()));
+
+	let mut output = output;
+	if let Some(omega) = omega {
+		output = output.summary("omega", omega);
+	}
+	Ok(output
+		.summary("iterations", solution.iterations)
+		.summary("converged", solution.error <= eps)
+		.summary("error", solution.error)
+		.summary("residual norm", residual.norm())
+		.summary("max u", solution.x.max()))
+}
+
+/// The table of `linsys compare`.
+fn compare(a: &Csr, b: &DVector<f64>, name: String, eps: f64, max_iterations: usize, omega: f64, restart: usize) -> Result<Output> {
+	let symmetric = a.is_symmetric();
+	let mut output = Output::default()
+		.summary("matrix", name)
+		.summary("unknowns", a.nrows())
+		.summary("non-zero values", a.nnz())
+		.summary("symmetric", symmetric)
+		.columns(&["method", "preconditioner", "iterations", "stop", "relative residual"]);
+
+	let mut methods = vec![None];
+	if symmetric {
+		methods.push(Some(Krylov::ConjugateGradient));
+	}
+	methods.extend([Some(Krylov::Bicgstab), Some(Krylov::Gmres { restart })]);
+
+	for method in methods {
+		for preconditioning in Preconditioning::ALL {
+			let preconditioner = preconditioning.build(a, omega)?;
+			let solution = match method {
+				// without a preconditioner the stationary iteration is x += b - Ax, which rarely converges
+				None if preconditioning == Preconditioning::None => continue,
+				None => preconditioned_iteration(a, &preconditioner, b.as_slice(), eps, max_iterations)?,
+				Some(method) => method.solve(a, &preconditioner, b, eps, max_iterations)?,
+			};
+			output.row(vec![
+				method.map_or("stationary", Krylov::name).into(),
+				preconditioning.name().into(),
+				solution.iterations.into(),
+				solution.stop.to_string().into(),
+				(solution.residual() / b.norm()).into(),
+			]);
+		}
+	}
+	Ok(output)
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use clap::{Parser, Subcommand};
+use crate::output::{Format, Output};
+
+mod experiment;
+mod input;
+mod interp;
+mod linsys;
+mod ode;
+mod output;
+mod quad;
+mod roots;
+mod spline;
+
+/// Numerical methods of the course from the command line.
+///
+/// Functions are given as expressions such as "(x - 1)^3 + 0.5 * exp(x)";
+/// the right-hand side of an ODE may use both x and y.
+#[derive(Parser)]
+#[command(name = "nm")]
+struct Cli {
+	#[arg(long, value_enum, default_value_t, global = true)]
+	format: Format,
+	/// Digits after the point in the markdown and latex formats.
+	#[arg(long, default_value_t = 6, global = true)]
+	precision: usize,
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Roots of f(x) = 0.
+	#[command(subcommand)]
+	Roots(roots::Command),
+	/// Linear systems Ax = b.
+	#[command(subcommand)]
+	Linsys(linsys::Command),
+	/// Interpolation polynomials.
+	#[command(subcommand)]
+	Interp(interp::Command),
+	/// Splines.
+	#[command(subcommand)]
+	Spline(spline::Command),
+	/// The Cauchy problem y' = f(x, y), y(x0) = y0.
+	#[command(subcommand)]
+	Ode(ode::Command),
+	/// Definite integrals.
+	#[command(subcommand)]
+	Quad(quad::Command),
+	/// Runs an experiment file (TOML or JSON) and writes a bundle with the results.
+	Run {
+		file: PathBuf,
+		/// Directory of the bundle, instead of the one given in the experiment.
+		#[arg(long)]
+		out: Option<PathBuf>,
+	},
+}
+
+fn run(command: &Command) -> input::Result<Output> {
+	match command {
+		Command::Roots(command) => roots::run(command),
+		Command::Linsys(command) => linsys::run(command),
+		Command::Interp(command) => interp::run(command),
+		Command::Spline(command) => spline::run(command),
+		Command::Ode(command) => ode::run(command),
+		Command::Quad(command) => quad::run(command),
+		Command::Run { file, out } => experiment::run(file, out.as_deref()),
+	}
+}
+
+fn main() -> ExitCode {
+	let cli = Cli::parse();
+
+	match run(&cli.command) {
+		Ok(output) => {
+			print!("{}", output.render(cli.format, cli.precision));
+			ExitCode::SUCCESS
+		}
+		Err(error) => {
+			eprintln!("error: {error}");
+			ExitCode::FAILURE
+		}
+	}
+}
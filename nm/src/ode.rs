@@ -0,0 +1,71 @@
+use clap::{Args, Subcommand};
+use numerics::ode::{adams, euler, runge_kutta};
+use crate::input::{function_xy, number, Result};
+use crate::output::Output;
+
+#[derive(Args)]
+pub struct Problem {
+	/// Right-hand side f(x, y) of y' = f(x, y).
+	#[arg(long)]
+	f: String,
+	#[arg(long, allow_hyphen_values = true, value_parser = number)]
+	x0: f64,
+	#[arg(long, allow_hyphen_values = true, value_parser = number)]
+	y0: f64,
+	/// The integration continues while x <= right.
+	#[arg(long, allow_hyphen_values = true, value_parser = number)]
+	right: f64,
+	#[arg(long)]
+	h: f64,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+	/// Explicit Euler method.
+	Euler {
+		#[command(flatten)]
+		problem: Problem,
+	},
+	/// Runge–Kutta method of the section 4 exercise.
+	#[command(alias = "rk4")]
+	RungeKutta {
+		#[command(flatten)]
+		problem: Problem,
+	},
+	/// Four-step Adams method started with Runge–Kutta.
+	Adams {
+		#[command(flatten)]
+		problem: Problem,
+	},
+}
+
+pub fn run(command: &Command) -> Result<Output> {
+	let problem = match command {
+		Command::Euler { problem } | Command::RungeKutta { problem } | Command::Adams { problem } => problem,
+	};
+	if problem.h <= 0.0 {
+		return Err("the step h must be positive".into());
+	}
+
+	let f = function_xy(&problem.f)?;
+
+	let solution = match command {
+		Command::Euler { .. } => euler(&f, problem.x0, problem.y0, problem.right, problem.h),
+		_ => runge_kutta(&f, problem.x0, problem.y0, problem.right, problem.h),
+	};
+
+	if let Command::Adams { .. } = command {
+		let mut output = Output::default().columns(&["x", "y"]);
+		for [x, y] in adams(&f, &solution.points[..4], problem.right, problem.h) {
+			output.row(vec![x.into(), y.into()]);
+		}
+		return Ok(output);
+	}
+
+	let mut output = Output::default().columns(&["x", "y", "runge error"]);
+	output.row(vec![problem.x0.into(), problem.y0.into(), 0.0.into()]);
+	for ([x, y], error) in solution.points[1..].iter().zip(&solution.errors) {
+		output.row(vec![(*x).into(), (*y).into(), (*error).into()]);
+	}
+	Ok(output)
+}
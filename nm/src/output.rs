@@ -0,0 +1,165 @@
+use clap::ValueEnum;
+use numerics::report::{self, Cell, Table};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+#[derive(Copy, Clone, Debug, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+	/// Aligned columns for reading in a terminal.
+	#[default]
+	Table,
+	Json,
+	Csv,
+	/// Markdown tables for reports, with numbers rounded to --precision digits.
+	Markdown,
+	/// LaTeX tabulars for reports, with numbers rounded to --precision digits.
+	Latex,
+}
+
+/// Result of a subcommand: a few scalar results and an optional table of steps.
+#[derive(Default)]
+pub struct Output {
+	pub summary: Vec<(String, Value)>,
+	pub columns: Vec<String>,
+	pub rows: Vec<Vec<Value>>,
+}
+
+impl Output {
+	pub fn summary(mut self, name: &str, value: impl Into<Value>) -> Self {
+		self.summary.push((name.to_string(), value.into()));
+		self
+	}
+
+	pub fn columns(mut self, columns: &[&str]) -> Self {
+		self.columns = columns.iter().map(|column| column.to_string()).collect();
+		self
+	}
+
+	pub fn row(&mut self, row: Vec<Value>) {
+		self.rows.push(row);
+	}
+
+	/// `precision` is only used by the report formats; the others print every digit.
+	pub fn render(&self, format: Format, precision: usize) -> String {
+		match format {
+			Format::Table => self.render_table(),
+			Format::Json => self.render_json(),
+			Format::Csv => self.render_csv(),
+			Format::Markdown => self.render_report(report::Format::Markdown, precision),
+			Format::Latex => self.render_report(report::Format::Latex, precision),
+		}
+	}
+
+	fn render_table(&self) -> String {
+		let mut result = String::new();
+
+		let width = self.summary.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+		for (name, value) in &self.summary {
+			result += &format!("{name:<width$}  {}\n", text(value));
+		}
+
+		if self.columns.is_empty() {
+			return result;
+		}
+
+		if !result.is_empty() {
+			result += "\n";
+		}
+
+		let cells: Vec<Vec<String>> = self.rows.iter().map(|row| row.iter().map(text).collect()).collect();
+		let widths: Vec<usize> = self.columns.iter().enumerate().map(|(index, column)| {
+			cells.iter().map(|row| row[index].len()).chain([column.len()]).max().unwrap()
+		}).collect();
+
+		let line = |row: Vec<&str>| {
+			let row: Vec<_> = row.iter().zip(&widths).map(|(cell, &width)| format!("{cell:>width$}")).collect();
+			row.join("  ") + "\n"
+		};
+
+		result += &line(self.columns.iter().map(String::as_str).collect());
+		for row in &cells {
+			result += &line(row.iter().map(String::as_str).collect());
+		}
+
+		result
+	}
+
+	fn render_json(&self) -> String {
+		let summary: Map<_, _> = self.summary.iter().cloned().collect();
+		let rows: Vec<Value> = self.rows.iter().map(|row| {
+			Value::Object(self.columns.iter().cloned().zip(row.iter().cloned()).collect())
+		}).collect();
+
+		let mut object = Map::new();
+		object.insert("summary".to_string(), Value::Object(summary));
+		if !self.columns.is_empty() {
+			object.insert("rows".to_string(), Value::Array(rows));
+		}
+
+		serde_json::to_string_pretty(&Value::Object(object)).unwrap() + "\n"
+	}
+
+	/// The table of steps, or the summary as a single row when there is no table.
+	fn render_csv(&self) -> String {
+		let (columns, rows) = if self.columns.is_empty() {
+			let (columns, row) = self.summary.iter().cloned().unzip();
+			(columns, vec![row])
+		} else {
+			(self.columns.clone(), self.rows.clone())
+		};
+
+		let columns: Vec<_> = columns.iter().map(|column| report::escape(column, report::Format::Csv)).collect();
+		let mut result = columns.join(",") + "\n";
+		for row in rows {
+			let row: Vec<_> = row.iter().map(|value| report::escape(&text(value), report::Format::Csv)).collect();
+			result += &(row.join(",") + "\n");
+		}
+
+		result
+	}
+
+	/// The summary as a table of names and values, followed by the table of steps.
+	fn render_report(&self, format: report::Format, precision: usize) -> String {
+		let mut tables = Vec::new();
+
+		if !self.summary.is_empty() {
+			let mut summary = Table::new("", &["name", "value"]);
+			for (name, value) in &self.summary {
+				summary.row(vec![name.as_str().into(), cell(value)]);
+			}
+			tables.push(summary);
+		}
+
+		if !self.columns.is_empty() {
+			let columns: Vec<_> = self.columns.iter().map(String::as_str).collect();
+			let mut steps = Table::new("", &columns);
+			for row in &self.rows {
+				steps.row(row.iter().map(cell).collect());
+			}
+			tables.push(steps);
+		}
+
+		tables.iter().map(|table| table.render(format, precision)).collect::<Vec<_>>().join("\n")
+	}
+}
+
+/// Strings without the JSON quotes, anything else as JSON.
+fn text(value: &Value) -> String {
+	match value {
+		Value::String(text) => text.clone(),
+		value => value.to_string(),
+	}
+}
+
+fn cell(value: &Value) -> Cell {
+	match value {
+		Value::Number(number) => match number.as_i64() {
+			Some(integer) => Cell::Integer(integer),
+			None => number.as_f64().into(),
+		},
+		Value::String(text) => text.as_str().into(),
+		Value::Null => Cell::Empty,
+		value => value.to_string().into(),
+	}
+}
@@ -0,0 +1,68 @@
+use clap::{Args, Subcommand};
+use numerics::quad::{automatic_step, gauss_6, simpson, trapezoid};
+use crate::input::{function, number, Result};
+use crate::output::Output;
+
+#[derive(Args)]
+pub struct Integral {
+	#[arg(long)]
+	f: String,
+	#[arg(long, allow_hyphen_values = true, value_parser = number)]
+	a: f64,
+	#[arg(long, allow_hyphen_values = true, value_parser = number)]
+	b: f64,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+	/// Trapezoid rule with automatic step selection.
+	Trapezoid {
+		#[command(flatten)]
+		integral: Integral,
+		#[arg(long, default_value_t = 1e-5)]
+		eps: f64,
+		/// Times the number of intervals may be doubled before giving up.
+		#[arg(long, default_value_t = 20)]
+		max_halvings: u32,
+	},
+	/// Simpson rule with automatic step selection.
+	Simpson {
+		#[command(flatten)]
+		integral: Integral,
+		#[arg(long, default_value_t = 1e-5)]
+		eps: f64,
+		/// Times the number of intervals may be doubled before giving up.
+		#[arg(long, default_value_t = 20)]
+		max_halvings: u32,
+	},
+	/// Gauss formula with six nodes.
+	Gauss6 {
+		#[command(flatten)]
+		integral: Integral,
+	},
+}
+
+pub fn run(command: &Command) -> Result<Output> {
+	let (integral, p, eps, max_halvings) = match command {
+		Command::Trapezoid { integral, eps, max_halvings } => (integral, 2, *eps, *max_halvings),
+		Command::Simpson { integral, eps, max_halvings } => (integral, 4, *eps, *max_halvings),
+		Command::Gauss6 { integral } => {
+			let f = function(&integral.f)?;
+			return Ok(Output::default().summary("value", gauss_6(f, integral.a, integral.b)));
+		}
+	};
+
+	let f = function(&integral.f)?;
+	let (a, b) = (integral.a, integral.b);
+	let solution = if p == 2 {
+		automatic_step(|n| trapezoid(&f, a, b, n), p, eps, max_halvings)?
+	} else {
+		automatic_step(|n| simpson(&f, a, b, n), p, eps, max_halvings)?
+	};
+
+	Ok(Output::default()
+		.summary("value", solution.value)
+		.summary("richardson", solution.refined)
+		.summary("intervals", solution.intervals)
+		.summary("error", solution.error))
+}
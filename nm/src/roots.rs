@@ -0,0 +1,100 @@
+use clap::Subcommand;
+use numerics::roots::{find_solution_newton_secant, find_solution_simple_iterations, find_solution_split, Point};
+use crate::input::{function, number, Result};
+use crate::output::Output;
+
+#[derive(Subcommand)]
+pub enum Command {
+	/// Simple iterations x = phi(x).
+	Simple {
+		#[arg(long)]
+		phi: String,
+		/// Function to report along the path; `x - phi(x)` by default.
+		#[arg(long)]
+		f: Option<String>,
+		#[arg(long, allow_hyphen_values = true, value_parser = number)]
+		x0: f64,
+		#[arg(long, default_value_t = 1e-7)]
+		eps: f64,
+		/// Iterations before the method gives up.
+		#[arg(long, default_value_t = 10_000)]
+		max_iterations: usize,
+	},
+	/// Bisection of [a, b], where f(a) and f(b) have different signs.
+	Bisect {
+		#[arg(long)]
+		f: String,
+		#[arg(long, allow_hyphen_values = true, value_parser = number)]
+		a: f64,
+		#[arg(long, allow_hyphen_values = true, value_parser = number)]
+		b: f64,
+		#[arg(long, default_value_t = 1e-9)]
+		eps: f64,
+		/// Iterations before the method gives up.
+		#[arg(long, default_value_t = 10_000)]
+		max_iterations: usize,
+	},
+	/// Secant method started from x0 and x1.
+	Secant {
+		#[arg(long)]
+		f: String,
+		#[arg(long, allow_hyphen_values = true, value_parser = number)]
+		x0: f64,
+		#[arg(long, allow_hyphen_values = true, value_parser = number)]
+		x1: f64,
+		#[arg(long, default_value_t = 1e-9)]
+		eps: f64,
+		/// Iterations before the method gives up.
+		#[arg(long, default_value_t = 10_000)]
+		max_iterations: usize,
+	},
+}
+
+pub fn run(command: &Command) -> Result<Output> {
+	match command {
+		Command::Simple { phi, f, x0, eps, max_iterations } => {
+			let phi = function(phi)?;
+			let f: Box<dyn Fn(f64) -> f64> = match f {
+				Some(f) => Box::new(function(f)?),
+				None => Box::new(|x| x - phi(x)),
+			};
+
+			let solution = find_solution_simple_iterations(&phi, &f, Point::new(*x0, &f), *eps, *max_iterations)?;
+
+			let mut output = Output::default()
+				.summary("x", solution.current.x)
+				.summary("f(x)", solution.current.y)
+				.summary("iterations", solution.iterations)
+				.columns(&["iteration", "x", "f(x)"]);
+			for (index, point) in solution.path.iter().enumerate() {
+				output.row(vec![(index + 1).into(), point.x.into(), point.y.into()]);
+			}
+			Ok(output)
+		}
+		Command::Bisect { f, a, b, eps, max_iterations } => {
+			let f = function(f)?;
+			let left = Point::new(*a, &f);
+			let right = Point::new(*b, &f);
+			if left.y.is_sign_positive() == right.y.is_sign_positive() {
+				return Err("f(a) and f(b) must have different signs".into());
+			}
+
+			let solution = find_solution_split(&f, left, right, *eps, *max_iterations)?;
+
+			Ok(Output::default()
+				.summary("x", solution.current.x)
+				.summary("f(x)", solution.current.y)
+				.summary("other end", solution.previous.x)
+				.summary("iterations", solution.iterations))
+		}
+		Command::Secant { f, x0, x1, eps, max_iterations } => {
+			let f = function(f)?;
+			let solution = find_solution_newton_secant(&f, Point::new(*x1, &f), Point::new(*x0, &f), *eps, *max_iterations)?;
+
+			Ok(Output::default()
+				.summary("x", solution.current.x)
+				.summary("f(x)", solution.current.y)
+				.summary("iterations", solution.iterations))
+		}
+	}
+}
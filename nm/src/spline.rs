@@ -0,0 +1,110 @@
+use clap::{Subcommand, ValueEnum};
+use numerics::spline::{cubic_spline_coefficients, parabolic_spline_coefficients, piecewise_polynomial, BSpline, Boundary, HermiteSpline};
+use crate::input::{number, Nodes, Result};
+use crate::interp::evaluate;
+use crate::output::Output;
+
+#[derive(Subcommand)]
+pub enum Command {
+	/// Parabolic spline with the given derivative at the first node.
+	Parabolic {
+		#[command(flatten)]
+		nodes: Nodes,
+		#[arg(long, default_value_t = 0.0, allow_hyphen_values = true, value_parser = number)]
+		first_derivative: f64,
+	},
+	/// Natural cubic spline.
+	Cubic {
+		#[command(flatten)]
+		nodes: Nodes,
+	},
+	/// Cubic spline in Hermite form with its derivatives, integral, roots and extrema.
+	Hermite {
+		#[command(flatten)]
+		nodes: Nodes,
+		#[arg(long, value_enum, default_value_t = Ends::Natural)]
+		boundary: Ends,
+		/// Slopes at the ends for --boundary clamped, estimated from the data when not given.
+		#[arg(long, value_delimiter = ',', allow_hyphen_values = true, value_parser = number)]
+		slopes: Option<Vec<f64>>,
+	},
+	/// Interpolating spline of any degree in the B-spline basis.
+	Bspline {
+		#[command(flatten)]
+		nodes: Nodes,
+		#[arg(long, default_value_t = 3)]
+		degree: usize,
+	},
+}
+
+/// End conditions of the Hermite spline, see [`Boundary`].
+#[derive(Copy, Clone, ValueEnum)]
+pub enum Ends {
+	Natural,
+	Clamped,
+	NotAKnot,
+	Periodic,
+}
+
+pub fn run(command: &Command) -> Result<Output> {
+	match command {
+		Command::Parabolic { nodes, first_derivative } => {
+			let points = nodes.points()?;
+			let coefficients = parabolic_spline_coefficients(&points, *first_derivative)?;
+			evaluate(nodes, |x| piecewise_polynomial(&points, &coefficients, 2, x))
+		}
+		Command::Cubic { nodes } => {
+			let points = nodes.points()?;
+			let coefficients = cubic_spline_coefficients(&points)?;
+			evaluate(nodes, |x| piecewise_polynomial(&points, &coefficients, 3, x))
+		}
+		Command::Hermite { nodes, boundary, slopes } => hermite(nodes, *boundary, slopes.as_deref()),
+		Command::Bspline { nodes, degree } => {
+			let points = nodes.points()?;
+			let x: Vec<f64> = points.iter().map(|point| point[0]).collect();
+			let y: Vec<f64> = points.iter().map(|point| point[1]).collect();
+			let spline = BSpline::interpolating(&x, &y, *degree)?;
+			evaluate(nodes, |x| spline.solve(x))
+		}
+	}
+}
+
+fn hermite(nodes: &Nodes, boundary: Ends, slopes: Option<&[f64]>) -> Result<Output> {
+	let points = nodes.points()?;
+	let x: Vec<f64> = points.iter().map(|point| point[0]).collect();
+	let y: Vec<f64> = points.iter().map(|point| point[1]).collect();
+
+	let boundary = match boundary {
+		Ends::Natural => Boundary::Natural,
+		Ends::Clamped => {
+			let [start, end] = match slopes {
+				Some(&[start, end]) => [start, end],
+				Some(slopes) => return Err(format!("--slopes needs two values, found {}", slopes.len()).into()),
+				None => HermiteSpline::estimated_end_slopes(&x, &y),
+			};
+			Boundary::Clamped(start, end)
+		}
+		Ends::NotAKnot => Boundary::NotAKnot,
+		Ends::Periodic => Boundary::Periodic,
+	};
+	let spline = HermiteSpline::interpolating(&x, &y, boundary)?;
+
+	let (a, b) = (x[0], x[x.len() - 1]);
+	let extrema = spline.extrema();
+	let minima: Vec<[f64; 2]> = extrema.iter().filter(|extremum| !extremum.is_maximum).map(|extremum| [extremum.x, extremum.y]).collect();
+	let maxima: Vec<[f64; 2]> = extrema.iter().filter(|extremum| extremum.is_maximum).map(|extremum| [extremum.x, extremum.y]).collect();
+
+	let mut output = Output::default()
+		.summary("integral", spline.integral(a, b))
+		.summary("arc length", spline.arc_length(a, b))
+		.summary("roots", spline.roots())
+		.summary("minima", serde_json::to_value(minima)?)
+		.summary("maxima", serde_json::to_value(maxima)?)
+		.columns(&["x", "value", "derivative", "second derivative"]);
+
+	for &t in &nodes.at {
+		output.row(vec![t.into(), spline.solve(t).into(), spline.derivative(t).into(), spline.second_derivative(t).into()]);
+	}
+
+	Ok(output)
+}
@@ -0,0 +1,60 @@
+//! Evaluation of a spline on 10^4 nodes at 10^6 points, one by one and in a batch.
+//!
+//! `cargo bench -p numerics --bench spline`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use numerics::interp::random_nodes;
+use numerics::spline::{hermite, Boundary, HermiteSpline};
+
+const NODES: usize = 10_000;
+const POINTS: usize = 1_000_000;
+
+/// The lookup the spline used before: a linear walk over the nodes from the start for every point.
+fn linear_solve(spline: &HermiteSpline, t: f64) -> f64 {
+	let (x, y, s) = (spline.nodes(), spline.values(), spline.slopes());
+
+	let mut i = 1;
+	while t >= x[i] && i < (x.len() - 1) {
+		i += 1;
+	}
+
+	hermite(x[i - 1], y[i - 1], s[i - 1], x[i], y[i], s[i], t)
+}
+
+fn measure(name: &str, baseline: Option<Duration>, run: impl Fn() -> Vec<f64>) -> (Duration, Vec<f64>) {
+	let start = Instant::now();
+	let values = black_box(run());
+	let elapsed = start.elapsed();
+
+	match baseline {
+		Some(baseline) => println!("{name:<28} {elapsed:>12.2?}  {:>8.1}x", baseline.as_secs_f64() / elapsed.as_secs_f64()),
+		None => println!("{name:<28} {elapsed:>12.2?}"),
+	}
+	(elapsed, values)
+}
+
+fn main() {
+	let (a, b) = (0.0, 100.0);
+	let points = random_nodes(|x| (x / 3.0).sin() + 0.1 * x, a, b, NODES, 1);
+	let x: Vec<f64> = points.iter().map(|point| point[0]).collect();
+	let y: Vec<f64> = points.iter().map(|point| point[1]).collect();
+	let spline = HermiteSpline::interpolating(&x, &y, Boundary::Natural).unwrap();
+
+	let sorted: Vec<f64> = (0..POINTS).map(|i| a + (b - a) * i as f64 / (POINTS - 1) as f64).collect();
+	let mut rng = ChaCha8Rng::seed_from_u64(2);
+	let random: Vec<f64> = (0..POINTS).map(|_| rng.random_range(a..b)).collect();
+
+	println!("{NODES} nodes, {POINTS} points");
+	for (name, t) in [("sorted", &sorted), ("random", &random)] {
+		println!("\n{name} points");
+		let (baseline, expected) = measure("linear search, one by one", None, || t.iter().map(|&t| linear_solve(&spline, t)).collect());
+		let (_, single) = measure("binary search, one by one", Some(baseline), || t.iter().map(|&t| spline.solve(t)).collect());
+		let (_, batch) = measure("eval_many", Some(baseline), || spline.eval_many(t));
+
+		let difference = |values: &[f64]| values.iter().zip(&expected).fold(0.0, |max: f64, (a, b)| max.max((a - b).abs()));
+		println!("largest difference {:e}", difference(&single).max(difference(&batch)));
+	}
+}
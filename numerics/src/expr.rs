@@ -0,0 +1,329 @@
+//! Arithmetic expressions such as `(x - 1)^3 + 0.5 * exp(x)`, parsed at run time.
+//!
+//! Supported are numbers, the constants `pi` and `e`, the binary operators `+ - * / ^`
+//! (`**` is accepted for `^`), unary minus, parentheses and the functions `sin`, `cos`, `tan`,
+//! `asin`, `acos`, `atan`, `sinh`, `cosh`, `tanh`, `exp`, `ln`, `log10`, `sqrt`, `abs`
+//! and the two-argument `pow` and `atan2`.
+
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+	/// Byte offset in the source where the problem was found.
+	pub position: usize,
+	pub message: String,
+}
+
+impl Display for ParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} at position {}", self.message, self.position)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+type Function = fn(&[f64]) -> f64;
+
+/// Depth of the expression tree beyond which parsing fails, so that neither the parser nor [`Expr::eval`] overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Clone, Debug)]
+enum Node {
+	Number(f64),
+	Variable(usize),
+	Negate(Box<Node>),
+	Binary(char, Box<Node>, Box<Node>),
+	Call(Function, Vec<Node>),
+}
+
+impl Node {
+	fn eval(&self, variables: &[f64]) -> f64 {
+		match self {
+			Node::Number(value) => *value,
+			Node::Variable(index) => variables[*index],
+			Node::Negate(node) => -node.eval(variables),
+			Node::Binary(operator, left, right) => {
+				let left = left.eval(variables);
+				let right = right.eval(variables);
+				match operator {
+					'+' => left + right,
+					'-' => left - right,
+					'*' => left * right,
+					'/' => left / right,
+					_ => left.powf(right),
+				}
+			}
+			Node::Call(function, arguments) => {
+				let arguments: Vec<_> = arguments.iter().map(|argument| argument.eval(variables)).collect();
+				function(&arguments)
+			}
+		}
+	}
+}
+
+/// A parsed expression over a fixed list of variables.
+#[derive(Clone, Debug)]
+pub struct Expr {
+	source: String,
+	root: Node,
+}
+
+impl Expr {
+	/// Parses `source`, where `variables` lists the names that may appear in it.
+	pub fn parse(source: &str, variables: &[&str]) -> Result<Self, ParseError> {
+		let mut parser = Parser { source, position: 0, variables, depth: 0 };
+		let root = parser.expression()?;
+
+		parser.skip_whitespace();
+		if parser.position < source.len() {
+			return Err(parser.error("unexpected input"));
+		}
+
+		Ok(Self { source: source.to_string(), root })
+	}
+
+	/// Evaluates the expression; `values` follow the order of the variables given to [`Expr::parse`].
+	pub fn eval(&self, values: &[f64]) -> f64 {
+		self.root.eval(values)
+	}
+
+	pub fn source(&self) -> &str {
+		&self.source
+	}
+}
+
+fn function(name: &str) -> Option<(usize, Function)> {
+	let function: (usize, Function) = match name {
+		"sin" => (1, |a| a[0].sin()),
+		"cos" => (1, |a| a[0].cos()),
+		"tan" => (1, |a| a[0].tan()),
+		"asin" => (1, |a| a[0].asin()),
+		"acos" => (1, |a| a[0].acos()),
+		"atan" => (1, |a| a[0].atan()),
+		"sinh" => (1, |a| a[0].sinh()),
+		"cosh" => (1, |a| a[0].cosh()),
+		"tanh" => (1, |a| a[0].tanh()),
+		"exp" => (1, |a| a[0].exp()),
+		"ln" => (1, |a| a[0].ln()),
+		"log10" => (1, |a| a[0].log10()),
+		"sqrt" => (1, |a| a[0].sqrt()),
+		"abs" => (1, |a| a[0].abs()),
+		"pow" => (2, |a| a[0].powf(a[1])),
+		"atan2" => (2, |a| a[0].atan2(a[1])),
+		_ => return None,
+	};
+	Some(function)
+}
+
+struct Parser<'a> {
+	source: &'a str,
+	position: usize,
+	variables: &'a [&'a str],
+	/// Depth of the node being parsed.
+	depth: usize,
+}
+
+impl Parser<'_> {
+	fn error(&self, message: &str) -> ParseError {
+		ParseError { position: self.position, message: message.to_string() }
+	}
+
+	fn rest(&self) -> &str {
+		&self.source[self.position..]
+	}
+
+	fn skip_whitespace(&mut self) {
+		let rest = self.rest();
+		self.position += rest.len() - rest.trim_start().len();
+	}
+
+	/// Goes one level deeper into the tree, failing past [`MAX_DEPTH`].
+	fn descend(&mut self) -> Result<(), ParseError> {
+		if self.depth == MAX_DEPTH {
+			return Err(self.error(&format!("the expression is nested more than {MAX_DEPTH} levels deep")));
+		}
+		self.depth += 1;
+		Ok(())
+	}
+
+	fn eat(&mut self, token: &str) -> bool {
+		self.skip_whitespace();
+		if self.rest().starts_with(token) {
+			self.position += token.len();
+			true
+		} else {
+			false
+		}
+	}
+
+	// expression = term (('+' | '-') term)*
+	fn expression(&mut self) -> Result<Node, ParseError> {
+		let depth = self.depth;
+		let mut node = self.term()?;
+		loop {
+			let operator = if self.eat("+") { '+' } else if self.eat("-") { '-' } else { break };
+			// every operator nests the terms before it one level deeper
+			self.descend()?;
+			node = Node::Binary(operator, Box::new(node), Box::new(self.term()?));
+		}
+		self.depth = depth;
+		Ok(node)
+	}
+
+	// term = unary (('*' | '/') unary)*
+	fn term(&mut self) -> Result<Node, ParseError> {
+		let depth = self.depth;
+		let mut node = self.unary()?;
+		loop {
+			self.skip_whitespace();
+			if self.rest().starts_with("**") {
+				break;
+			}
+			let operator = if self.eat("*") { '*' } else if self.eat("/") { '/' } else { break };
+			self.descend()?;
+			node = Node::Binary(operator, Box::new(node), Box::new(self.unary()?));
+		}
+		self.depth = depth;
+		Ok(node)
+	}
+
+	// unary = '-' unary | '+' unary | power
+	fn unary(&mut self) -> Result<Node, ParseError> {
+		if self.eat("-") {
+			self.descend()?;
+			let node = Node::Negate(Box::new(self.unary()?));
+			self.depth -= 1;
+			Ok(node)
+		} else if self.eat("+") {
+			self.descend()?;
+			let node = self.unary()?;
+			self.depth -= 1;
+			Ok(node)
+		} else {
+			self.power()
+		}
+	}
+
+	// power = atom ('^' unary)?, so that 2^-x and right associativity work
+	fn power(&mut self) -> Result<Node, ParseError> {
+		let node = self.atom()?;
+		if self.eat("^") || self.eat("**") {
+			self.descend()?;
+			let node = Node::Binary('^', Box::new(node), Box::new(self.unary()?));
+			self.depth -= 1;
+			Ok(node)
+		} else {
+			Ok(node)
+		}
+	}
+
+	fn atom(&mut self) -> Result<Node, ParseError> {
+		if self.eat("(") {
+			self.descend()?;
+			let node = self.expression()?;
+			if !self.eat(")") {
+				return Err(self.error("expected ')'"));
+			}
+			self.depth -= 1;
+			return Ok(node);
+		}
+
+		let source = self.source;
+		let rest = &source[self.position..];
+
+		let length = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+		if length > 0 {
+			// the exponent of a literal like 1e-7
+			let mut end = length;
+			let tail = &rest[length..];
+			if tail.starts_with(['e', 'E']) {
+				let sign = usize::from(tail[1..].starts_with(['+', '-']));
+				let digits = tail[1 + sign..].find(|c: char| !c.is_ascii_digit()).unwrap_or(tail.len() - 1 - sign);
+				if digits > 0 {
+					end += 1 + sign + digits;
+				}
+			}
+
+			let value = rest[..end].parse().map_err(|_| self.error("invalid number"))?;
+			self.position += end;
+			return Ok(Node::Number(value));
+		}
+
+		let length = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+		if length == 0 {
+			return Err(self.error("expected a number, a name or '('"));
+		}
+
+		let name = &rest[..length];
+		let start = self.position;
+		self.position += length;
+
+		if let Some(index) = self.variables.iter().position(|&variable| variable == name) {
+			return Ok(Node::Variable(index));
+		}
+
+		match name {
+			"pi" => return Ok(Node::Number(std::f64::consts::PI)),
+			"e" => return Ok(Node::Number(std::f64::consts::E)),
+			_ => {}
+		}
+
+		let Some((arity, function)) = function(name) else {
+			self.position = start;
+			return Err(self.error(&format!("unknown name '{name}'")));
+		};
+
+		if !self.eat("(") {
+			return Err(self.error(&format!("expected '(' after '{name}'")));
+		}
+
+		self.descend()?;
+		let mut arguments = vec![self.expression()?];
+		while self.eat(",") {
+			arguments.push(self.expression()?);
+		}
+		self.depth -= 1;
+
+		if !self.eat(")") {
+			return Err(self.error("expected ')'"));
+		}
+		if arguments.len() != arity {
+			return Err(self.error(&format!("'{name}' takes {arity} argument(s)")));
+		}
+
+		Ok(Node::Call(function, arguments))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn precedence_and_functions() {
+		let expr = Expr::parse("-2^2 + 3 * pow(x, 2) / 2 - sin(0)", &["x"]).unwrap();
+		assert_eq!(expr.eval(&[2.0]), 2.0);
+		assert_eq!(Expr::parse("2 ** 3 ** 2", &[]).unwrap().eval(&[]), 512.0);
+	}
+
+	#[test]
+	fn deep_nesting_is_an_error() {
+		let depth = 30_000;
+		let source = "(".repeat(depth) + "x" + &")".repeat(depth);
+		let error = Expr::parse(&source, &["x"]).unwrap_err();
+		assert!(error.message.contains("nested"), "{error}");
+
+		let chain = vec!["x"; 1000].join(" + ");
+		assert!(Expr::parse(&chain, &["x"]).is_err());
+		assert!(Expr::parse(&"-".repeat(depth), &["x"]).is_err());
+	}
+
+	#[test]
+	fn moderate_nesting_is_accepted() {
+		let source = "(".repeat(100) + "x" + &")".repeat(100);
+		assert_eq!(Expr::parse(&source, &["x"]).unwrap().eval(&[3.0]), 3.0);
+
+		let chain = vec!["x"; 100].join(" + ");
+		assert_eq!(Expr::parse(&chain, &["x"]).unwrap().eval(&[1.0]), 100.0);
+	}
+}
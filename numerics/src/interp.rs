@@ -0,0 +1,89 @@
+//! Polynomial interpolation through nodes `[x, y]`.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// `amount` equally spaced nodes of `f` on `[a, b]`.
+pub fn uniform_nodes(f: impl Fn(f64) -> f64, a: f64, b: f64, amount: usize) -> Vec<[f64; 2]> {
+	let step = (b - a) / (amount - 1) as f64;
+
+	(0..amount).map(|index| {
+		let x = a + step * index as f64;
+		[x, f(x)]
+	}).collect()
+}
+
+/// `amount` nodes of `f`: the ends of `[a, b]` and uniformly random points between them.
+///
+/// The same `seed` gives the same nodes on every platform and run.
+pub fn random_nodes(f: impl Fn(f64) -> f64, a: f64, b: f64, amount: usize, seed: u64) -> Vec<[f64; 2]> {
+	let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+	let mut points: Vec<_> = (0..amount.saturating_sub(2)).map(|_| {
+		let x = rng.random_range(a..b);
+		[x, f(x)]
+	}).collect();
+	points.push([a, f(a)]);
+	points.push([b, f(b)]);
+	points.sort_by(|[a, _], [b, _]| a.total_cmp(b));
+
+	points
+}
+
+/// The `n` nodes closest to `x`, sorted by abscissa.
+pub fn nearest(points: &[[f64; 2]], x: f64, n: usize) -> Vec<[f64; 2]> {
+	let mut points = points.to_vec();
+	points.sort_by(|[a, _], [b, _]| (a - x).abs().total_cmp(&(b - x).abs()));
+	points.truncate(n);
+	points.sort_by(|[a, _], [b, _]| a.total_cmp(b));
+	points
+}
+
+/// Value at `x` of the Lagrange polynomial through all `points`.
+pub fn lagrange(points: &[[f64; 2]], x: f64) -> f64 {
+	points.iter().enumerate().map(|(i, &[xi, yi])| {
+		let basis: f64 = points.iter().enumerate()
+			.filter(|&(j, _)| j != i)
+			.map(|(_, &[xj, _])| (x - xj) / (xi - xj))
+			.product();
+		yi * basis
+	}).sum()
+}
+
+/// Value at `x` of the piecewise Lagrange polynomial of degree `n - 1`.
+///
+/// The nodes are split into consecutive groups of `n` that share their end nodes, and the group
+/// whose span contains `x` is used.
+pub fn piecewise_lagrange(points: &[[f64; 2]], x: f64, n: usize) -> f64 {
+	let step = n - 1;
+	let mut begin = 0;
+	while begin + step < points.len() - 1 && x > points[begin + step][0] {
+		begin += step;
+	}
+	lagrange(&points[begin..=begin + step], x)
+}
+
+/// Divided difference `f(x0, x1, ..., xn)` over all `points`.
+pub fn divided_difference(points: &[[f64; 2]]) -> f64 {
+	if points.len() == 1 {
+		return points[0][1];
+	}
+
+	let a = divided_difference(&points[1..]) - divided_difference(&points[..points.len() - 1]);
+	let b = points.last().unwrap()[0] - points.first().unwrap()[0];
+
+	a / b
+}
+
+/// Value at `x` of the Newton polynomial through all `points`.
+pub fn newton(points: &[[f64; 2]], x: f64) -> f64 {
+	let mut product = 1.0;
+	let mut result = 0.0;
+
+	for i in 0..points.len() {
+		result += divided_difference(&points[0..=i]) * product;
+		product *= x - points[i][0];
+	}
+
+	result
+}
@@ -0,0 +1,475 @@
+//! Reading tables of numbers from text files such as CSV or TSV, and matrices in the Matrix Market format.
+//!
+//! Values are separated by whitespace, commas or semicolons. Empty lines and lines starting
+//! with `#` or `%` are skipped, and so is a first line without any numbers, like `x,y`.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use nalgebra::{DMatrix, DVector};
+use crate::linalg::Csr;
+
+#[derive(Debug)]
+pub enum Error {
+	Io(std::io::Error),
+	/// `value` on the 1-based `line` is not a number.
+	InvalidNumber { line: usize, value: String },
+	/// The 1-based `line` has `found` values where `expected` are needed.
+	WrongLength { line: usize, expected: usize, found: usize },
+	/// The first line of a Matrix Market file is not a supported `%%MatrixMarket matrix ...` header.
+	InvalidHeader { header: String },
+	/// An entry on the 1-based `line` lies outside the matrix.
+	OutOfRange { line: usize, row: usize, column: usize },
+	/// A file holds `found` entries or values where `expected` are needed.
+	WrongCount { expected: usize, found: usize },
+	/// The matrix of a linear system is not square.
+	NotSquare { rows: usize, columns: usize },
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::Io(error) => write!(f, "{error}"),
+			Error::InvalidNumber { line, value } => write!(f, "line {line}: invalid number '{value}'"),
+			Error::WrongLength { line, expected, found } => write!(f, "line {line}: expected {expected} numbers, found {found}"),
+			Error::InvalidHeader { header } => write!(f, "unsupported Matrix Market header '{header}'"),
+			Error::OutOfRange { line, row, column } => write!(f, "line {line}: entry ({row}, {column}) is outside the matrix"),
+			Error::WrongCount { expected, found } => write!(f, "expected {expected} entries, found {found}"),
+			Error::NotSquare { rows, columns } => write!(f, "the matrix has {rows} rows and {columns} columns, it must be square"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+	fn from(error: std::io::Error) -> Self {
+		Error::Io(error)
+	}
+}
+
+/// The numbers of every data line of `text` with its 1-based line number.
+fn numbered_rows(text: &str) -> Result<Vec<(usize, Vec<f64>)>, Error> {
+	let mut rows = Vec::new();
+
+	for (index, line) in text.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with(['#', '%']) {
+			continue;
+		}
+
+		let values: Vec<&str> = line.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+			.filter(|value| !value.is_empty())
+			.collect();
+		let numbers: Vec<Option<f64>> = values.iter().map(|value| value.parse().ok()).collect();
+
+		// a header
+		if rows.is_empty() && numbers.iter().all(Option::is_none) {
+			continue;
+		}
+
+		let row = numbers.iter().zip(&values).map(|(number, value)| {
+			number.ok_or_else(|| Error::InvalidNumber { line: index + 1, value: value.to_string() })
+		}).collect::<Result<_, _>>()?;
+		rows.push((index + 1, row));
+	}
+
+	Ok(rows)
+}
+
+/// The numbers of every data line of `text`.
+pub fn parse_rows(text: &str) -> Result<Vec<Vec<f64>>, Error> {
+	Ok(numbered_rows(text)?.into_iter().map(|(_, row)| row).collect())
+}
+
+pub fn read_rows(path: &Path) -> Result<Vec<Vec<f64>>, Error> {
+	parse_rows(&fs::read_to_string(path)?)
+}
+
+/// Points `[x, y]` from text with two numbers per line.
+pub fn parse_points(text: &str) -> Result<Vec<[f64; 2]>, Error> {
+	numbered_rows(text)?.into_iter().map(|(line, row)| match row[..] {
+		[x, y] => Ok([x, y]),
+		_ => Err(Error::WrongLength { line, expected: 2, found: row.len() }),
+	}).collect()
+}
+
+pub fn read_points(path: &Path) -> Result<Vec<[f64; 2]>, Error> {
+	parse_points(&fs::read_to_string(path)?)
+}
+
+/// A matrix of Matrix Market coordinate entries, with 0-based indices and every symmetric entry spelled out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triplets {
+	pub rows: usize,
+	pub columns: usize,
+	pub entries: Vec<(usize, usize, f64)>,
+}
+
+impl Triplets {
+	/// The dense matrix, adding up repeated entries.
+	pub fn to_dense(&self) -> DMatrix<f64> {
+		let mut matrix = DMatrix::zeros(self.rows, self.columns);
+		for &(i, j, value) in &self.entries {
+			matrix[(i, j)] += value;
+		}
+		matrix
+	}
+
+	/// The sparse matrix, adding up repeated entries.
+	pub fn to_csr(&self) -> Csr {
+		Csr::from_triplets(self.rows, self.columns, &self.entries).expect("the parser checks the indices")
+	}
+
+	/// The non-zero entries of `matrix`.
+	pub fn from_dense(matrix: &DMatrix<f64>) -> Self {
+		let entries = (0..matrix.nrows())
+			.flat_map(|i| (0..matrix.ncols()).map(move |j| (i, j, matrix[(i, j)])))
+			.filter(|&(_, _, value)| value != 0.0)
+			.collect();
+		Self { rows: matrix.nrows(), columns: matrix.ncols(), entries }
+	}
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Symmetry {
+	General,
+	Symmetric,
+	SkewSymmetric,
+}
+
+/// Parses a real matrix in the Matrix Market format, either `coordinate` or `array`.
+///
+/// The field may be `real`, `integer` or, for coordinate matrices, `pattern`, where every entry is 1;
+/// the symmetry may be `general`, `symmetric` or `skew-symmetric`.
+pub fn parse_matrix_market(text: &str) -> Result<Triplets, Error> {
+	let mut lines = text.lines().enumerate().map(|(index, line)| (index + 1, line.trim()));
+
+	let (_, header) = lines.next().unwrap_or((1, ""));
+	let invalid_header = || Error::InvalidHeader { header: header.to_string() };
+	let words: Vec<String> = header.split_whitespace().map(str::to_lowercase).collect();
+	let [banner, object, format, field, symmetry] = &words[..] else {
+		return Err(invalid_header());
+	};
+	if banner != "%%matrixmarket" || object != "matrix" {
+		return Err(invalid_header());
+	}
+	let coordinate = match format.as_str() {
+		"coordinate" => true,
+		"array" => false,
+		_ => return Err(invalid_header()),
+	};
+	let pattern = match field.as_str() {
+		"real" | "integer" => false,
+		"pattern" if coordinate => true,
+		_ => return Err(invalid_header()),
+	};
+	let symmetry = match symmetry.as_str() {
+		"general" => Symmetry::General,
+		"symmetric" => Symmetry::Symmetric,
+		"skew-symmetric" => Symmetry::SkewSymmetric,
+		_ => return Err(invalid_header()),
+	};
+
+	let mut data = lines.filter(|(_, line)| !line.is_empty() && !line.starts_with('%')).map(|(line, text)| {
+		let values: Vec<&str> = text.split_whitespace().collect();
+		(line, values)
+	});
+	let number = |line: usize, value: &str| value.parse::<f64>().map_err(|_| Error::InvalidNumber { line, value: value.to_string() });
+	let index = |line: usize, value: &str| value.parse::<usize>().map_err(|_| Error::InvalidNumber { line, value: value.to_string() });
+
+	let (line, size) = data.next().ok_or(Error::WrongCount { expected: 1, found: 0 })?;
+	let expected = if coordinate { 3 } else { 2 };
+	if size.len() != expected {
+		return Err(Error::WrongLength { line, expected, found: size.len() });
+	}
+	let size = size.iter().map(|value| index(line, value)).collect::<Result<Vec<_>, _>>()?;
+	let (rows, columns) = (size[0], size[1]);
+	if symmetry != Symmetry::General && rows != columns {
+		return Err(invalid_header());
+	}
+
+	let mut entries = Vec::new();
+	if coordinate {
+		let count = size[2];
+		let lines: Vec<(usize, Vec<&str>)> = data.collect();
+		if lines.len() != count {
+			return Err(Error::WrongCount { expected: count, found: lines.len() });
+		}
+		for (line, values) in lines {
+			let expected = if pattern { 2 } else { 3 };
+			if values.len() != expected {
+				return Err(Error::WrongLength { line, expected, found: values.len() });
+			}
+			let (i, j) = (index(line, values[0])?, index(line, values[1])?);
+			if i == 0 || j == 0 || i > rows || j > columns {
+				return Err(Error::OutOfRange { line, row: i, column: j });
+			}
+			let value = if pattern { 1.0 } else { number(line, values[2])? };
+			entries.push((i - 1, j - 1, value));
+		}
+	} else {
+		// column by column, only the lower triangle of symmetric matrices
+		let positions: Vec<(usize, usize)> = (0..columns).flat_map(|j| {
+			let first = match symmetry {
+				Symmetry::General => 0,
+				Symmetry::Symmetric => j,
+				Symmetry::SkewSymmetric => j + 1,
+			};
+			(first..rows).map(move |i| (i, j))
+		}).collect();
+
+		let values: Vec<(usize, &str)> = data.flat_map(|(line, values)| values.into_iter().map(move |value| (line, value))).collect();
+		if values.len() != positions.len() {
+			return Err(Error::WrongCount { expected: positions.len(), found: values.len() });
+		}
+		for ((line, value), &(i, j)) in values.into_iter().zip(&positions) {
+			entries.push((i, j, number(line, value)?));
+		}
+	}
+
+	let mirrored: Vec<(usize, usize, f64)> = entries.iter().filter(|(i, j, _)| i != j).filter_map(|&(i, j, value)| match symmetry {
+		Symmetry::General => None,
+		Symmetry::Symmetric => Some((j, i, value)),
+		Symmetry::SkewSymmetric => Some((j, i, -value)),
+	}).collect();
+	entries.extend(mirrored);
+
+	Ok(Triplets { rows, columns, entries })
+}
+
+pub fn read_matrix_market(path: &Path) -> Result<Triplets, Error> {
+	parse_matrix_market(&fs::read_to_string(path)?)
+}
+
+/// Writes `value` so that it reads back exactly, in the plain notation unless it is very large or small.
+fn format_number(value: f64) -> String {
+	if value == 0.0 || (1e-4..1e15).contains(&value.abs()) || !value.is_finite() {
+		format!("{value}")
+	} else {
+		format!("{value:e}")
+	}
+}
+
+/// The matrix in the Matrix Market `array real general` format.
+pub fn format_matrix_market(matrix: &DMatrix<f64>) -> String {
+	let mut text = format!("%%MatrixMarket matrix array real general\n{} {}\n", matrix.nrows(), matrix.ncols());
+	for value in matrix.iter() {
+		text += &format_number(*value);
+		text.push('\n');
+	}
+	text
+}
+
+/// The matrix as comma-separated values, one row per line.
+pub fn format_csv(matrix: &DMatrix<f64>) -> String {
+	matrix.row_iter().map(|row| {
+		let values: Vec<String> = row.iter().map(|value| format_number(*value)).collect();
+		values.join(",") + "\n"
+	}).collect()
+}
+
+/// Whether `path` names a Matrix Market file, by its `.mtx` extension.
+pub fn is_matrix_market(path: &Path) -> bool {
+	path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("mtx"))
+}
+
+/// Reads a dense matrix from a Matrix Market file, or from a table of numbers with any other extension.
+pub fn read_matrix(path: &Path) -> Result<DMatrix<f64>, Error> {
+	if is_matrix_market(path) {
+		return Ok(read_matrix_market(path)?.to_dense());
+	}
+
+	parse_matrix(&fs::read_to_string(path)?)
+}
+
+/// A dense matrix from text with one row per line, all of the same length.
+pub fn parse_matrix(text: &str) -> Result<DMatrix<f64>, Error> {
+	let rows = numbered_rows(text)?;
+	let columns = rows.first().map_or(0, |(_, row)| row.len());
+	if let Some((line, row)) = rows.iter().find(|(_, row)| row.len() != columns) {
+		return Err(Error::WrongLength { line: *line, expected: columns, found: row.len() });
+	}
+	Ok(DMatrix::from_row_iterator(rows.len(), columns, rows.into_iter().flat_map(|(_, row)| row)))
+}
+
+/// Writes `matrix` in the format given by the extension of `path`, as [`read_matrix`] reads it.
+pub fn write_matrix(path: &Path, matrix: &DMatrix<f64>) -> Result<(), Error> {
+	let text = if is_matrix_market(path) { format_matrix_market(matrix) } else { format_csv(matrix) };
+	Ok(fs::write(path, text)?)
+}
+
+/// Writes `vector` as a single column.
+pub fn write_vector(path: &Path, vector: &DVector<f64>) -> Result<(), Error> {
+	write_matrix(path, &DMatrix::from_column_slice(vector.len(), 1, vector.as_slice()))
+}
+
+/// A vector written as a row or as a column.
+fn read_vector(path: &Path) -> Result<DVector<f64>, Error> {
+	let vector = read_matrix(path)?;
+	Ok(DVector::from_iterator(vector.len(), vector.transpose().iter().copied()))
+}
+
+/// Checks that `A` is square and fits `b`.
+fn check_system(rows: usize, columns: usize, rhs: usize) -> Result<(), Error> {
+	if rows != columns || rows == 0 {
+		return Err(Error::NotSquare { rows, columns });
+	}
+	if rhs != rows {
+		return Err(Error::WrongCount { expected: rows, found: rhs });
+	}
+	Ok(())
+}
+
+/// Reads `A` and `b` of a linear system `Ax = b` from Matrix Market or CSV files.
+///
+/// Without `rhs` the matrix file holds the augmented matrix `[A | b]`. The right-hand side may be
+/// written as a row or as a column.
+pub fn read_system(matrix: &Path, rhs: Option<&Path>) -> Result<(DMatrix<f64>, DVector<f64>), Error> {
+	let mut a = read_matrix(matrix)?;
+	let b = match rhs {
+		Some(rhs) => read_vector(rhs)?,
+		None => {
+			if a.ncols() == 0 {
+				return Err(Error::NotSquare { rows: a.nrows(), columns: 0 });
+			}
+			let last = a.ncols() - 1;
+			let b = a.column(last).into_owned();
+			a = a.remove_column(last);
+			b
+		}
+	};
+
+	check_system(a.nrows(), a.ncols(), b.len())?;
+	Ok((a, b))
+}
+
+/// The entries of a Matrix Market file, or the non-zero values of a table of numbers.
+fn read_triplets(path: &Path) -> Result<Triplets, Error> {
+	if is_matrix_market(path) {
+		read_matrix_market(path)
+	} else {
+		Ok(Triplets::from_dense(&read_matrix(path)?))
+	}
+}
+
+/// Reads a sparse matrix; a Matrix Market file is never expanded to a dense one.
+pub fn read_sparse(path: &Path) -> Result<Csr, Error> {
+	Ok(read_triplets(path)?.to_csr())
+}
+
+/// Reads a linear system with a sparse matrix, as [`read_system`] does.
+pub fn read_sparse_system(matrix: &Path, rhs: Option<&Path>) -> Result<(Csr, DVector<f64>), Error> {
+	let mut triplets = read_triplets(matrix)?;
+	let b = match rhs {
+		Some(rhs) => read_vector(rhs)?,
+		None => {
+			if triplets.columns == 0 {
+				return Err(Error::NotSquare { rows: triplets.rows, columns: 0 });
+			}
+			triplets.columns -= 1;
+			let last = triplets.columns;
+			let mut b = DVector::zeros(triplets.rows);
+			for &(i, _, value) in triplets.entries.iter().filter(|(_, j, _)| *j == last) {
+				b[i] += value;
+			}
+			triplets.entries.retain(|(_, j, _)| *j != last);
+			b
+		}
+	};
+
+	check_system(triplets.rows, triplets.columns, b.len())?;
+	Ok((triplets.to_csr(), b))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wrong_length_reports_the_file_line() {
+		let text = "a,b\n# comment\n1,2\n\n3,4\n5\n";
+		let error = parse_matrix(text).unwrap_err();
+		assert!(matches!(error, Error::WrongLength { line: 6, expected: 2, found: 1 }), "{error}");
+	}
+
+	#[test]
+	fn matrix_skips_header_and_comments() {
+		let matrix = parse_matrix("x y\n% note\n1 2\n3 4\n").unwrap();
+		assert_eq!(matrix, DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]));
+	}
+
+	#[test]
+	fn matrix_market_coordinate_general() {
+		let text = "%%MatrixMarket matrix coordinate real general\n% a comment\n\n2 3 4\n1 1 1.5\n2 3 -2e-3\n1 3 4\n1 1 0.5\n";
+		let triplets = parse_matrix_market(text).unwrap();
+		assert_eq!((triplets.rows, triplets.columns), (2, 3));
+		assert_eq!(triplets.entries, [(0, 0, 1.5), (1, 2, -2e-3), (0, 2, 4.0), (0, 0, 0.5)]);
+		assert_eq!(triplets.to_dense(), DMatrix::from_row_slice(2, 3, &[2.0, 0.0, 4.0, 0.0, 0.0, -2e-3]));
+	}
+
+	#[test]
+	fn matrix_market_array_is_column_major() {
+		let text = "%%MatrixMarket matrix array integer general\n2 3\n1\n2\n3\n4 5\n6\n";
+		let matrix = parse_matrix_market(text).unwrap().to_dense();
+		assert_eq!(matrix, DMatrix::from_row_slice(2, 3, &[1.0, 3.0, 5.0, 2.0, 4.0, 6.0]));
+	}
+
+	#[test]
+	fn matrix_market_symmetric_mirrors_entries() {
+		let coordinate = "%%MatrixMarket matrix coordinate real symmetric\n3 3 4\n1 1 2\n2 1 -1\n3 2 5\n3 3 7\n";
+		let expected = DMatrix::from_row_slice(3, 3, &[2.0, -1.0, 0.0, -1.0, 0.0, 5.0, 0.0, 5.0, 7.0]);
+		let triplets = parse_matrix_market(coordinate).unwrap();
+		// the diagonal is not mirrored
+		assert_eq!(triplets.entries.len(), 6);
+		assert_eq!(triplets.to_dense(), expected);
+
+		let array = "%%MatrixMarket matrix array real symmetric\n3 3\n2\n-1\n0\n0\n5\n7\n";
+		assert_eq!(parse_matrix_market(array).unwrap().to_dense(), expected);
+	}
+
+	#[test]
+	fn matrix_market_skew_symmetric_negates_mirrored_entries() {
+		let coordinate = "%%MatrixMarket matrix coordinate real skew-symmetric\n3 3 2\n2 1 1.5\n3 1 -4\n";
+		let expected = DMatrix::from_row_slice(3, 3, &[0.0, -1.5, 4.0, 1.5, 0.0, 0.0, -4.0, 0.0, 0.0]);
+		assert_eq!(parse_matrix_market(coordinate).unwrap().to_dense(), expected);
+
+		let array = "%%MatrixMarket matrix array real skew-symmetric\n3 3\n1.5 -4 0\n";
+		assert_eq!(parse_matrix_market(array).unwrap().to_dense(), expected);
+	}
+
+	#[test]
+	fn matrix_market_pattern_entries_are_ones() {
+		let text = "%%MatrixMarket matrix coordinate pattern general\n2 2 3\n1 2\n2 1\n2 2\n";
+		let matrix = parse_matrix_market(text).unwrap().to_dense();
+		assert_eq!(matrix, DMatrix::from_row_slice(2, 2, &[0.0, 1.0, 1.0, 1.0]));
+		// array matrices have no pattern field
+		assert!(matches!(parse_matrix_market("%%MatrixMarket matrix array pattern general\n1 1\n"), Err(Error::InvalidHeader { .. })));
+	}
+
+	#[test]
+	fn matrix_market_malformed_input() {
+		let size = parse_matrix_market("%%MatrixMarket matrix coordinate real general\n% size next\n2 2\n1 1 1\n").unwrap_err();
+		assert!(matches!(size, Error::WrongLength { line: 3, expected: 3, found: 2 }), "{size}");
+		let number = parse_matrix_market("%%MatrixMarket matrix array real general\n2 x\n").unwrap_err();
+		assert!(matches!(number, Error::InvalidNumber { line: 2, .. }), "{number}");
+		let range = parse_matrix_market("%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1\n").unwrap_err();
+		assert!(matches!(range, Error::OutOfRange { line: 3, row: 3, column: 1 }), "{range}");
+		let count = parse_matrix_market("%%MatrixMarket matrix coordinate real general\n2 2 2\n1 1 1\n").unwrap_err();
+		assert!(matches!(count, Error::WrongCount { expected: 2, found: 1 }), "{count}");
+		assert!(matches!(parse_matrix_market("%%MatrixMarket matrix coordinate complex general\n"), Err(Error::InvalidHeader { .. })));
+		assert!(matches!(parse_matrix_market("%%MatrixMarket matrix array real symmetric\n2 3\n"), Err(Error::InvalidHeader { .. })));
+	}
+
+	#[test]
+	fn matrix_market_round_trip() {
+		let poisson = Csr::poisson_2d(5).to_dense();
+		let read = parse_matrix_market(&format_matrix_market(&poisson)).unwrap();
+		assert_eq!(read.to_dense(), poisson);
+		assert_eq!(Csr::from_dense(&read.to_dense()), Csr::poisson_2d(5));
+
+		// values that need all their digits or the exponent notation come back exactly
+		let awkward = DMatrix::from_row_slice(2, 2, &[1.0 / 3.0, -1e-20, 6.02e23, 0.1]);
+		assert_eq!(parse_matrix_market(&format_matrix_market(&awkward)).unwrap().to_dense(), awkward);
+	}
+}
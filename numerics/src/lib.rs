@@ -0,0 +1,23 @@
+//! Numerical methods from the course exercises, collected into one library.
+//!
+//! Every exercise binary in the workspace is a thin front-end over these modules:
+//!
+//! * [`roots`] — simple iterations, bisection and the secant method for `f(x) = 0`;
+//! * [`linalg`] — tridiagonal systems by the sweep method, band matrices and stationary iterations for `Ax = b`;
+//! * [`interp`] — Lagrange and Newton interpolation polynomials;
+//! * [`spline`] — Hermite, parabolic and cubic splines, parametric curves and bicubic surfaces;
+//! * [`ode`] — Euler, Runge–Kutta and Adams methods for the Cauchy problem;
+//! * [`quad`] — quadrature formulas with automatic step selection;
+//! * [`io`] — tables of numbers read from CSV or TSV files;
+//! * [`report`] — iteration tables rendered as Markdown, LaTeX or CSV;
+//! * [`expr`] — run-time parsing of functions like `x^2 - sin(x)`.
+
+pub mod expr;
+pub mod interp;
+pub mod io;
+pub mod linalg;
+pub mod ode;
+pub mod quad;
+pub mod report;
+pub mod roots;
+pub mod spline;
@@ -0,0 +1,376 @@
+//! Band matrices, whose non-zero values lie within a few diagonals of the main one.
+
+use nalgebra::DMatrix;
+use super::Error;
+
+/// A square matrix with `lower` diagonals below the main one and `upper` above it.
+///
+/// Only the band is stored, row by row, so the memory and the work of the factorizations grow linearly with the size.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Banded {
+	size: usize,
+	lower: usize,
+	upper: usize,
+	values: Vec<f64>,
+}
+
+impl Banded {
+	pub fn zeros(size: usize, lower: usize, upper: usize) -> Self {
+		Self { size, lower, upper, values: vec![0.0; size * (lower + upper + 1)] }
+	}
+
+	pub fn size(&self) -> usize {
+		self.size
+	}
+
+	/// Number of diagonals below the main one.
+	pub fn lower_bandwidth(&self) -> usize {
+		self.lower
+	}
+
+	/// Number of diagonals above the main one.
+	pub fn upper_bandwidth(&self) -> usize {
+		self.upper
+	}
+
+	fn in_band(&self, i: usize, j: usize) -> bool {
+		i < self.size && j < self.size && j + self.lower >= i && j <= i + self.upper
+	}
+
+	fn index(&self, i: usize, j: usize) -> usize {
+		i * (self.lower + self.upper + 1) + j + self.lower - i
+	}
+
+	/// Entry `(i, j)`, zero outside the band.
+	pub fn get(&self, i: usize, j: usize) -> f64 {
+		if self.in_band(i, j) { self.values[self.index(i, j)] } else { 0.0 }
+	}
+
+	/// Entry `(i, j)`, which must lie within the band.
+	pub fn get_mut(&mut self, i: usize, j: usize) -> &mut f64 {
+		assert!(self.in_band(i, j), "({i}, {j}) is outside the band");
+		let index = self.index(i, j);
+		&mut self.values[index]
+	}
+
+	/// Columns of row `i` within the band.
+	fn columns(&self, i: usize) -> std::ops::Range<usize> {
+		i.saturating_sub(self.lower)..(i + self.upper + 1).min(self.size)
+	}
+
+	pub fn mul_vec(&self, x: &[f64]) -> Vec<f64> {
+		assert_eq!(x.len(), self.size);
+		(0..self.size).map(|i| self.columns(i).map(|j| self.get(i, j) * x[j]).sum()).collect()
+	}
+
+	pub fn to_dense(&self) -> DMatrix<f64> {
+		DMatrix::from_fn(self.size, self.size, |i, j| self.get(i, j))
+	}
+
+	/// Factorization `P A = L U` by Gaussian elimination with partial pivoting inside the band.
+	///
+	/// Row exchanges widen `U` to `lower + upper` diagonals above the main one, while `L` keeps `lower` below it.
+	/// Fails with [`Error::ZeroPivot`] when a whole column below the diagonal vanishes relative to the matrix.
+	pub fn lu(&self) -> Result<BandedLu, Error> {
+		let (n, lower) = (self.size, self.lower);
+		if n == 0 {
+			return Err(Error::Empty);
+		}
+
+		let upper = self.lower + self.upper;
+		let mut factors = Banded::zeros(n, lower, upper);
+		for i in 0..n {
+			for j in self.columns(i) {
+				*factors.get_mut(i, j) = self.get(i, j);
+			}
+		}
+
+		let scale = self.values.iter().fold(0.0, |max: f64, value| max.max(value.abs()));
+		let mut pivots = Vec::with_capacity(n);
+
+		for k in 0..n {
+			let last_row = (k + lower).min(n - 1);
+			let last_column = (k + upper).min(n - 1);
+
+			let pivot_row = (k..=last_row).max_by(|&a, &b| factors.get(a, k).abs().total_cmp(&factors.get(b, k).abs())).unwrap();
+			let pivot = factors.get(pivot_row, k);
+			if pivot.abs() <= f64::EPSILON * scale || !pivot.is_finite() {
+				return Err(Error::ZeroPivot { row: k });
+			}
+
+			pivots.push(pivot_row);
+			if pivot_row != k {
+				for j in k..=last_column {
+					let value = factors.get(k, j);
+					*factors.get_mut(k, j) = factors.get(pivot_row, j);
+					*factors.get_mut(pivot_row, j) = value;
+				}
+			}
+
+			for i in k + 1..=last_row {
+				let factor = factors.get(i, k) / pivot;
+				*factors.get_mut(i, k) = factor;
+				for j in k + 1..=last_column {
+					let value = factors.get(k, j);
+					*factors.get_mut(i, j) -= factor * value;
+				}
+			}
+		}
+
+		Ok(BandedLu { factors, pivots, norm: self.norm_1() })
+	}
+
+	/// The 1-norm, the largest sum of absolute values in a column.
+	pub fn norm_1(&self) -> f64 {
+		(0..self.size).map(|j| {
+			(j.saturating_sub(self.upper)..(j + self.lower + 1).min(self.size)).map(|i| self.get(i, j).abs()).sum::<f64>()
+		}).fold(0.0, f64::max)
+	}
+
+	pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, Error> {
+		self.lu()?.solve(rhs)
+	}
+
+	/// Factorization `L D L^T` of a symmetric positive definite matrix, reading only the lower band.
+	///
+	/// Fails with [`Error::NotPositiveDefinite`] when a pivot of `D` is not positive.
+	pub fn ldl(&self) -> Result<Ldl, Error> {
+		let (n, p) = (self.size, self.lower);
+		if n == 0 {
+			return Err(Error::Empty);
+		}
+
+		// l[i * p + k] = L[i][i - 1 - k]
+		let mut l = vec![0.0; n * p];
+		let mut d = vec![0.0; n];
+
+		for i in 0..n {
+			for j in i.saturating_sub(p)..i {
+				// L[i][j] d[j] = A[i][j] - sum over k < j of L[i][k] d[k] L[j][k]
+				let sum: f64 = (i.saturating_sub(p)..j).map(|k| l[i * p + i - 1 - k] * d[k] * l[j * p + j - 1 - k]).sum();
+				l[i * p + i - 1 - j] = (self.get(i, j) - sum) / d[j];
+			}
+
+			let sum: f64 = (i.saturating_sub(p)..i).map(|k| l[i * p + i - 1 - k].powi(2) * d[k]).sum();
+			d[i] = self.get(i, i) - sum;
+			if d[i] <= f64::EPSILON * self.get(i, i).abs() || !d[i].is_finite() {
+				return Err(Error::NotPositiveDefinite { row: i });
+			}
+		}
+
+		Ok(Ldl { bandwidth: p, l, d })
+	}
+}
+
+/// The factorization `P A = L U` of a [`Banded`] matrix, with `L` unit lower triangular.
+#[derive(Clone, Debug)]
+pub struct BandedLu {
+	/// Multipliers of `L` below the diagonal and `U` on and above it.
+	factors: Banded,
+	/// Row exchanged with row `k` at step `k`.
+	pivots: Vec<usize>,
+	/// 1-norm of the factorized matrix.
+	norm: f64,
+}
+
+impl BandedLu {
+	pub fn size(&self) -> usize {
+		self.factors.size
+	}
+
+	pub fn determinant(&self) -> f64 {
+		let exchanges = self.pivots.iter().enumerate().filter(|&(k, &pivot)| pivot != k).count();
+		let sign = if exchanges % 2 == 0 { 1.0 } else { -1.0 };
+		sign * (0..self.size()).map(|i| self.factors.get(i, i)).product::<f64>()
+	}
+
+	/// Overwrites `rhs` with the solution, without allocating.
+	pub fn solve_in_place(&self, rhs: &mut [f64]) -> Result<(), Error> {
+		let factors = &self.factors;
+		let n = factors.size;
+		if rhs.len() != n {
+			return Err(Error::DimensionMismatch { expected: n, found: rhs.len() });
+		}
+
+		for k in 0..n {
+			rhs.swap(k, self.pivots[k]);
+			for i in k + 1..(k + factors.lower + 1).min(n) {
+				rhs[i] -= factors.get(i, k) * rhs[k];
+			}
+		}
+		for i in (0..n).rev() {
+			let sum: f64 = (i + 1..(i + factors.upper + 1).min(n)).map(|j| factors.get(i, j) * rhs[j]).sum();
+			rhs[i] = (rhs[i] - sum) / factors.get(i, i);
+		}
+
+		Ok(())
+	}
+
+	pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, Error> {
+		let mut x = rhs.to_vec();
+		self.solve_in_place(&mut x)?;
+		Ok(x)
+	}
+
+	/// Solves `A^T x = rhs` with the same factors.
+	pub fn solve_transposed_in_place(&self, rhs: &mut [f64]) -> Result<(), Error> {
+		let factors = &self.factors;
+		let n = factors.size;
+		if rhs.len() != n {
+			return Err(Error::DimensionMismatch { expected: n, found: rhs.len() });
+		}
+
+		for i in 0..n {
+			let sum: f64 = (i.saturating_sub(factors.upper)..i).map(|j| factors.get(j, i) * rhs[j]).sum();
+			rhs[i] = (rhs[i] - sum) / factors.get(i, i);
+		}
+		for k in (0..n).rev() {
+			let sum: f64 = (k + 1..(k + factors.lower + 1).min(n)).map(|i| factors.get(i, k) * rhs[i]).sum();
+			rhs[k] -= sum;
+			rhs.swap(k, self.pivots[k]);
+		}
+
+		Ok(())
+	}
+
+	/// Estimate of the condition number `‖A‖₁ ‖A^-1‖₁`, see [`estimate_inverse_norm`](super::estimate_inverse_norm).
+	pub fn condition(&self) -> f64 {
+		let solve = |x: &mut [f64]| self.solve_in_place(x).unwrap();
+		let solve_transposed = |x: &mut [f64]| self.solve_transposed_in_place(x).unwrap();
+		self.norm * super::estimate_inverse_norm(self.size(), solve, solve_transposed)
+	}
+}
+
+/// The factorization `L D L^T` of a symmetric positive definite [`Banded`] matrix,
+/// with `L` unit lower triangular of the same bandwidth.
+#[derive(Clone, Debug)]
+pub struct Ldl {
+	bandwidth: usize,
+	l: Vec<f64>,
+	d: Vec<f64>,
+}
+
+impl Ldl {
+	pub fn size(&self) -> usize {
+		self.d.len()
+	}
+
+	/// `L[i][j]` for `j < i`.
+	fn l(&self, i: usize, j: usize) -> f64 {
+		if i - j <= self.bandwidth { self.l[i * self.bandwidth + i - 1 - j] } else { 0.0 }
+	}
+
+	/// Product of the pivots.
+	pub fn determinant(&self) -> f64 {
+		self.d.iter().product()
+	}
+
+	/// Overwrites `rhs` with the solution, without allocating.
+	pub fn solve_in_place(&self, rhs: &mut [f64]) -> Result<(), Error> {
+		let (n, p) = (self.size(), self.bandwidth);
+		if rhs.len() != n {
+			return Err(Error::DimensionMismatch { expected: n, found: rhs.len() });
+		}
+
+		for i in 0..n {
+			let sum: f64 = (i.saturating_sub(p)..i).map(|j| self.l(i, j) * rhs[j]).sum();
+			rhs[i] -= sum;
+		}
+		for (value, d) in rhs.iter_mut().zip(&self.d) {
+			*value /= d;
+		}
+		for i in (0..n).rev() {
+			let sum: f64 = (i + 1..(i + p + 1).min(n)).map(|j| self.l(j, i) * rhs[j]).sum();
+			rhs[i] -= sum;
+		}
+
+		Ok(())
+	}
+
+	pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, Error> {
+		let mut x = rhs.to_vec();
+		self.solve_in_place(&mut x)?;
+		Ok(x)
+	}
+
+	/// The entries of the inverse matrix within the band, in linear time.
+	///
+	/// This is enough for traces like `tr(A^-1 B)` with `B` of the same bandwidth,
+	/// as met in the cross-validation of smoothing splines.
+	pub fn inverse_band(&self) -> Banded {
+		let (n, p) = (self.size(), self.bandwidth);
+		let mut inverse = Banded::zeros(n, p, p);
+
+		// S = D^-1 L^-1 + (I - L^T) S, solved upwards from the last row
+		for i in (0..n).rev() {
+			for j in (i..(i + p + 1).min(n)).rev() {
+				let diagonal = if i == j { 1.0 / self.d[i] } else { 0.0 };
+				let sum: f64 = (i + 1..(i + p + 1).min(n)).map(|k| self.l(k, i) * inverse.get(k.min(j), k.max(j))).sum();
+				let value = diagonal - sum;
+				*inverse.get_mut(i, j) = value;
+				*inverse.get_mut(j, i) = value;
+			}
+		}
+
+		inverse
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use nalgebra::DVector;
+
+	/// A band matrix with one lower and two upper diagonals whose diagonal vanishes in every third row,
+	/// so the elimination has to exchange rows.
+	fn matrix(n: usize) -> Banded {
+		let mut a = Banded::zeros(n, 1, 2);
+		for i in 0..n {
+			for j in i.saturating_sub(1)..(i + 3).min(n) {
+				*a.get_mut(i, j) = if i == j && i % 3 == 1 { 0.0 } else { 1.0 + ((i * 7 + j * 3) % 5) as f64 * 0.5 };
+			}
+		}
+		a
+	}
+
+	fn exact_inverse_norm(a: &DMatrix<f64>) -> f64 {
+		let inverse = a.clone().try_inverse().unwrap();
+		inverse.column_iter().map(|column| column.abs().sum()).fold(0.0, f64::max)
+	}
+
+	#[test]
+	fn pivoted_lu_matches_dense_lu() {
+		for n in [1, 2, 5, 17] {
+			let a = matrix(n);
+			let dense = a.to_dense();
+			let b: Vec<f64> = (0..n).map(|i| (i as f64).cos()).collect();
+			let exact = dense.clone().lu().solve(&DVector::from_vec(b.clone())).unwrap();
+			let exact_transposed = dense.transpose().lu().solve(&DVector::from_vec(b.clone())).unwrap();
+
+			let lu = a.lu().unwrap();
+			let mut transposed = b.clone();
+			lu.solve_transposed_in_place(&mut transposed).unwrap();
+
+			assert!((DVector::from_vec(lu.solve(&b).unwrap()) - exact).norm() < 1e-10, "n = {n}");
+			assert!((DVector::from_vec(transposed) - exact_transposed).norm() < 1e-10, "n = {n}");
+			assert!((lu.determinant() - dense.determinant()).abs() < 1e-9 * dense.determinant().abs().max(1.0), "n = {n}");
+		}
+	}
+
+	#[test]
+	fn condition_estimate_is_a_lower_bound_within_a_factor_of_three() {
+		let a = matrix(17);
+		let dense = a.to_dense();
+		let exact = a.norm_1() * exact_inverse_norm(&dense);
+		let estimate = a.lu().unwrap().condition();
+		assert!(estimate <= exact * (1.0 + 1e-10) && estimate >= exact / 3.0, "{estimate} vs {exact}");
+	}
+
+	#[test]
+	fn singular_band_is_an_error() {
+		let mut a = Banded::zeros(3, 1, 1);
+		*a.get_mut(0, 0) = 1.0;
+		*a.get_mut(2, 2) = 1.0;
+		assert!(matches!(a.lu(), Err(Error::ZeroPivot { row: 1 })));
+	}
+}
@@ -0,0 +1,276 @@
+//! Block tridiagonal systems, solved by the block version of the sweep method.
+
+use nalgebra::{DMatrix, DVector};
+use super::Error;
+
+/// A matrix of square diagonal blocks with one block on either side of each, as met in implicit schemes on 2D grids.
+///
+/// Block row `i` reads `lower[i - 1] x[i - 1] + diagonal[i] x[i] + upper[i] x[i + 1]`.
+/// The diagonal blocks may have different sizes, the others must fit between them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockTridiagonal {
+	lower: Vec<DMatrix<f64>>,
+	diagonal: Vec<DMatrix<f64>>,
+	upper: Vec<DMatrix<f64>>,
+}
+
+impl BlockTridiagonal {
+	pub fn new(lower: Vec<DMatrix<f64>>, diagonal: Vec<DMatrix<f64>>, upper: Vec<DMatrix<f64>>) -> Result<Self, Error> {
+		let n = diagonal.len();
+		if n == 0 {
+			return Err(Error::Empty);
+		}
+		for band in [&lower, &upper] {
+			if band.len() != n - 1 {
+				return Err(Error::DimensionMismatch { expected: n - 1, found: band.len() });
+			}
+		}
+
+		let sizes: Vec<usize> = diagonal.iter().map(DMatrix::nrows).collect();
+		let check = |block: &DMatrix<f64>, rows: usize, columns: usize| {
+			if block.nrows() != rows {
+				Err(Error::DimensionMismatch { expected: rows, found: block.nrows() })
+			} else if block.ncols() != columns {
+				Err(Error::DimensionMismatch { expected: columns, found: block.ncols() })
+			} else {
+				Ok(())
+			}
+		};
+		for i in 0..n {
+			check(&diagonal[i], sizes[i], sizes[i])?;
+			if i + 1 < n {
+				check(&lower[i], sizes[i + 1], sizes[i])?;
+				check(&upper[i], sizes[i], sizes[i + 1])?;
+			}
+		}
+
+		Ok(Self { lower, diagonal, upper })
+	}
+
+	pub fn block_count(&self) -> usize {
+		self.diagonal.len()
+	}
+
+	/// Number of rows of the whole matrix.
+	pub fn size(&self) -> usize {
+		self.diagonal.iter().map(DMatrix::nrows).sum()
+	}
+
+	/// First row of every block.
+	fn offsets(&self) -> Vec<usize> {
+		self.diagonal.iter().scan(0, |offset, block| {
+			let start = *offset;
+			*offset += block.nrows();
+			Some(start)
+		}).collect()
+	}
+
+	pub fn to_dense(&self) -> DMatrix<f64> {
+		let offsets = self.offsets();
+		let mut dense = DMatrix::zeros(self.size(), self.size());
+		for i in 0..self.block_count() {
+			let mut place = |row: usize, column: usize, block: &DMatrix<f64>| {
+				dense.view_mut((offsets[row], offsets[column]), block.shape()).copy_from(block);
+			};
+			place(i, i, &self.diagonal[i]);
+			if i + 1 < self.block_count() {
+				place(i + 1, i, &self.lower[i]);
+				place(i, i + 1, &self.upper[i]);
+			}
+		}
+		dense
+	}
+
+	pub fn mul_vec(&self, x: &DVector<f64>) -> DVector<f64> {
+		let offsets = self.offsets();
+		let part = |i: usize| x.rows(offsets[i], self.diagonal[i].nrows());
+
+		let mut result = DVector::zeros(self.size());
+		for (i, &offset) in offsets.iter().enumerate() {
+			let mut row = &self.diagonal[i] * part(i);
+			if i > 0 {
+				row += &self.lower[i - 1] * part(i - 1);
+			}
+			if i + 1 < self.block_count() {
+				row += &self.upper[i] * part(i + 1);
+			}
+			result.rows_mut(offset, row.len()).copy_from(&row);
+		}
+		result
+	}
+
+	/// The 1-norm, the largest sum of absolute values in a column.
+	pub fn norm_1(&self) -> f64 {
+		let column_sums = |block: &DMatrix<f64>| block.row_iter().fold(DVector::zeros(block.ncols()).transpose(), |sums, row| sums + row.abs());
+		(0..self.block_count()).map(|j| {
+			let mut sums = column_sums(&self.diagonal[j]);
+			if j > 0 {
+				sums += column_sums(&self.upper[j - 1]);
+			}
+			if j + 1 < self.block_count() {
+				sums += column_sums(&self.lower[j]);
+			}
+			sums.max()
+		}).fold(0.0, f64::max)
+	}
+
+	/// The direct sweep over the blocks, `S[i] = diagonal[i] - lower[i - 1] S[i - 1]^-1 upper[i - 1]`.
+	///
+	/// Fails with [`Error::SingularBlock`] when some `S[i]` cannot be inverted.
+	pub fn factorize(&self) -> Result<BlockFactorization, Error> {
+		let n = self.block_count();
+		let mut inverses: Vec<DMatrix<f64>> = Vec::with_capacity(n);
+
+		for i in 0..n {
+			let pivot = if i == 0 {
+				self.diagonal[0].clone()
+			} else {
+				&self.diagonal[i] - &self.lower[i - 1] * &inverses[i - 1] * &self.upper[i - 1]
+			};
+			let inverse = pivot.try_inverse().filter(|inverse| inverse.iter().all(|value| value.is_finite()));
+			inverses.push(inverse.ok_or(Error::SingularBlock { block: i })?);
+		}
+
+		Ok(BlockFactorization { matrix: self.clone(), inverses, norm: self.norm_1() })
+	}
+
+	pub fn solve(&self, rhs: &DVector<f64>) -> Result<DVector<f64>, Error> {
+		self.factorize()?.solve(rhs)
+	}
+}
+
+/// The direct sweep of a [`BlockTridiagonal`] matrix, keeping the inverses of the pivot blocks `S[i]`.
+#[derive(Clone, Debug)]
+pub struct BlockFactorization {
+	matrix: BlockTridiagonal,
+	inverses: Vec<DMatrix<f64>>,
+	/// 1-norm of the factorized matrix.
+	norm: f64,
+}
+
+impl BlockFactorization {
+	pub fn size(&self) -> usize {
+		self.matrix.size()
+	}
+
+	pub fn solve(&self, rhs: &DVector<f64>) -> Result<DVector<f64>, Error> {
+		self.sweep(rhs, false)
+	}
+
+	/// Solves `A^T x = rhs` with the same pivot blocks.
+	pub fn solve_transposed(&self, rhs: &DVector<f64>) -> Result<DVector<f64>, Error> {
+		self.sweep(rhs, true)
+	}
+
+	/// `A = L U` with `U` holding `S[i]` and `upper[i]`, and `L` holding `lower[i - 1] S[i - 1]^-1` below the unit diagonal.
+	fn sweep(&self, rhs: &DVector<f64>, transposed: bool) -> Result<DVector<f64>, Error> {
+		if rhs.len() != self.size() {
+			return Err(Error::DimensionMismatch { expected: self.size(), found: rhs.len() });
+		}
+
+		let matrix = &self.matrix;
+		let n = matrix.block_count();
+		let offsets = matrix.offsets();
+		let part = |x: &DVector<f64>, i: usize| x.rows(offsets[i], matrix.diagonal[i].nrows()).into_owned();
+		let mut x: Vec<DVector<f64>> = (0..n).map(|i| part(rhs, i)).collect();
+
+		if transposed {
+			// U^T z = rhs, then L^T x = z
+			for i in 0..n {
+				if i > 0 {
+					let carried = matrix.upper[i - 1].transpose() * &x[i - 1];
+					x[i] -= carried;
+				}
+				x[i] = self.inverses[i].transpose() * &x[i];
+			}
+			for i in (0..n - 1).rev() {
+				let carried = self.inverses[i].transpose() * (matrix.lower[i].transpose() * &x[i + 1]);
+				x[i] -= carried;
+			}
+		} else {
+			// L z = rhs, then U x = z
+			for i in 1..n {
+				let carried = &matrix.lower[i - 1] * (&self.inverses[i - 1] * &x[i - 1]);
+				x[i] -= carried;
+			}
+			for i in (0..n).rev() {
+				if i + 1 < n {
+					let carried = &matrix.upper[i] * &x[i + 1];
+					x[i] -= carried;
+				}
+				x[i] = &self.inverses[i] * &x[i];
+			}
+		}
+
+		let mut result = DVector::zeros(self.size());
+		for (i, part) in x.iter().enumerate() {
+			result.rows_mut(offsets[i], part.len()).copy_from(part);
+		}
+		Ok(result)
+	}
+
+	/// Estimate of the condition number `‖A‖₁ ‖A^-1‖₁`, see [`estimate_inverse_norm`](super::estimate_inverse_norm).
+	pub fn condition(&self) -> f64 {
+		let solve = |transposed: bool| move |x: &mut [f64]| {
+			let solution = self.sweep(&DVector::from_column_slice(x), transposed).unwrap();
+			x.copy_from_slice(solution.as_slice());
+		};
+		self.norm * super::estimate_inverse_norm(self.size(), solve(false), solve(true))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Blocks of sizes 2, 3, 1 and 2 that are diagonally dominant together with their neighbours.
+	fn matrix() -> BlockTridiagonal {
+		let sizes = [2, 3, 1, 2];
+		let block = |rows: usize, columns: usize, seed: usize| {
+			DMatrix::from_fn(rows, columns, |i, j| (((i + 2 * j + seed) * 7) % 5) as f64 * 0.3 - 0.6)
+		};
+		let diagonal = sizes.iter().enumerate().map(|(k, &n)| {
+			block(n, n, k) + DMatrix::identity(n, n) * 6.0
+		}).collect();
+		let lower = sizes.windows(2).enumerate().map(|(k, pair)| block(pair[1], pair[0], k + 1)).collect();
+		let upper = sizes.windows(2).enumerate().map(|(k, pair)| block(pair[0], pair[1], k + 2)).collect();
+		BlockTridiagonal::new(lower, diagonal, upper).unwrap()
+	}
+
+	#[test]
+	fn block_sweep_matches_dense_lu() {
+		let a = matrix();
+		let dense = a.to_dense();
+		let b = DVector::from_fn(a.size(), |i, _| 1.0 + i as f64);
+
+		let factorization = a.factorize().unwrap();
+		let x = factorization.solve(&b).unwrap();
+		assert!((&x - dense.clone().lu().solve(&b).unwrap()).norm() < 1e-12);
+		assert!((a.mul_vec(&x) - &b).norm() < 1e-12);
+
+		let transposed = factorization.solve_transposed(&b).unwrap();
+		assert!((transposed - dense.transpose().lu().solve(&b).unwrap()).norm() < 1e-12);
+	}
+
+	#[test]
+	fn condition_estimate_is_close_to_the_exact_one() {
+		let a = matrix();
+		let dense = a.to_dense();
+		let column_norm = |m: &DMatrix<f64>| m.column_iter().map(|column| column.abs().sum()).fold(0.0, f64::max);
+		let exact = column_norm(&dense) * column_norm(&dense.clone().try_inverse().unwrap());
+
+		assert!((a.norm_1() - column_norm(&dense)).abs() < 1e-12);
+		let estimate = a.factorize().unwrap().condition();
+		assert!(estimate <= exact * (1.0 + 1e-10) && estimate >= exact / 3.0, "{estimate} vs {exact}");
+	}
+
+	#[test]
+	fn mismatched_blocks_are_errors() {
+		let identity = |n: usize| DMatrix::identity(n, n);
+		let result = BlockTridiagonal::new(vec![DMatrix::zeros(2, 2)], vec![identity(2), identity(3)], vec![DMatrix::zeros(2, 3)]);
+		assert!(matches!(result, Err(Error::DimensionMismatch { expected: 3, found: 2 })));
+
+		let singular = BlockTridiagonal::new(vec![], vec![DMatrix::zeros(2, 2)], vec![]).unwrap();
+		assert!(matches!(singular.solve(&DVector::zeros(2)), Err(Error::SingularBlock { block: 0 })));
+	}
+}
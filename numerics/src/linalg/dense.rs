@@ -0,0 +1,488 @@
+//! Direct methods for dense matrices: Gaussian elimination with partial or complete pivoting,
+//! the Cholesky factorization and the Householder QR factorization.
+//!
+//! Every factorization solves any number of right-hand sides, and gives the determinant,
+//! the inverse and an estimate of the condition number in the 1-norm.
+
+use nalgebra::{DMatrix, DVector};
+use super::{estimate_inverse_norm, Error};
+
+/// How Gaussian elimination chooses its pivots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pivoting {
+	/// The largest entry of the column, exchanging rows.
+	Partial,
+	/// The largest entry of the remaining submatrix, exchanging rows and columns.
+	Complete,
+}
+
+/// Solves `Ax = b` by Gaussian elimination, through the factorization [`Lu`].
+pub fn gauss(a: &DMatrix<f64>, b: &DVector<f64>, pivoting: Pivoting) -> Result<DVector<f64>, Error> {
+	Lu::new(a, pivoting)?.solve(b)
+}
+
+/// The size of a square matrix.
+fn check_square(a: &DMatrix<f64>) -> Result<usize, Error> {
+	if a.nrows() == 0 {
+		return Err(Error::Empty);
+	}
+	if a.ncols() != a.nrows() {
+		return Err(Error::DimensionMismatch { expected: a.nrows(), found: a.ncols() });
+	}
+	Ok(a.nrows())
+}
+
+fn norm_1(a: &DMatrix<f64>) -> f64 {
+	a.column_iter().map(|column| column.iter().map(|value| value.abs()).sum::<f64>()).fold(0.0, f64::max)
+}
+
+/// Copies `b` and overwrites the copy with `solve`, checking its length first.
+fn solve_copy(n: usize, b: &DVector<f64>, solve: impl Fn(&mut [f64])) -> Result<DVector<f64>, Error> {
+	if b.len() != n {
+		return Err(Error::DimensionMismatch { expected: n, found: b.len() });
+	}
+	let mut x = b.clone();
+	solve(x.as_mut_slice());
+	Ok(x)
+}
+
+/// The columns of the identity matrix overwritten by `solve`.
+fn inverse(n: usize, solve: impl Fn(&mut [f64])) -> DMatrix<f64> {
+	let mut inverse = DMatrix::identity(n, n);
+	inverse.as_mut_slice().chunks_mut(n).for_each(solve);
+	inverse
+}
+
+/// The factorization `P A Q = L U` by Gaussian elimination, with `L` unit lower triangular and
+/// the permutations `P` of the rows and `Q` of the columns; `Q = I` with partial pivoting.
+#[derive(Clone, Debug)]
+pub struct Lu {
+	/// Multipliers of `L` below the diagonal and `U` on and above it.
+	factors: DMatrix<f64>,
+	/// Row exchanged with row `k` at step `k`.
+	rows: Vec<usize>,
+	/// Column exchanged with column `k` at step `k`.
+	columns: Vec<usize>,
+	/// 1-norm of the factorized matrix.
+	norm: f64,
+}
+
+impl Lu {
+	/// Fails with [`Error::ZeroPivot`] when no pivot is left that is nonzero relative to the matrix,
+	/// which with complete pivoting means that the rank of `a` is the row of the error.
+	pub fn new(a: &DMatrix<f64>, pivoting: Pivoting) -> Result<Self, Error> {
+		let n = check_square(a)?;
+		let mut factors = a.clone();
+		let scale = a.amax();
+		let (mut rows, mut columns) = (Vec::with_capacity(n), Vec::with_capacity(n));
+
+		for k in 0..n {
+			let larger = |&(i, j): &(usize, usize), &(p, q): &(usize, usize)| factors[(i, j)].abs().total_cmp(&factors[(p, q)].abs());
+			let (pivot_row, pivot_column) = match pivoting {
+				Pivoting::Partial => (k..n).map(|i| (i, k)).max_by(larger).unwrap(),
+				Pivoting::Complete => (k..n).flat_map(|i| (k..n).map(move |j| (i, j))).max_by(larger).unwrap(),
+			};
+			let pivot = factors[(pivot_row, pivot_column)];
+			if pivot.abs() <= f64::EPSILON * scale || !pivot.is_finite() {
+				return Err(Error::ZeroPivot { row: k });
+			}
+
+			factors.swap_rows(k, pivot_row);
+			factors.swap_columns(k, pivot_column);
+			rows.push(pivot_row);
+			columns.push(pivot_column);
+
+			for i in k + 1..n {
+				let factor = factors[(i, k)] / pivot;
+				factors[(i, k)] = factor;
+				for j in k + 1..n {
+					let value = factors[(k, j)];
+					factors[(i, j)] -= factor * value;
+				}
+			}
+		}
+
+		Ok(Self { factors, rows, columns, norm: norm_1(a) })
+	}
+
+	pub fn size(&self) -> usize {
+		self.rows.len()
+	}
+
+	/// Unit lower triangular `L`.
+	pub fn l(&self) -> DMatrix<f64> {
+		let mut l = self.factors.lower_triangle();
+		l.fill_diagonal(1.0);
+		l
+	}
+
+	/// Upper triangular `U`.
+	pub fn u(&self) -> DMatrix<f64> {
+		self.factors.upper_triangle()
+	}
+
+	pub fn determinant(&self) -> f64 {
+		let exchanges = |pivots: &[usize]| pivots.iter().enumerate().filter(|&(k, &exchanged)| exchanged != k).count();
+		let exchanges = exchanges(&self.rows) + exchanges(&self.columns);
+		let sign = if exchanges % 2 == 0 { 1.0 } else { -1.0 };
+		sign * self.factors.diagonal().product()
+	}
+
+	/// Overwrites `x` with `A^-1 x`.
+	fn substitute(&self, x: &mut [f64]) {
+		let (factors, n) = (&self.factors, self.size());
+		for (k, &row) in self.rows.iter().enumerate() {
+			x.swap(k, row);
+		}
+		for i in 0..n {
+			let sum: f64 = (0..i).map(|j| factors[(i, j)] * x[j]).sum();
+			x[i] -= sum;
+		}
+		for i in (0..n).rev() {
+			let sum: f64 = (i + 1..n).map(|j| factors[(i, j)] * x[j]).sum();
+			x[i] = (x[i] - sum) / factors[(i, i)];
+		}
+		for (k, &column) in self.columns.iter().enumerate().rev() {
+			x.swap(k, column);
+		}
+	}
+
+	/// Overwrites `x` with `A^-T x`.
+	fn substitute_transposed(&self, x: &mut [f64]) {
+		let (factors, n) = (&self.factors, self.size());
+		for (k, &column) in self.columns.iter().enumerate() {
+			x.swap(k, column);
+		}
+		for i in 0..n {
+			let sum: f64 = (0..i).map(|j| factors[(j, i)] * x[j]).sum();
+			x[i] = (x[i] - sum) / factors[(i, i)];
+		}
+		for i in (0..n).rev() {
+			let sum: f64 = (i + 1..n).map(|j| factors[(j, i)] * x[j]).sum();
+			x[i] -= sum;
+		}
+		for (k, &row) in self.rows.iter().enumerate().rev() {
+			x.swap(k, row);
+		}
+	}
+
+	pub fn solve(&self, b: &DVector<f64>) -> Result<DVector<f64>, Error> {
+		solve_copy(self.size(), b, |x| self.substitute(x))
+	}
+
+	/// Solves `A^T x = b` with the same factors.
+	pub fn solve_transposed(&self, b: &DVector<f64>) -> Result<DVector<f64>, Error> {
+		solve_copy(self.size(), b, |x| self.substitute_transposed(x))
+	}
+
+	pub fn inverse(&self) -> DMatrix<f64> {
+		inverse(self.size(), |x| self.substitute(x))
+	}
+
+	/// Estimate of the condition number `‖A‖₁ ‖A^-1‖₁`, see [`estimate_inverse_norm`].
+	pub fn condition(&self) -> f64 {
+		self.norm * estimate_inverse_norm(self.size(), |x| self.substitute(x), |x| self.substitute_transposed(x))
+	}
+}
+
+/// The factorization `A = L L^T` of a symmetric positive definite matrix, with `L` lower triangular.
+#[derive(Clone, Debug)]
+pub struct Cholesky {
+	l: DMatrix<f64>,
+	/// 1-norm of the factorized matrix.
+	norm: f64,
+}
+
+impl Cholesky {
+	/// Reads only the lower triangle of `a`, and fails with [`Error::NotPositiveDefinite`]
+	/// when a pivot `L[i][i]²` is not positive.
+	pub fn new(a: &DMatrix<f64>) -> Result<Self, Error> {
+		let n = check_square(a)?;
+		let mut l = DMatrix::zeros(n, n);
+
+		for j in 0..n {
+			let sum: f64 = (0..j).map(|k| l[(j, k)] * l[(j, k)]).sum();
+			let pivot = a[(j, j)] - sum;
+			if pivot <= f64::EPSILON * a[(j, j)].abs() || !pivot.is_finite() {
+				return Err(Error::NotPositiveDefinite { row: j });
+			}
+			l[(j, j)] = pivot.sqrt();
+
+			for i in j + 1..n {
+				let sum: f64 = (0..j).map(|k| l[(i, k)] * l[(j, k)]).sum();
+				l[(i, j)] = (a[(i, j)] - sum) / l[(j, j)];
+			}
+		}
+
+		// the symmetric matrix, of which only the lower triangle was read
+		let norm = (0..n).map(|j| (0..n).map(|i| a[(i.max(j), i.min(j))].abs()).sum::<f64>()).fold(0.0, f64::max);
+		Ok(Self { l, norm })
+	}
+
+	pub fn size(&self) -> usize {
+		self.l.nrows()
+	}
+
+	pub fn l(&self) -> &DMatrix<f64> {
+		&self.l
+	}
+
+	/// Product of the squared diagonal of `L`.
+	pub fn determinant(&self) -> f64 {
+		self.l.diagonal().iter().map(|value| value * value).product()
+	}
+
+	/// Overwrites `x` with `A^-1 x`.
+	fn substitute(&self, x: &mut [f64]) {
+		let (l, n) = (&self.l, self.size());
+		for i in 0..n {
+			let sum: f64 = (0..i).map(|j| l[(i, j)] * x[j]).sum();
+			x[i] = (x[i] - sum) / l[(i, i)];
+		}
+		for i in (0..n).rev() {
+			let sum: f64 = (i + 1..n).map(|j| l[(j, i)] * x[j]).sum();
+			x[i] = (x[i] - sum) / l[(i, i)];
+		}
+	}
+
+	pub fn solve(&self, b: &DVector<f64>) -> Result<DVector<f64>, Error> {
+		solve_copy(self.size(), b, |x| self.substitute(x))
+	}
+
+	pub fn inverse(&self) -> DMatrix<f64> {
+		inverse(self.size(), |x| self.substitute(x))
+	}
+
+	/// Estimate of the condition number `‖A‖₁ ‖A^-1‖₁`, see [`estimate_inverse_norm`].
+	pub fn condition(&self) -> f64 {
+		self.norm * estimate_inverse_norm(self.size(), |x| self.substitute(x), |x| self.substitute(x))
+	}
+}
+
+/// The factorization `A = Q R` by Householder reflections, with `Q` orthogonal and `R` upper triangular.
+///
+/// It needs twice the work of [`Lu`], but never amplifies rounding errors by growing entries.
+#[derive(Clone, Debug)]
+pub struct Qr {
+	/// `R` on and above the diagonal, and below it the reflection vectors `v` scaled to `v[k] = 1`.
+	factors: DMatrix<f64>,
+	/// Factors `τ` of the reflections `I - τ v v^T`.
+	taus: Vec<f64>,
+	/// 1-norm of the factorized matrix.
+	norm: f64,
+}
+
+impl Qr {
+	/// Fails with [`Error::ZeroPivot`] when a diagonal entry of `R` vanishes relative to the matrix.
+	pub fn new(a: &DMatrix<f64>) -> Result<Self, Error> {
+		let n = check_square(a)?;
+		let mut factors = a.clone();
+		let scale = a.amax();
+		let mut taus = Vec::with_capacity(n);
+
+		for k in 0..n {
+			let head = factors[(k, k)];
+			let length = factors.view((k, k), (n - k, 1)).norm();
+			// the sign of alpha opposite to the head avoids cancellation in head - alpha
+			let alpha = if head > 0.0 { -length } else { length };
+			if alpha.abs() <= f64::EPSILON * scale || !alpha.is_finite() {
+				return Err(Error::ZeroPivot { row: k });
+			}
+
+			let tau = (alpha - head) / alpha;
+			for i in k + 1..n {
+				factors[(i, k)] /= head - alpha;
+			}
+			factors[(k, k)] = alpha;
+
+			for j in k + 1..n {
+				let dot = factors[(k, j)] + (k + 1..n).map(|i| factors[(i, k)] * factors[(i, j)]).sum::<f64>();
+				let step = tau * dot;
+				factors[(k, j)] -= step;
+				for i in k + 1..n {
+					let v = factors[(i, k)];
+					factors[(i, j)] -= step * v;
+				}
+			}
+			taus.push(tau);
+		}
+
+		Ok(Self { factors, taus, norm: norm_1(a) })
+	}
+
+	pub fn size(&self) -> usize {
+		self.taus.len()
+	}
+
+	/// Upper triangular `R`.
+	pub fn r(&self) -> DMatrix<f64> {
+		self.factors.upper_triangle()
+	}
+
+	/// Orthogonal `Q`, the product of the reflections.
+	pub fn q(&self) -> DMatrix<f64> {
+		let n = self.size();
+		let mut q = DMatrix::identity(n, n);
+		q.as_mut_slice().chunks_mut(n).for_each(|column| self.reflect_back(column));
+		q
+	}
+
+	/// Every reflection has the determinant -1.
+	pub fn determinant(&self) -> f64 {
+		let sign = if self.size().is_multiple_of(2) { 1.0 } else { -1.0 };
+		sign * self.factors.diagonal().product()
+	}
+
+	/// Applies the reflection `k` to `x`.
+	fn reflect(&self, k: usize, x: &mut [f64]) {
+		let (factors, n) = (&self.factors, self.size());
+		let dot = x[k] + (k + 1..n).map(|i| factors[(i, k)] * x[i]).sum::<f64>();
+		let step = self.taus[k] * dot;
+		x[k] -= step;
+		for i in k + 1..n {
+			x[i] -= step * factors[(i, k)];
+		}
+	}
+
+	/// Overwrites `x` with `Q x`.
+	fn reflect_back(&self, x: &mut [f64]) {
+		(0..self.size()).rev().for_each(|k| self.reflect(k, x));
+	}
+
+	/// Overwrites `x` with `A^-1 x = R^-1 Q^T x`.
+	fn substitute(&self, x: &mut [f64]) {
+		let (factors, n) = (&self.factors, self.size());
+		(0..n).for_each(|k| self.reflect(k, x));
+		for i in (0..n).rev() {
+			let sum: f64 = (i + 1..n).map(|j| factors[(i, j)] * x[j]).sum();
+			x[i] = (x[i] - sum) / factors[(i, i)];
+		}
+	}
+
+	/// Overwrites `x` with `A^-T x = Q R^-T x`.
+	fn substitute_transposed(&self, x: &mut [f64]) {
+		let (factors, n) = (&self.factors, self.size());
+		for i in 0..n {
+			let sum: f64 = (0..i).map(|j| factors[(j, i)] * x[j]).sum();
+			x[i] = (x[i] - sum) / factors[(i, i)];
+		}
+		self.reflect_back(x);
+	}
+
+	pub fn solve(&self, b: &DVector<f64>) -> Result<DVector<f64>, Error> {
+		solve_copy(self.size(), b, |x| self.substitute(x))
+	}
+
+	pub fn inverse(&self) -> DMatrix<f64> {
+		inverse(self.size(), |x| self.substitute(x))
+	}
+
+	/// Estimate of the condition number `‖A‖₁ ‖A^-1‖₁`, see [`estimate_inverse_norm`].
+	pub fn condition(&self) -> f64 {
+		self.norm * estimate_inverse_norm(self.size(), |x| self.substitute(x), |x| self.substitute_transposed(x))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A non-symmetric matrix with a zero in the first pivot position, so every method needs its pivoting.
+	fn matrix(n: usize) -> DMatrix<f64> {
+		DMatrix::from_fn(n, n, |i, j| if i == 0 && j == 0 { 0.0 } else { ((i * 5 + j * 3 + 1) % 7) as f64 - 3.0 + if i == j { 4.0 } else { 0.0 } })
+	}
+
+	/// `M^T M + I`, symmetric positive definite.
+	fn spd(n: usize) -> DMatrix<f64> {
+		let m = matrix(n);
+		m.transpose() * m + DMatrix::identity(n, n)
+	}
+
+	fn rhs(n: usize) -> DVector<f64> {
+		DVector::from_fn(n, |i, _| (i as f64 + 1.0).sqrt())
+	}
+
+	fn exact_condition(a: &DMatrix<f64>) -> f64 {
+		norm_1(a) * norm_1(&a.clone().try_inverse().unwrap())
+	}
+
+	fn assert_close(actual: &DMatrix<f64>, expected: &DMatrix<f64>, tolerance: f64) {
+		assert!((actual - expected).amax() <= tolerance * expected.amax().max(1.0), "{actual} vs {expected}");
+	}
+
+	fn assert_condition(estimate: f64, a: &DMatrix<f64>) {
+		let exact = exact_condition(a);
+		assert!(estimate <= exact * (1.0 + 1e-10) && estimate >= exact / 3.0, "{estimate} vs {exact}");
+	}
+
+	#[test]
+	fn gauss_matches_lu_with_both_pivotings() {
+		for n in [2, 6, 12] {
+			let (a, b) = (matrix(n), rhs(n));
+			let exact = a.clone().lu().solve(&b).unwrap();
+			for pivoting in [Pivoting::Partial, Pivoting::Complete] {
+				let x = gauss(&a, &b, pivoting).unwrap();
+				assert!((x - &exact).norm() < 1e-10 * exact.norm(), "n = {n}, {pivoting:?}");
+			}
+		}
+	}
+
+	#[test]
+	fn lu_factors_determinant_inverse_and_condition() {
+		let a = matrix(6);
+		let b = rhs(6);
+		for pivoting in [Pivoting::Partial, Pivoting::Complete] {
+			let lu = Lu::new(&a, pivoting).unwrap();
+			assert!((lu.determinant() - a.determinant()).abs() < 1e-9 * a.determinant().abs());
+			// |det| of the product of the factors, as the permutations only change the sign
+			assert!(((lu.l() * lu.u()).determinant().abs() - a.determinant().abs()).abs() < 1e-9 * a.determinant().abs());
+			assert_close(&(lu.inverse() * &a), &DMatrix::identity(6, 6), 1e-12);
+
+			let transposed = lu.solve_transposed(&b).unwrap();
+			assert!((a.transpose() * transposed - &b).norm() < 1e-10);
+			assert_condition(lu.condition(), &a);
+		}
+	}
+
+	#[test]
+	fn cholesky_matches_nalgebra() {
+		let a = spd(7);
+		let cholesky = Cholesky::new(&a).unwrap();
+		assert_close(&(cholesky.l() * cholesky.l().transpose()), &a, 1e-13);
+		assert_close(cholesky.l(), &a.clone().cholesky().unwrap().l(), 1e-12);
+
+		let b = rhs(7);
+		assert!((cholesky.solve(&b).unwrap() - a.clone().lu().solve(&b).unwrap()).norm() < 1e-10);
+		assert!((cholesky.determinant() - a.determinant()).abs() < 1e-9 * a.determinant());
+		assert_close(&(cholesky.inverse() * &a), &DMatrix::identity(7, 7), 1e-10);
+		assert_condition(cholesky.condition(), &a);
+	}
+
+	#[test]
+	fn qr_is_orthogonal_times_triangular() {
+		let a = matrix(6);
+		let qr = Qr::new(&a).unwrap();
+		let (q, r) = (qr.q(), qr.r());
+		assert_close(&(q.transpose() * &q), &DMatrix::identity(6, 6), 1e-13);
+		assert_close(&(&q * &r), &a, 1e-13);
+		assert_eq!(r, r.upper_triangle());
+
+		let b = rhs(6);
+		assert!((qr.solve(&b).unwrap() - a.clone().lu().solve(&b).unwrap()).norm() < 1e-10);
+		assert!((qr.determinant() - a.determinant()).abs() < 1e-9 * a.determinant().abs());
+		assert_close(&(qr.inverse() * &a), &DMatrix::identity(6, 6), 1e-12);
+		assert_condition(qr.condition(), &a);
+	}
+
+	#[test]
+	fn rank_deficient_and_indefinite_matrices_are_errors() {
+		// rank 2: the third row is the sum of the first two
+		let a = DMatrix::from_row_slice(3, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 5.0, 7.0, 9.0]);
+		assert!(matches!(Lu::new(&a, Pivoting::Complete), Err(Error::ZeroPivot { row: 2 })));
+		assert!(matches!(Qr::new(&a), Err(Error::ZeroPivot { .. })));
+
+		let indefinite = DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 2.0, 1.0]);
+		assert!(matches!(Cholesky::new(&indefinite), Err(Error::NotPositiveDefinite { row: 1 })));
+		assert!(matches!(Lu::new(&DMatrix::zeros(2, 3), Pivoting::Partial), Err(Error::DimensionMismatch { .. })));
+	}
+}
@@ -0,0 +1,243 @@
+//! Whether the stationary iterations converge for a system, judged by the iteration matrix `G` of `x = Gx + f`.
+//!
+//! `‖G‖ < 1` in any norm guarantees convergence and bounds the error, while the spectral radius `ρ(G) < 1`
+//! is necessary and sufficient but says nothing about the first iterations.
+
+use nalgebra::DMatrix;
+use super::krylov::{norm, LinearOperator};
+use super::{jacobi_form, Error, MatrixFree};
+
+/// Iterations of [`power_iteration`] used by the diagnostics.
+const POWER_ITERATIONS: usize = 500;
+
+/// How the diagonal of a matrix compares to the rest of its rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dominance {
+	/// `|a[i][i]| > Σ |a[i][j]|` over `j ≠ i` in every row; the Jacobi and Seidel methods converge.
+	Strict,
+	/// `≥` in every row and `>` in at least one.
+	Weak,
+	None,
+}
+
+pub fn diagonal_dominance(a: &DMatrix<f64>) -> Dominance {
+	let margins: Vec<f64> = a.row_iter().enumerate().map(|(i, row)| {
+		let diagonal = row[i].abs();
+		2.0 * diagonal - row.iter().map(|value| value.abs()).sum::<f64>()
+	}).collect();
+
+	if margins.iter().all(|&margin| margin > 0.0) {
+		Dominance::Strict
+	} else if margins.iter().all(|&margin| margin >= 0.0) && margins.iter().any(|&margin| margin > 0.0) {
+		Dominance::Weak
+	} else {
+		Dominance::None
+	}
+}
+
+/// Estimate of the spectral radius of `a` as the average growth `‖A^k x‖^(1/k)` over `iterations` steps.
+///
+/// Unlike the Rayleigh quotient, the average growth also converges when the largest eigenvalues are
+/// a complex pair or differ only in sign, although slowly when the next eigenvalue is almost as large.
+pub fn power_iteration(a: &impl LinearOperator, iterations: usize) -> f64 {
+	let n = a.size();
+	// a start that is unlikely to miss an eigenvector
+	let mut x: Vec<f64> = (0..n).map(|i| 1.0 + (i as f64 * 0.618_034).fract()).collect();
+	let length = norm(&x);
+	x.iter_mut().for_each(|x| *x /= length);
+	let mut y = vec![0.0; n];
+
+	// the first half only brings x towards the dominant eigenvectors
+	let (mut logarithms, mut counted) = (0.0, 0);
+	for k in 0..iterations {
+		a.apply(&x, &mut y);
+		let growth = norm(&y);
+		if growth == 0.0 || !growth.is_finite() {
+			return if growth == 0.0 { 0.0 } else { f64::INFINITY };
+		}
+		if 2 * k >= iterations {
+			logarithms += growth.ln();
+			counted += 1;
+		}
+		x.iter_mut().zip(&y).for_each(|(x, y)| *x = y / growth);
+	}
+
+	if counted == 0 { 0.0 } else { (logarithms / counted as f64).exp() }
+}
+
+/// Norms and spectral radius of the Jacobi iteration matrix `G = -D^-1 (L + U)` of a system.
+#[derive(Clone, Debug)]
+pub struct Diagnostics {
+	g: DMatrix<f64>,
+	pub dominance: Dominance,
+	/// Largest sum of absolute values in a row.
+	pub norm_inf: f64,
+	/// Largest sum of absolute values in a column.
+	pub norm_1: f64,
+	/// Largest singular value.
+	pub norm_2: f64,
+	pub frobenius: f64,
+	/// Estimated by power iteration.
+	pub spectral_radius: f64,
+}
+
+impl Diagnostics {
+	/// Fails unless `a` is square with a nonzero diagonal.
+	pub fn new(a: &DMatrix<f64>) -> Result<Self, Error> {
+		if a.nrows() == 0 {
+			return Err(Error::Empty);
+		}
+		if a.ncols() != a.nrows() {
+			return Err(Error::DimensionMismatch { expected: a.nrows(), found: a.ncols() });
+		}
+		if let Some(row) = (0..a.nrows()).find(|&i| a[(i, i)] == 0.0) {
+			return Err(Error::ZeroPivot { row });
+		}
+
+		let (g, _) = jacobi_form(a, &a.column(0).into_owned());
+		let norm_inf = g.row_iter().map(|row| row.iter().map(|value| value.abs()).sum::<f64>()).fold(0.0, f64::max);
+		let norm_1 = g.column_iter().map(|column| column.iter().map(|value| value.abs()).sum::<f64>()).fold(0.0, f64::max);
+		let norm_2 = g.singular_values().max();
+		let spectral_radius = power_iteration(&g, POWER_ITERATIONS);
+
+		Ok(Self { dominance: diagonal_dominance(a), norm_inf, norm_1, norm_2, frobenius: g.norm(), spectral_radius, g })
+	}
+
+	/// The smallest of the four norms, which guarantees convergence and bounds the error when it is below 1.
+	pub fn best_norm(&self) -> f64 {
+		[self.norm_inf, self.norm_1, self.norm_2, self.frobenius].into_iter().fold(f64::INFINITY, f64::min)
+	}
+
+	/// Whether a norm below 1 guarantees the convergence of the Jacobi method for any initial guess.
+	pub fn is_guaranteed(&self) -> bool {
+		self.best_norm() < 1.0
+	}
+
+	/// Estimate of the spectral radius of the relaxation method with parameter `omega`,
+	/// `(I - ω G_L)^-1 ((1 - ω) I + ω G_U)` where `G_L` and `G_U` are the parts of `G` below and above the diagonal.
+	///
+	/// It is at least `|ω - 1|` (Kahan), so the method diverges for `ω` outside `(0, 2)`;
+	/// the bound is applied to the estimate, which converges slowly near `ω = 2`.
+	pub fn relaxation_radius(&self, omega: f64) -> f64 {
+		let g = &self.g;
+		let n = g.nrows();
+		let iteration = MatrixFree::new(n, |x: &[f64], y: &mut [f64]| {
+			for i in 0..n {
+				let lower: f64 = (0..i).map(|j| g[(i, j)] * y[j]).sum();
+				let upper: f64 = (i + 1..n).map(|j| g[(i, j)] * x[j]).sum();
+				y[i] = (1.0 - omega) * x[i] + omega * (lower + upper);
+			}
+		});
+		power_iteration(&iteration, POWER_ITERATIONS).max((omega - 1.0).abs())
+	}
+
+	/// Explanations of why convergence is not guaranteed, empty when a norm is below 1.
+	pub fn warnings(&self) -> Vec<String> {
+		let mut warnings = Vec::new();
+		if self.is_guaranteed() {
+			return warnings;
+		}
+
+		if self.spectral_radius >= 1.0 {
+			warnings.push(format!(
+				"the Jacobi method diverges: the spectral radius of its iteration matrix is {:.6} ≥ 1",
+				self.spectral_radius,
+			));
+		} else {
+			warnings.push(format!(
+				"every norm of the iteration matrix is at least 1 (the smallest is {:.6}), so the error is not bounded; \
+				the Jacobi method converges only because the spectral radius is {:.6} < 1",
+				self.best_norm(),
+				self.spectral_radius,
+			));
+		}
+		if self.dominance != Dominance::Strict {
+			warnings.push("the matrix is not strictly diagonally dominant, so the Seidel method may diverge as well".to_string());
+		}
+		warnings
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use nalgebra::DVector;
+	use crate::linalg::{seidel_method, simple_iterations_method};
+
+	/// Every norm of its Jacobi matrix is at least 2, but the spectral radius is about 0.823.
+	fn unbounded_but_convergent() -> DMatrix<f64> {
+		DMatrix::from_row_slice(3, 3, &[
+			1.0, 2.0, 1.0,
+			0.1, 1.0, 0.2,
+			0.3, 0.1, 1.0,
+		])
+	}
+
+	/// The largest modulus of the eigenvalues by nalgebra.
+	fn eigenvalue_radius(g: &DMatrix<f64>) -> f64 {
+		g.complex_eigenvalues().iter().map(|value| value.re.hypot(value.im)).fold(0.0, f64::max)
+	}
+
+	#[test]
+	fn spectral_radius_below_one_with_norms_above() {
+		let a = unbounded_but_convergent();
+		let diagnostics = Diagnostics::new(&a).unwrap();
+		assert!(diagnostics.best_norm() >= 1.0, "{diagnostics:?}");
+		assert!(!diagnostics.is_guaranteed());
+		assert_eq!(diagnostics.dominance, Dominance::None);
+
+		let (g, _) = jacobi_form(&a, &DVector::zeros(3));
+		let exact = eigenvalue_radius(&g);
+		assert!((exact - 0.823).abs() < 1e-3, "{exact}");
+		assert!((power_iteration(&g, 500) - exact).abs() < 1e-6);
+		assert!((diagnostics.spectral_radius - exact).abs() < 1e-6);
+
+		let warnings = diagnostics.warnings();
+		assert_eq!(warnings.len(), 2);
+		assert!(warnings[0].contains("converges only because"), "{}", warnings[0]);
+
+		// and the methods do converge
+		let b = DVector::from_vec(vec![1.0, 2.0, 3.0]);
+		let exact = a.clone().lu().solve(&b).unwrap();
+		for solution in [simple_iterations_method(&a, &b, 1e-10).unwrap(), seidel_method(&a, &b, 1e-10, 1.0).unwrap()] {
+			assert!((&solution.x - &exact).norm() < 1e-8);
+		}
+	}
+
+	#[test]
+	fn power_iteration_matches_eigenvalues() {
+		let a = DMatrix::from_row_slice(3, 3, &[
+			4.0, 1.0, -2.0,
+			0.5, 3.0, 1.0,
+			-1.0, 2.0, -5.0,
+		]);
+		assert!((power_iteration(&a, 500) - eigenvalue_radius(&a)).abs() < 1e-6);
+		assert_eq!(power_iteration(&DMatrix::<f64>::zeros(2, 2), 10), 0.0);
+	}
+
+	#[test]
+	fn dominance() {
+		let strict = DMatrix::from_row_slice(2, 2, &[3.0, -2.0, 1.0, -2.0]);
+		let weak = DMatrix::from_row_slice(2, 2, &[3.0, -3.0, 1.0, -2.0]);
+		let none = DMatrix::from_row_slice(2, 2, &[1.0, 3.0, 1.0, 2.0]);
+		// equal margins in every row are not dominance
+		let equal = DMatrix::from_row_slice(2, 2, &[1.0, -1.0, -1.0, 1.0]);
+		assert_eq!(diagonal_dominance(&strict), Dominance::Strict);
+		assert_eq!(diagonal_dominance(&weak), Dominance::Weak);
+		assert_eq!(diagonal_dominance(&none), Dominance::None);
+		assert_eq!(diagonal_dominance(&equal), Dominance::None);
+
+		let diagnostics = Diagnostics::new(&strict).unwrap();
+		assert!(diagnostics.is_guaranteed());
+		assert!(diagnostics.warnings().is_empty());
+		assert!((diagnostics.norm_inf - 2.0 / 3.0).abs() < 1e-15);
+	}
+
+	#[test]
+	fn invalid_matrices() {
+		assert!(matches!(Diagnostics::new(&DMatrix::zeros(0, 0)), Err(Error::Empty)));
+		assert!(matches!(Diagnostics::new(&DMatrix::from_element(2, 3, 1.0)), Err(Error::DimensionMismatch { .. })));
+		let zero = DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 3.0, 0.0]);
+		assert!(matches!(Diagnostics::new(&zero), Err(Error::ZeroPivot { row: 1 })));
+	}
+}
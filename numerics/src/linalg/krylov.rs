@@ -0,0 +1,434 @@
+//! Krylov subspace methods: conjugate gradients, BiCGSTAB and restarted GMRES.
+//!
+//! They only multiply vectors by the matrix, so they work on dense, sparse and matrix-free operators alike,
+//! and take a [`Preconditioner`]; pass [`Identity`](super::Identity) for none.
+
+use std::fmt::{Display, Formatter};
+use nalgebra::{DMatrix, DVector, DVectorView, DVectorViewMut};
+use super::{Csr, Error, Preconditioner};
+
+/// A square matrix known only by its product with a vector.
+pub trait LinearOperator {
+	/// Number of rows and columns.
+	fn size(&self) -> usize;
+
+	/// Overwrites `y` with `A x`.
+	fn apply(&self, x: &[f64], y: &mut [f64]);
+}
+
+impl LinearOperator for DMatrix<f64> {
+	fn size(&self) -> usize {
+		self.nrows()
+	}
+
+	fn apply(&self, x: &[f64], y: &mut [f64]) {
+		let n = self.nrows();
+		DVectorViewMut::from_slice(y, n).gemv(1.0, self, &DVectorView::from_slice(x, self.ncols()), 0.0);
+	}
+}
+
+impl LinearOperator for Csr {
+	fn size(&self) -> usize {
+		self.nrows()
+	}
+
+	fn apply(&self, x: &[f64], y: &mut [f64]) {
+		for (i, y) in y.iter_mut().enumerate() {
+			let (columns, values) = self.row(i);
+			*y = columns.iter().zip(values).map(|(&j, value)| value * x[j]).sum();
+		}
+	}
+}
+
+/// An operator given by a function writing `A x` into its second argument, e.g. a stencil on a grid.
+pub struct MatrixFree<F: Fn(&[f64], &mut [f64])> {
+	size: usize,
+	apply: F,
+}
+
+impl<F: Fn(&[f64], &mut [f64])> MatrixFree<F> {
+	pub fn new(size: usize, apply: F) -> Self {
+		Self { size, apply }
+	}
+}
+
+impl<F: Fn(&[f64], &mut [f64])> LinearOperator for MatrixFree<F> {
+	fn size(&self) -> usize {
+		self.size
+	}
+
+	fn apply(&self, x: &[f64], y: &mut [f64]) {
+		(self.apply)(x, y)
+	}
+}
+
+/// Why an iterative method stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+	/// The relative residual `‖b - Ax‖ / ‖b‖` dropped to the tolerance.
+	Converged,
+	/// The iteration limit was reached first.
+	MaxIterations,
+	/// A division by zero ended the recurrence, e.g. conjugate gradients on a matrix that is not positive definite.
+	Breakdown,
+	/// The residual of a stationary iteration overflowed.
+	Diverged,
+}
+
+impl Display for StopReason {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			StopReason::Converged => write!(f, "converged"),
+			StopReason::MaxIterations => write!(f, "iteration limit reached"),
+			StopReason::Breakdown => write!(f, "breakdown"),
+			StopReason::Diverged => write!(f, "diverged"),
+		}
+	}
+}
+
+/// Result of a Krylov method or a preconditioned stationary iteration.
+#[derive(Clone, Debug)]
+pub struct IterativeSolution {
+	pub x: DVector<f64>,
+	pub iterations: usize,
+	/// `‖b - Ax‖` of the initial guess and after every iteration.
+	pub residuals: Vec<f64>,
+	pub stop: StopReason,
+}
+
+impl IterativeSolution {
+	/// The last residual norm.
+	pub fn residual(&self) -> f64 {
+		self.residuals.last().copied().unwrap_or(0.0)
+	}
+}
+
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+	x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+pub(super) fn norm(x: &[f64]) -> f64 {
+	dot(x, x).sqrt()
+}
+
+/// `y += alpha x`.
+fn axpy(alpha: f64, x: &[f64], y: &mut [f64]) {
+	y.iter_mut().zip(x).for_each(|(y, x)| *y += alpha * x);
+}
+
+/// Checks the system and returns the absolute tolerance on `‖r‖` for a relative `tolerance`.
+pub(super) fn check(a: &impl LinearOperator, b: &[f64], tolerance: f64) -> Result<f64, Error> {
+	if a.size() == 0 {
+		return Err(Error::Empty);
+	}
+	if b.len() != a.size() {
+		return Err(Error::DimensionMismatch { expected: a.size(), found: b.len() });
+	}
+	Ok(tolerance * norm(b))
+}
+
+/// Solves `Ax = b` for a symmetric positive definite `A` by the conjugate gradient method, starting from zero.
+///
+/// The preconditioner must be symmetric positive definite as well.
+/// Stops when `‖b - Ax‖ ≤ tolerance ‖b‖` or after `max_iterations`; in exact arithmetic it takes at most `n`.
+pub fn conjugate_gradient(
+	a: &impl LinearOperator,
+	preconditioner: &impl Preconditioner,
+	b: &[f64],
+	tolerance: f64,
+	max_iterations: usize,
+) -> Result<IterativeSolution, Error> {
+	let target = check(a, b, tolerance)?;
+	let n = b.len();
+
+	let mut x = vec![0.0; n];
+	let mut r = b.to_vec();
+	let mut z = vec![0.0; n];
+	preconditioner.apply(&r, &mut z);
+	let mut p = z.clone();
+	let mut q = vec![0.0; n];
+	let mut rho = dot(&r, &z);
+	let mut residuals = vec![norm(&r)];
+
+	let stop = loop {
+		if residuals[residuals.len() - 1] <= target {
+			break StopReason::Converged;
+		}
+		if residuals.len() > max_iterations {
+			break StopReason::MaxIterations;
+		}
+
+		a.apply(&p, &mut q);
+		let curvature = dot(&p, &q);
+		if curvature <= 0.0 || !curvature.is_finite() {
+			break StopReason::Breakdown;
+		}
+
+		let alpha = rho / curvature;
+		axpy(alpha, &p, &mut x);
+		axpy(-alpha, &q, &mut r);
+
+		preconditioner.apply(&r, &mut z);
+		let next = dot(&r, &z);
+		let beta = next / rho;
+		rho = next;
+		p.iter_mut().zip(&z).for_each(|(p, z)| *p = z + beta * *p);
+		residuals.push(norm(&r));
+	};
+
+	Ok(IterativeSolution { x: DVector::from_vec(x), iterations: residuals.len() - 1, residuals, stop })
+}
+
+/// Solves `Ax = b` for any non-singular `A` by the stabilized biconjugate gradient method of van der Vorst.
+///
+/// Every iteration takes two products with `A` and two with `M^-1`, which is applied from the right,
+/// so the residuals are those of the original system. Stops as [`conjugate_gradient`] does.
+pub fn bicgstab(
+	a: &impl LinearOperator,
+	preconditioner: &impl Preconditioner,
+	b: &[f64],
+	tolerance: f64,
+	max_iterations: usize,
+) -> Result<IterativeSolution, Error> {
+	let target = check(a, b, tolerance)?;
+	let n = b.len();
+
+	let mut x = vec![0.0; n];
+	let mut r = b.to_vec();
+	let shadow = r.clone();
+	let (mut p, mut v, mut s, mut t) = (vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+	// the preconditioned directions M^-1 p and M^-1 s
+	let (mut p_hat, mut s_hat) = (vec![0.0; n], vec![0.0; n]);
+	let (mut rho, mut alpha, mut omega) = (1.0, 1.0, 1.0);
+	let mut residuals = vec![norm(&r)];
+
+	let stop = loop {
+		if residuals[residuals.len() - 1] <= target {
+			break StopReason::Converged;
+		}
+		if residuals.len() > max_iterations {
+			break StopReason::MaxIterations;
+		}
+
+		let next = dot(&shadow, &r);
+		if next == 0.0 || omega == 0.0 {
+			break StopReason::Breakdown;
+		}
+		let beta = (next / rho) * (alpha / omega);
+		rho = next;
+		for i in 0..n {
+			p[i] = r[i] + beta * (p[i] - omega * v[i]);
+		}
+
+		preconditioner.apply(&p, &mut p_hat);
+		a.apply(&p_hat, &mut v);
+		let projection = dot(&shadow, &v);
+		if projection == 0.0 {
+			break StopReason::Breakdown;
+		}
+		alpha = rho / projection;
+		for i in 0..n {
+			s[i] = r[i] - alpha * v[i];
+		}
+
+		// the half step may already be good enough
+		if norm(&s) <= target {
+			axpy(alpha, &p_hat, &mut x);
+			residuals.push(norm(&s));
+			continue;
+		}
+
+		preconditioner.apply(&s, &mut s_hat);
+		a.apply(&s_hat, &mut t);
+		let squares = dot(&t, &t);
+		omega = if squares == 0.0 { 0.0 } else { dot(&t, &s) / squares };
+		for i in 0..n {
+			x[i] += alpha * p_hat[i] + omega * s_hat[i];
+			r[i] = s[i] - omega * t[i];
+		}
+
+		let residual = norm(&r);
+		if !residual.is_finite() {
+			break StopReason::Breakdown;
+		}
+		residuals.push(residual);
+	};
+
+	Ok(IterativeSolution { x: DVector::from_vec(x), iterations: residuals.len() - 1, residuals, stop })
+}
+
+/// Solves `Ax = b` for any non-singular `A` by GMRES restarted every `restart` iterations.
+///
+/// Each cycle builds an orthonormal basis of the Krylov subspace by the Arnoldi process and minimizes the
+/// residual over it, so the residual never grows; the memory is `restart + 1` vectors of size `n`.
+/// The preconditioner is applied from the right, to the subspace of `A M^-1`.
+/// Stops as [`conjugate_gradient`] does, counting every Arnoldi step as an iteration.
+/// Fails with [`Error::ZeroRestart`] when `restart` is zero.
+pub fn gmres(
+	a: &impl LinearOperator,
+	preconditioner: &impl Preconditioner,
+	b: &[f64],
+	restart: usize,
+	tolerance: f64,
+	max_iterations: usize,
+) -> Result<IterativeSolution, Error> {
+	if restart == 0 {
+		return Err(Error::ZeroRestart);
+	}
+	let target = check(a, b, tolerance)?;
+	let n = b.len();
+	let m = restart;
+
+	let mut x = vec![0.0; n];
+	let mut r = b.to_vec();
+	let mut w = vec![0.0; n];
+	let mut z = vec![0.0; n];
+	let mut residuals = vec![norm(&r)];
+
+	let stop = 'cycles: loop {
+		let beta = norm(&r);
+		if beta <= target {
+			break StopReason::Converged;
+		}
+		if residuals.len() > max_iterations {
+			break StopReason::MaxIterations;
+		}
+
+		let mut basis: Vec<Vec<f64>> = vec![r.iter().map(|r| r / beta).collect()];
+		// the Hessenberg matrix column by column, already rotated to upper triangular form
+		let mut h: Vec<Vec<f64>> = Vec::with_capacity(m);
+		let mut rotations: Vec<(f64, f64)> = Vec::with_capacity(m);
+		let mut g = vec![0.0; m + 1];
+		g[0] = beta;
+
+		let mut steps = 0;
+		let mut reason = None;
+		while steps < m {
+			preconditioner.apply(&basis[steps], &mut z);
+			a.apply(&z, &mut w);
+			let mut column = vec![0.0; steps + 2];
+			for (i, v) in basis.iter().enumerate() {
+				column[i] = dot(&w, v);
+				axpy(-column[i], v, &mut w);
+			}
+			column[steps + 1] = norm(&w);
+
+			for (i, &(c, s)) in rotations.iter().enumerate() {
+				let (upper, lower) = (column[i], column[i + 1]);
+				column[i] = c * upper + s * lower;
+				column[i + 1] = -s * upper + c * lower;
+			}
+			let radius = column[steps].hypot(column[steps + 1]);
+			if radius == 0.0 || !radius.is_finite() {
+				reason = Some(StopReason::Breakdown);
+				break;
+			}
+			let (c, s) = (column[steps] / radius, column[steps + 1] / radius);
+			let next = column[steps + 1];
+			column[steps] = radius;
+			column.truncate(steps + 1);
+			rotations.push((c, s));
+			g[steps + 1] = -s * g[steps];
+			g[steps] *= c;
+
+			h.push(column);
+			steps += 1;
+			residuals.push(g[steps].abs());
+
+			if g[steps].abs() <= target || residuals.len() > max_iterations {
+				break;
+			}
+			// a lucky breakdown: the subspace is invariant and holds the solution
+			if next == 0.0 {
+				break;
+			}
+			basis.push(w.iter().map(|w| w / next).collect());
+		}
+
+		// back substitution for the coefficients of the basis vectors
+		let mut y = vec![0.0; steps];
+		for i in (0..steps).rev() {
+			let sum: f64 = (i + 1..steps).map(|j| h[j][i] * y[j]).sum();
+			y[i] = (g[i] - sum) / h[i][i];
+		}
+		let mut update = vec![0.0; n];
+		for (y, v) in y.iter().zip(&basis) {
+			axpy(*y, v, &mut update);
+		}
+		preconditioner.apply(&update, &mut z);
+		axpy(1.0, &z, &mut x);
+
+		// the true residual, which rounding may set apart from the estimate
+		a.apply(&x, &mut w);
+		r.iter_mut().zip(b).zip(&w).for_each(|((r, b), w)| *r = b - w);
+		if let Some(last) = residuals.last_mut() {
+			*last = norm(&r);
+		}
+		if let Some(reason) = reason {
+			break 'cycles reason;
+		}
+	};
+
+	Ok(IterativeSolution { x: DVector::from_vec(x), iterations: residuals.len() - 1, residuals, stop })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::Identity;
+
+	const TOLERANCE: f64 = 1e-10;
+
+	/// The solution of `A x = 1` by nalgebra's dense LU.
+	fn exact(a: &Csr) -> DVector<f64> {
+		a.to_dense().lu().solve(&DVector::from_element(a.nrows(), 1.0)).unwrap()
+	}
+
+	fn assert_solves(a: &Csr, solution: &IterativeSolution) {
+		let b = vec![1.0; a.nrows()];
+		assert_eq!(solution.stop, StopReason::Converged);
+		assert_eq!(solution.residuals.len(), solution.iterations + 1);
+		assert!(norm(&a.residual(solution.x.as_slice(), &b)) <= 1.01 * TOLERANCE * norm(&b));
+		let exact = exact(a);
+		assert!((&solution.x - &exact).norm() < 1e-7 * exact.norm());
+	}
+
+	#[test]
+	fn conjugate_gradient_on_poisson() {
+		let a = Csr::poisson_2d(12);
+		let solution = conjugate_gradient(&a, &Identity, &vec![1.0; a.nrows()], TOLERANCE, 1000).unwrap();
+		assert_solves(&a, &solution);
+		// far fewer than the n = 144 steps of exact arithmetic
+		assert!(solution.iterations < 60, "{}", solution.iterations);
+	}
+
+	#[test]
+	fn bicgstab_on_poisson_and_convection() {
+		for a in [Csr::poisson_2d(12), Csr::convection_diffusion_2d(12, 0.8)] {
+			let solution = bicgstab(&a, &Identity, &vec![1.0; a.nrows()], TOLERANCE, 1000).unwrap();
+			assert_solves(&a, &solution);
+		}
+	}
+
+	#[test]
+	fn gmres_on_poisson_and_convection() {
+		for a in [Csr::poisson_2d(12), Csr::convection_diffusion_2d(12, 0.8)] {
+			let b = vec![1.0; a.nrows()];
+			let restarted = gmres(&a, &Identity, &b, 10, TOLERANCE, 2000).unwrap();
+			assert_solves(&a, &restarted);
+			// the residual is minimized over a growing subspace within a cycle and never grows across restarts
+			assert!(restarted.residuals.windows(2).all(|pair| pair[1] <= pair[0] * (1.0 + 1e-12)));
+
+			let full = gmres(&a, &Identity, &b, a.nrows(), TOLERANCE, 2000).unwrap();
+			assert_solves(&a, &full);
+			assert!(full.iterations <= restarted.iterations);
+		}
+	}
+
+	#[test]
+	fn invalid_input_is_an_error() {
+		let a = Csr::poisson_2d(3);
+		assert!(matches!(gmres(&a, &Identity, &[1.0; 9], 0, TOLERANCE, 10), Err(Error::ZeroRestart)));
+		assert!(matches!(conjugate_gradient(&a, &Identity, &[1.0; 4], TOLERANCE, 10), Err(Error::DimensionMismatch { expected: 9, found: 4 })));
+	}
+}
@@ -0,0 +1,368 @@
+//! Linear systems `Ax = b`: direct methods for dense, tridiagonal, band and block tridiagonal matrices,
+//! stationary iterations on dense and sparse matrices, and preconditioned Krylov subspace methods.
+
+use std::fmt::{Display, Formatter};
+use nalgebra::{DMatrix, DVector};
+
+mod banded;
+mod block_tridiagonal;
+mod dense;
+mod diagnostics;
+mod krylov;
+mod preconditioner;
+mod sparse;
+mod tridiagonal;
+
+pub use banded::{Banded, BandedLu, Ldl};
+pub use block_tridiagonal::{BlockFactorization, BlockTridiagonal};
+pub use dense::{gauss, Cholesky, Lu, Pivoting, Qr};
+pub use diagnostics::{diagonal_dominance, power_iteration, Diagnostics, Dominance};
+pub use krylov::{bicgstab, conjugate_gradient, gmres, IterativeSolution, LinearOperator, MatrixFree, StopReason};
+pub use preconditioner::{preconditioned_iteration, Identity, Ilu0, Jacobi, Preconditioner, Ssor};
+pub use sparse::{sparse_gauss_seidel, sparse_jacobi, sparse_sor, Csr};
+pub use tridiagonal::{Factorization, Tridiagonal};
+
+/// Why a method could not solve a system.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+	/// A matrix without rows.
+	Empty,
+	/// A vector or band has `found` values where `expected` are needed.
+	DimensionMismatch { expected: usize, found: usize },
+	/// The pivot of `row` is zero compared to the coefficients it was computed from.
+	ZeroPivot { row: usize },
+	/// The matrix is singular, although no single pivot vanished.
+	Singular,
+	/// A pivot of the `L D L^T` factorization in `row` is not positive.
+	NotPositiveDefinite { row: usize },
+	/// The pivot block of the block sweep with index `block` cannot be inverted.
+	SingularBlock { block: usize },
+	/// A stationary iteration was refused, as the spectral radius of its iteration matrix is at least 1.
+	Diverges { spectral_radius: f64 },
+	/// The relaxation parameter lies outside `(0, 2)`.
+	Relaxation { omega: f64 },
+	/// GMRES was asked to restart before its first step.
+	ZeroRestart,
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::Empty => write!(f, "the matrix is empty"),
+			Error::DimensionMismatch { expected, found } => write!(f, "expected {expected} values, found {found}"),
+			Error::ZeroPivot { row } => write!(f, "zero pivot in row {}", row + 1),
+			Error::Singular => write!(f, "the matrix is singular"),
+			Error::SingularBlock { block } => write!(f, "the pivot block {} is singular", block + 1),
+			Error::NotPositiveDefinite { row } => write!(f, "the matrix is not positive definite, pivot {} is not positive", row + 1),
+			Error::Diverges { spectral_radius } => write!(f, "the iteration diverges, its spectral radius is {spectral_radius:.6} ≥ 1"),
+			Error::Relaxation { omega } => write!(f, "the relaxation parameter {omega} must lie in (0, 2)"),
+			Error::ZeroRestart => write!(f, "GMRES needs at least one step between restarts"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Brings `Ax = b` to the form `x = Gx + f` by dividing every row by its diagonal element.
+pub fn jacobi_form(a: &DMatrix<f64>, b: &DVector<f64>) -> (DMatrix<f64>, DVector<f64>) {
+	let n = a.nrows();
+
+	let g = DMatrix::from_fn(n, n, |i, j| {
+		if i == j {
+			0.0
+		} else {
+			-(a[(i, j)] / a[(i, i)])
+		}
+	});
+
+	let f = DVector::from_fn(n, |i, _| b[i] / a[(i, i)]);
+
+	(g, f)
+}
+
+/// Result of an iterative method.
+pub struct Solution {
+	pub x: DVector<f64>,
+	pub iterations: usize,
+	/// Distance between the last two approximations.
+	pub error: f64,
+}
+
+/// Tolerance on `‖x(k+1) - x(k)‖` that guarantees `‖x(k+1) - x*‖ ≤ epsilon` for `‖G‖ = norm < 1`.
+fn corrected_epsilon(norm: f64, epsilon: f64) -> f64 {
+	((1.0 - norm) / norm) * epsilon
+}
+
+/// Tolerance and iteration limit of a stationary iteration whose iteration matrix has the spectral radius `radius`.
+///
+/// The Euclidean norm of the steps is consistent with `‖G‖₂`, so [`corrected_epsilon`] applies when it is below 1.
+/// Otherwise the error only shrinks by about `radius` per step in the long run, after it may have grown at first,
+/// so the tolerance is `(1 - radius) * epsilon` and the iterations are limited to many times the expected count.
+fn stopping_rule(diagnostics: &Diagnostics, radius: f64, epsilon: f64) -> (f64, usize) {
+	if diagnostics.norm_2 < 1.0 {
+		(corrected_epsilon(diagnostics.norm_2, epsilon), usize::MAX)
+	} else {
+		let expected = epsilon.ln() / radius.ln();
+		((1.0 - radius) * epsilon, (100.0 * expected).max(1000.0) as usize)
+	}
+}
+
+/// Diagnostics of `Ax = b` for the stationary iterations.
+fn check_stationary(a: &DMatrix<f64>, b: &DVector<f64>) -> Result<Diagnostics, Error> {
+	let diagnostics = Diagnostics::new(a)?;
+	if b.len() != a.nrows() {
+		return Err(Error::DimensionMismatch { expected: a.nrows(), found: b.len() });
+	}
+	Ok(diagnostics)
+}
+
+/// Solves `Ax = b` by simple (Jacobi) iterations.
+///
+/// Refuses with [`Error::Diverges`] when the spectral radius of the iteration matrix is at least 1.
+pub fn simple_iterations_method(a: &DMatrix<f64>, b: &DVector<f64>, epsilon: f64) -> Result<Solution, Error> {
+	let diagnostics = check_stationary(a, b)?;
+	let radius = diagnostics.spectral_radius;
+	if !diagnostics.is_guaranteed() && radius >= 1.0 {
+		return Err(Error::Diverges { spectral_radius: radius });
+	}
+
+	let (g, f) = jacobi_form(a, b);
+	let (epsilon, max_iterations) = stopping_rule(&diagnostics, radius, epsilon);
+
+	let mut iterations = 0;
+	let mut error = f64::MAX;
+	let mut x = &g * &f + &f;
+
+	while error > epsilon && iterations < max_iterations {
+		iterations += 1;
+		let next = &g * &x + &f;
+		error = (&next - &x).norm();
+		x = next;
+	}
+
+	Ok(Solution { x, iterations, error })
+}
+
+/// Solves `Ax = b` by the relaxation method.
+///
+/// Every component of the Seidel update is blended with the previous approximation as
+/// `(1 - omega) * x[i] + omega * next[i]`, so `omega = 1` gives the plain Seidel method.
+/// Refuses with [`Error::Diverges`] when the spectral radius of the method is at least 1, as for any `omega` outside `(0, 2)`.
+pub fn seidel_method(a: &DMatrix<f64>, b: &DVector<f64>, epsilon: f64, omega: f64) -> Result<Solution, Error> {
+	let diagnostics = check_stationary(a, b)?;
+	let radius = diagnostics.relaxation_radius(omega);
+	if radius >= 1.0 {
+		return Err(Error::Diverges { spectral_radius: radius });
+	}
+
+	let (g, f) = jacobi_form(a, b);
+	let (epsilon, max_iterations) = stopping_rule(&diagnostics, radius, epsilon);
+	Ok(relaxation(&g, &f, epsilon, omega, max_iterations))
+}
+
+/// The relaxation method on `x = Gx + f`, stopping on `‖x(k+1) - x(k)‖ ≤ epsilon` or after `max_iterations`.
+fn relaxation(g: &DMatrix<f64>, f: &DVector<f64>, epsilon: f64, omega: f64, max_iterations: usize) -> Solution {
+	let n = f.len();
+
+	let mut iterations = 0;
+	let mut error = f64::MAX;
+	let mut x = g * f + f;
+
+	while error > epsilon && iterations < max_iterations {
+		iterations += 1;
+		let mut next = x.clone();
+
+		for i in 0..n {
+			let s1: f64 = (0..i).map(|j| g[(i, j)] * next[j]).sum();
+			let s2: f64 = (i + 1..n).map(|j| g[(i, j)] * x[j]).sum();
+
+			next[i] = (1.0 - omega) * x[i] + omega * (s1 + s2 + f[i]);
+		}
+
+		error = (&next - &x).norm();
+		x = next;
+	}
+
+	Solution { x, iterations, error }
+}
+
+/// Estimate of `‖A^-1‖₁` from a few solves with `A` and `A^T`, by the method of Hager with Higham's safeguard.
+///
+/// `solve` and `solve_transposed` overwrite a vector of length `n` with the solution.
+/// The estimate never exceeds the true norm and is usually within a factor of 3 of it.
+pub fn estimate_inverse_norm(n: usize, solve: impl Fn(&mut [f64]), solve_transposed: impl Fn(&mut [f64])) -> f64 {
+	let norm_1 = |x: &[f64]| x.iter().map(|x| x.abs()).sum::<f64>();
+
+	let mut x = vec![1.0 / n as f64; n];
+	let mut estimate = 0.0;
+	for _ in 0..5 {
+		let mut y = x.clone();
+		solve(&mut y);
+		estimate = norm_1(&y);
+
+		let mut z: Vec<f64> = y.iter().map(|&y| if y >= 0.0 { 1.0 } else { -1.0 }).collect();
+		solve_transposed(&mut z);
+
+		let (j, largest) = z.iter().enumerate().fold((0, 0.0), |best, (j, z)| if z.abs() > best.1 { (j, z.abs()) } else { best });
+		let product: f64 = z.iter().zip(&x).map(|(z, x)| z * x).sum();
+		if largest <= product {
+			break;
+		}
+
+		x = vec![0.0; n];
+		x[j] = 1.0;
+	}
+
+	// alternating signs catch matrices that fool the iteration
+	let mut alternating: Vec<f64> = (0..n).map(|i| {
+		let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+		sign * (1.0 + i as f64 / (n.max(2) - 1) as f64)
+	}).collect();
+	solve(&mut alternating);
+
+	estimate.max(2.0 * norm_1(&alternating) / (3.0 * n as f64))
+}
+
+/// Spectral radius `max |λ|` of a square matrix, from all of its eigenvalues.
+///
+/// A symmetric matrix goes through the symmetric QR algorithm. Any other goes through a Schur decomposition,
+/// which can stall on eigenvalues of equal modulus; after `30 n` iterations [`power_iteration`] estimates the radius instead.
+pub fn spectral_radius(g: &DMatrix<f64>) -> f64 {
+	if g.is_empty() {
+		return 0.0;
+	}
+	if g == &g.transpose() {
+		return g.clone().symmetric_eigenvalues().amax();
+	}
+
+	match g.clone().try_schur(f64::EPSILON, 30 * g.nrows()) {
+		Some(schur) => schur.complex_eigenvalues().iter().map(|lambda| lambda.re.hypot(lambda.im)).fold(0.0, f64::max),
+		None => power_iteration(g, 1000),
+	}
+}
+
+/// The relaxation parameter `2 / (1 + √(1 - ρ²))` for the spectral radius `rho` of the Jacobi iteration matrix.
+///
+/// It minimizes the spectral radius of the relaxation method for consistently ordered matrices, such as
+/// tridiagonal ones, whose Jacobi iteration matrix has real eigenvalues; for others it is only a guess.
+/// `None` when `rho ≥ 1`, as the Jacobi method then diverges.
+pub fn optimal_relaxation(rho: f64) -> Option<f64> {
+	(rho < 1.0).then(|| 2.0 / (1.0 + (1.0 - rho * rho).sqrt()))
+}
+
+/// Iterations of the relaxation method with one parameter.
+#[derive(Clone, Copy, Debug)]
+pub struct SweepPoint {
+	pub omega: f64,
+	pub iterations: usize,
+	/// Whether the tolerance was reached within the iteration limit.
+	pub converged: bool,
+}
+
+/// Runs [`seidel_method`] with every parameter of `omegas`, giving up after `max_iterations`,
+/// as the method slows down near 0 and 2. Parameters for which it diverges are not run and count as not converged.
+pub fn relaxation_sweep(a: &DMatrix<f64>, b: &DVector<f64>, epsilon: f64, max_iterations: usize, omegas: &[f64]) -> Result<Vec<SweepPoint>, Error> {
+	let diagnostics = check_stationary(a, b)?;
+	let (g, f) = jacobi_form(a, b);
+
+	Ok(omegas.iter().map(|&omega| {
+		let radius = diagnostics.relaxation_radius(omega);
+		if radius >= 1.0 {
+			return SweepPoint { omega, iterations: 0, converged: false };
+		}
+
+		let (epsilon, limit) = stopping_rule(&diagnostics, radius, epsilon);
+		let solution = relaxation(&g, &f, epsilon, omega, limit.min(max_iterations));
+		SweepPoint { omega, iterations: solution.iterations, converged: solution.error <= epsilon }
+	}).collect())
+}
+
+/// The converged point with the fewest iterations, the first of them on a tie.
+pub fn best_relaxation(points: &[SweepPoint]) -> Option<SweepPoint> {
+	points.iter().filter(|point| point.converged).copied().reduce(|best, point| if point.iterations < best.iterations { point } else { best })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A diagonally dominant system with the solution `(1, 2, -1, 1)`.
+	fn system() -> (DMatrix<f64>, DVector<f64>) {
+		let a = DMatrix::from_row_slice(4, 4, &[
+			10.0, -1.0, 2.0, 0.0,
+			-1.0, 11.0, -1.0, 3.0,
+			2.0, -1.0, 10.0, -1.0,
+			0.0, 3.0, -1.0, 8.0,
+		]);
+		let b = DVector::from_vec(vec![6.0, 25.0, -11.0, 15.0]);
+		(a, b)
+	}
+
+	#[test]
+	fn stationary_methods_converge_to_the_solution() {
+		let (a, b) = system();
+		let exact = a.clone().lu().solve(&b).unwrap();
+
+		let jacobi = simple_iterations_method(&a, &b, 1e-10).unwrap();
+		assert!((&jacobi.x - &exact).norm() < 1e-9);
+		for omega in [0.7, 1.0, 1.3] {
+			let solution = seidel_method(&a, &b, 1e-10, omega).unwrap();
+			assert!((&solution.x - &exact).norm() < 1e-9, "omega = {omega}: {}", solution.x);
+		}
+	}
+
+	#[test]
+	fn seidel_sweep_uses_every_updated_component() {
+		// on a lower triangular matrix one sweep of forward substitution is exact
+		let a = DMatrix::from_row_slice(3, 3, &[2.0, 0.0, 0.0, 1.0, 4.0, 0.0, 3.0, -1.0, 5.0]);
+		let b = DVector::from_vec(vec![2.0, 9.0, 4.0]);
+		let (g, f) = jacobi_form(&a, &b);
+
+		let solution = relaxation(&g, &f, 0.0, 1.0, 1);
+		let exact = a.lu().solve(&b).unwrap();
+		assert_eq!(solution.iterations, 1);
+		assert!((solution.x - exact).norm() < 1e-14);
+	}
+
+	#[test]
+	fn relaxation_blends_every_component() {
+		let a = DMatrix::from_row_slice(2, 2, &[4.0, 1.0, 1.0, 4.0]);
+		let b = DVector::from_vec(vec![5.0, 5.0]);
+		let (g, f) = jacobi_form(&a, &b);
+		let omega = 1.5;
+
+		let x = &g * &f + &f;
+		let first = (1.0 - omega) * x[0] + omega * (f[0] - 0.25 * x[1]);
+		let second = (1.0 - omega) * x[1] + omega * (f[1] - 0.25 * first);
+
+		let solution = relaxation(&g, &f, 0.0, omega, 1);
+		assert!((solution.x[0] - first).abs() < 1e-15);
+		assert!((solution.x[1] - second).abs() < 1e-15);
+	}
+
+	#[test]
+	fn optimal_relaxation_beats_seidel_on_a_tridiagonal_system() {
+		let n = 20;
+		let a = DMatrix::from_fn(n, n, |i, j| match i.abs_diff(j) {
+			0 => 2.0,
+			1 => -1.0,
+			_ => 0.0,
+		});
+		let b = DVector::from_element(n, 1.0);
+
+		let omega = optimal_relaxation(spectral_radius(&jacobi_form(&a, &b).0)).unwrap();
+		let seidel = seidel_method(&a, &b, 1e-8, 1.0).unwrap();
+		let sor = seidel_method(&a, &b, 1e-8, omega).unwrap();
+		assert!(sor.iterations * 4 < seidel.iterations, "{} vs {}", sor.iterations, seidel.iterations);
+	}
+
+	#[test]
+	fn diverging_methods_are_refused() {
+		let a = DMatrix::from_row_slice(2, 2, &[1.0, 3.0, 3.0, 1.0]);
+		let b = DVector::from_vec(vec![1.0, 1.0]);
+		assert!(matches!(simple_iterations_method(&a, &b, 1e-6), Err(Error::Diverges { .. })));
+
+		let (a, b) = system();
+		assert!(matches!(seidel_method(&a, &b, 1e-6, 2.5), Err(Error::Diverges { .. })));
+	}
+}
@@ -0,0 +1,287 @@
+//! Preconditioners `M ≈ A` that are cheap to invert, to speed up the iterative methods.
+
+use super::krylov::{norm, IterativeSolution, LinearOperator, StopReason};
+use super::{Csr, Error};
+use nalgebra::DVector;
+
+/// An approximation `M` of a matrix, used through `z = M^-1 r`.
+pub trait Preconditioner {
+	/// Overwrites `z` with `M^-1 r`.
+	fn apply(&self, r: &[f64], z: &mut [f64]);
+}
+
+impl<P: Preconditioner + ?Sized> Preconditioner for Box<P> {
+	fn apply(&self, r: &[f64], z: &mut [f64]) {
+		(**self).apply(r, z)
+	}
+}
+
+/// No preconditioning, `M = I`.
+pub struct Identity;
+
+impl Preconditioner for Identity {
+	fn apply(&self, r: &[f64], z: &mut [f64]) {
+		z.copy_from_slice(r);
+	}
+}
+
+/// The diagonal of the matrix, `M = D`.
+pub struct Jacobi {
+	inverse: Vec<f64>,
+}
+
+impl Jacobi {
+	/// Fails with [`Error::ZeroPivot`] on a zero diagonal value.
+	pub fn new(a: &Csr) -> Result<Self, Error> {
+		Ok(Self { inverse: a.diagonal()?.iter().map(|d| 1.0 / d).collect() })
+	}
+}
+
+impl Preconditioner for Jacobi {
+	fn apply(&self, r: &[f64], z: &mut [f64]) {
+		z.iter_mut().zip(r).zip(&self.inverse).for_each(|((z, r), inverse)| *z = r * inverse);
+	}
+}
+
+/// Symmetric successive over-relaxation, `M = ω / (2 - ω) (D / ω + L) D^-1 (D / ω + U)`,
+/// where `L` and `U` are the strictly lower and upper parts of the matrix.
+///
+/// A forward and a backward relaxation sweep; `M` is symmetric for a symmetric matrix, unlike that of SOR.
+pub struct Ssor {
+	matrix: Csr,
+	diagonal: Vec<f64>,
+	omega: f64,
+}
+
+impl Ssor {
+	/// Fails with [`Error::Relaxation`] unless `omega` lies in `(0, 2)`, and with [`Error::ZeroPivot`] on a zero diagonal value.
+	pub fn new(a: &Csr, omega: f64) -> Result<Self, Error> {
+		if !(omega > 0.0 && omega < 2.0) {
+			return Err(Error::Relaxation { omega });
+		}
+		Ok(Self { matrix: a.clone(), diagonal: a.diagonal()?, omega })
+	}
+}
+
+impl Preconditioner for Ssor {
+	fn apply(&self, r: &[f64], z: &mut [f64]) {
+		let (a, omega) = (&self.matrix, self.omega);
+		let n = r.len();
+
+		// (D / ω + L) y = r
+		for i in 0..n {
+			let (columns, values) = a.row(i);
+			let lower: f64 = columns.iter().zip(values).take_while(|(&j, _)| j < i).map(|(&j, value)| value * z[j]).sum();
+			z[i] = omega * (r[i] - lower) / self.diagonal[i];
+		}
+		// (D / ω + U) z = D y, scaled by (2 - ω) / ω
+		for i in (0..n).rev() {
+			let (columns, values) = a.row(i);
+			let upper: f64 = columns.iter().zip(values).filter(|(&j, _)| j > i).map(|(&j, value)| value * z[j]).sum();
+			z[i] = omega * (z[i] - upper / self.diagonal[i]);
+		}
+		z.iter_mut().for_each(|z| *z *= (2.0 - omega) / omega);
+	}
+}
+
+/// Incomplete LU factorization without fill-in, `M = L U` with the sparsity pattern of the matrix.
+///
+/// For a symmetric positive definite M-matrix, such as the Poisson matrix, it is the incomplete Cholesky factorization.
+pub struct Ilu0 {
+	pattern: Csr,
+	/// `L` below the diagonal with its unit diagonal left out, and `U` on and above it, in the storage of `pattern`.
+	factors: Vec<f64>,
+	diagonal: Vec<usize>,
+}
+
+impl Ilu0 {
+	/// Fails with [`Error::ZeroPivot`] when a pivot is missing from the pattern or vanishes.
+	pub fn new(a: &Csr) -> Result<Self, Error> {
+		let n = a.nrows();
+		if a.ncols() != n {
+			return Err(Error::DimensionMismatch { expected: n, found: a.ncols() });
+		}
+		let indices = a.indices();
+		let mut factors = a.values().to_vec();
+		let diagonal = (0..n).map(|i| {
+			let range = a.range(i);
+			indices[range.clone()].binary_search(&i).map(|offset| range.start + offset).map_err(|_| Error::ZeroPivot { row: i })
+		}).collect::<Result<Vec<usize>, Error>>()?;
+
+		// where the columns of the current row are stored
+		let mut position = vec![usize::MAX; n];
+		for i in 0..n {
+			let range = a.range(i);
+			for p in range.clone() {
+				position[indices[p]] = p;
+			}
+
+			for p in range.start..diagonal[i] {
+				let k = indices[p];
+				factors[p] /= factors[diagonal[k]];
+				let factor = factors[p];
+				for q in diagonal[k] + 1..a.range(k).end {
+					let target = position[indices[q]];
+					if target != usize::MAX {
+						let update = factor * factors[q];
+						factors[target] -= update;
+					}
+				}
+			}
+
+			let pivot = factors[diagonal[i]];
+			if pivot == 0.0 || !pivot.is_finite() {
+				return Err(Error::ZeroPivot { row: i });
+			}
+			for p in range {
+				position[indices[p]] = usize::MAX;
+			}
+		}
+
+		Ok(Self { pattern: a.clone(), factors, diagonal })
+	}
+}
+
+impl Preconditioner for Ilu0 {
+	fn apply(&self, r: &[f64], z: &mut [f64]) {
+		let indices = self.pattern.indices();
+		let n = r.len();
+
+		for i in 0..n {
+			let lower: f64 = (self.pattern.range(i).start..self.diagonal[i]).map(|p| self.factors[p] * z[indices[p]]).sum();
+			z[i] = r[i] - lower;
+		}
+		for i in (0..n).rev() {
+			let upper: f64 = (self.diagonal[i] + 1..self.pattern.range(i).end).map(|p| self.factors[p] * z[indices[p]]).sum();
+			z[i] = (z[i] - upper) / self.factors[self.diagonal[i]];
+		}
+	}
+}
+
+/// Solves `Ax = b` by the stationary iteration `x(k+1) = x(k) + M^-1 (b - A x(k))`.
+///
+/// With [`Jacobi`] it is the Jacobi method, with [`Ssor`] the symmetric relaxation method. Stops when
+/// `‖b - Ax‖ ≤ tolerance ‖b‖` or after `max_iterations`, or as [`StopReason::Diverged`] once the residual overflows.
+pub fn preconditioned_iteration(
+	a: &impl LinearOperator,
+	preconditioner: &impl Preconditioner,
+	b: &[f64],
+	tolerance: f64,
+	max_iterations: usize,
+) -> Result<IterativeSolution, Error> {
+	let target = super::krylov::check(a, b, tolerance)?;
+	let n = b.len();
+
+	let mut x = vec![0.0; n];
+	let mut r = b.to_vec();
+	let mut z = vec![0.0; n];
+	let mut residuals = vec![norm(&r)];
+
+	let stop = loop {
+		let residual = residuals[residuals.len() - 1];
+		if !residual.is_finite() {
+			break StopReason::Diverged;
+		}
+		if residual <= target {
+			break StopReason::Converged;
+		}
+		if residuals.len() > max_iterations {
+			break StopReason::MaxIterations;
+		}
+
+		preconditioner.apply(&r, &mut z);
+		x.iter_mut().zip(&z).for_each(|(x, z)| *x += z);
+		a.apply(&x, &mut r);
+		r.iter_mut().zip(b).for_each(|(r, b)| *r = b - *r);
+		residuals.push(norm(&r));
+	};
+
+	Ok(IterativeSolution { x: DVector::from_vec(x), iterations: residuals.len() - 1, residuals, stop })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::linalg::{bicgstab, conjugate_gradient};
+
+	const TOLERANCE: f64 = 1e-10;
+
+	/// A non-symmetric, diagonally dominant tridiagonal matrix.
+	fn tridiagonal(n: usize) -> Csr {
+		let entries: Vec<(usize, usize, f64)> = (0..n).flat_map(|i| {
+			let mut row = vec![(i, i, 4.0 + i as f64 / n as f64)];
+			if i > 0 {
+				row.push((i, i - 1, -1.5));
+			}
+			if i + 1 < n {
+				row.push((i, i + 1, -0.5 - i as f64 / n as f64));
+			}
+			row
+		}).collect();
+		Csr::from_triplets(n, n, &entries).unwrap()
+	}
+
+	#[test]
+	fn incomplete_lu_of_a_tridiagonal_matrix_is_exact() {
+		let a = tridiagonal(20);
+		let b: Vec<f64> = (0..20).map(|i| (i as f64).sin()).collect();
+		let mut z = vec![0.0; 20];
+		Ilu0::new(&a).unwrap().apply(&b, &mut z);
+
+		let exact = a.to_dense().lu().solve(&DVector::from_vec(b.clone())).unwrap();
+		assert!((DVector::from_vec(z) - exact).norm() < 1e-13);
+
+		let solution = preconditioned_iteration(&a, &Ilu0::new(&a).unwrap(), &b, TOLERANCE, 10).unwrap();
+		assert_eq!((solution.stop, solution.iterations), (StopReason::Converged, 1));
+	}
+
+	#[test]
+	fn zero_diagonal_is_an_error() {
+		let missing = Csr::from_triplets(3, 3, &[(0, 0, 1.0), (1, 2, 1.0), (2, 1, 1.0), (2, 2, 1.0)]).unwrap();
+		assert!(matches!(Jacobi::new(&missing), Err(Error::ZeroPivot { row: 1 })));
+		assert!(matches!(Ilu0::new(&missing), Err(Error::ZeroPivot { row: 1 })));
+		assert!(matches!(Ssor::new(&missing, 1.0), Err(Error::ZeroPivot { row: 1 })));
+
+		// stored, but eliminated to zero
+		let vanishing = Csr::from_triplets(2, 2, &[(0, 0, 1.0), (0, 1, 2.0), (1, 0, 1.0), (1, 1, 2.0)]).unwrap();
+		assert!(matches!(Ilu0::new(&vanishing), Err(Error::ZeroPivot { row: 1 })));
+
+		assert!(matches!(Ssor::new(&tridiagonal(3), 2.0), Err(Error::Relaxation { .. })));
+	}
+
+	#[test]
+	fn preconditioning_saves_krylov_iterations_on_poisson() {
+		let a = Csr::poisson_2d(16);
+		let b = vec![1.0; a.nrows()];
+		let exact = a.to_dense().lu().solve(&DVector::from_vec(b.clone())).unwrap();
+
+		let plain = conjugate_gradient(&a, &Identity, &b, TOLERANCE, 1000).unwrap().iterations;
+		let ssor = conjugate_gradient(&a, &Ssor::new(&a, 1.5).unwrap(), &b, TOLERANCE, 1000).unwrap();
+		let ilu = conjugate_gradient(&a, &Ilu0::new(&a).unwrap(), &b, TOLERANCE, 1000).unwrap();
+		for solution in [&ssor, &ilu] {
+			assert_eq!(solution.stop, StopReason::Converged);
+			assert!((&solution.x - &exact).norm() < 1e-8 * exact.norm());
+			assert!(solution.iterations < plain, "{} vs {plain}", solution.iterations);
+		}
+
+		let plain = bicgstab(&a, &Identity, &b, TOLERANCE, 1000).unwrap().iterations;
+		let ilu = bicgstab(&a, &Ilu0::new(&a).unwrap(), &b, TOLERANCE, 1000).unwrap();
+		assert_eq!(ilu.stop, StopReason::Converged);
+		assert!((&ilu.x - &exact).norm() < 1e-8 * exact.norm());
+		assert!(ilu.iterations < plain, "{} vs {plain}", ilu.iterations);
+	}
+
+	#[test]
+	fn stationary_iteration_with_preconditioners() {
+		let a = Csr::poisson_2d(6);
+		let b = vec![1.0; a.nrows()];
+		let jacobi = preconditioned_iteration(&a, &Jacobi::new(&a).unwrap(), &b, 1e-8, 5000).unwrap();
+		let ssor = preconditioned_iteration(&a, &Ssor::new(&a, 1.5).unwrap(), &b, 1e-8, 5000).unwrap();
+		let none = preconditioned_iteration(&a, &Identity, &b, 1e-8, 100).unwrap();
+		assert_eq!(jacobi.stop, StopReason::Converged);
+		assert_eq!(ssor.stop, StopReason::Converged);
+		assert!(ssor.iterations < jacobi.iterations);
+		// x += b - Ax with ‖I - A‖ > 1 blows up
+		assert_ne!(none.stop, StopReason::Converged);
+	}
+}
@@ -0,0 +1,348 @@
+//! Sparse matrices in the compressed sparse row (CSR) format, and the stationary iterations on them.
+
+use nalgebra::{DMatrix, DVector};
+use super::{Error, Solution};
+
+/// A matrix storing only its non-zero values, row by row.
+///
+/// The values of row `i` and their columns are `values[offsets[i]..offsets[i + 1]]` and
+/// `columns[offsets[i]..offsets[i + 1]]`, with the columns of a row in increasing order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Csr {
+	rows: usize,
+	columns: usize,
+	offsets: Vec<usize>,
+	indices: Vec<usize>,
+	values: Vec<f64>,
+}
+
+impl Csr {
+	/// The matrix of `(row, column, value)` entries in any order, adding up repeated entries.
+	pub fn from_triplets(rows: usize, columns: usize, entries: &[(usize, usize, f64)]) -> Result<Self, Error> {
+		let mut counts = vec![0; rows + 1];
+		for &(i, j, _) in entries {
+			if i >= rows {
+				return Err(Error::DimensionMismatch { expected: rows, found: i + 1 });
+			}
+			if j >= columns {
+				return Err(Error::DimensionMismatch { expected: columns, found: j + 1 });
+			}
+			counts[i + 1] += 1;
+		}
+		for i in 0..rows {
+			counts[i + 1] += counts[i];
+		}
+
+		// bucket the entries by row, then sort and merge every row
+		let mut next = counts.clone();
+		let mut unsorted = vec![(0, 0.0); entries.len()];
+		for &(i, j, value) in entries {
+			unsorted[next[i]] = (j, value);
+			next[i] += 1;
+		}
+
+		let mut offsets = Vec::with_capacity(rows + 1);
+		let mut indices = Vec::with_capacity(entries.len());
+		let mut values = Vec::with_capacity(entries.len());
+		offsets.push(0);
+		for i in 0..rows {
+			let row = &mut unsorted[counts[i]..counts[i + 1]];
+			row.sort_by_key(|&(j, _)| j);
+			for &(j, value) in row.iter() {
+				if indices.len() > offsets[i] && indices.last() == Some(&j) {
+					*values.last_mut().unwrap() += value;
+				} else {
+					indices.push(j);
+					values.push(value);
+				}
+			}
+			offsets.push(indices.len());
+		}
+
+		Ok(Self { rows, columns, offsets, indices, values })
+	}
+
+	/// The non-zero values of `matrix`.
+	pub fn from_dense(matrix: &DMatrix<f64>) -> Self {
+		let entries: Vec<(usize, usize, f64)> = (0..matrix.nrows())
+			.flat_map(|i| (0..matrix.ncols()).map(move |j| (i, j, matrix[(i, j)])))
+			.filter(|&(_, _, value)| value != 0.0)
+			.collect();
+		Self::from_triplets(matrix.nrows(), matrix.ncols(), &entries).unwrap()
+	}
+
+	/// The five-point Laplacian `4 u[i][j] - u[i - 1][j] - u[i + 1][j] - u[i][j - 1] - u[i][j + 1]` on the `m × m`
+	/// inner nodes of a square grid with zero boundary values, numbered row by row.
+	///
+	/// Divided by `h²` it approximates `-Δu`; its `m²` unknowns make a large test system with five values per row.
+	pub fn poisson_2d(m: usize) -> Self {
+		Self::convection_diffusion_2d(m, 0.0)
+	}
+
+	/// The five-point scheme of `-Δu + c ∂u/∂x` times `h²` with central differences, as [`Csr::poisson_2d`],
+	/// for the cell Péclet number `peclet = c h / 2`.
+	///
+	/// The matrix is not symmetric unless `peclet = 0`, and is no longer diagonally dominant once `|peclet| > 1`.
+	pub fn convection_diffusion_2d(m: usize, peclet: f64) -> Self {
+		let n = m * m;
+		let mut offsets = Vec::with_capacity(n + 1);
+		let mut indices = Vec::with_capacity(5 * n);
+		let mut values = Vec::with_capacity(5 * n);
+		offsets.push(0);
+
+		for row in 0..m {
+			for column in 0..m {
+				let k = row * m + column;
+				let neighbours = [
+					(row > 0, k.wrapping_sub(m), -1.0),
+					(column > 0, k.wrapping_sub(1), -1.0 - peclet),
+					(true, k, 4.0),
+					(column + 1 < m, k + 1, -1.0 + peclet),
+					(row + 1 < m, k + m, -1.0),
+				];
+				for (present, j, value) in neighbours {
+					if present {
+						indices.push(j);
+						values.push(value);
+					}
+				}
+				offsets.push(indices.len());
+			}
+		}
+
+		Self { rows: n, columns: n, offsets, indices, values }
+	}
+
+	pub fn nrows(&self) -> usize {
+		self.rows
+	}
+
+	pub fn ncols(&self) -> usize {
+		self.columns
+	}
+
+	/// Number of stored values.
+	pub fn nnz(&self) -> usize {
+		self.values.len()
+	}
+
+	/// Positions of row `i` in the stored columns and values.
+	pub(super) fn range(&self, i: usize) -> std::ops::Range<usize> {
+		self.offsets[i]..self.offsets[i + 1]
+	}
+
+	/// Columns of all the stored values.
+	pub(super) fn indices(&self) -> &[usize] {
+		&self.indices
+	}
+
+	pub(super) fn values(&self) -> &[f64] {
+		&self.values
+	}
+
+	/// Columns and values of row `i`.
+	pub fn row(&self, i: usize) -> (&[usize], &[f64]) {
+		let range = self.range(i);
+		(&self.indices[range.clone()], &self.values[range])
+	}
+
+	/// Entry `(i, j)`, zero when it is not stored.
+	pub fn get(&self, i: usize, j: usize) -> f64 {
+		let (columns, values) = self.row(i);
+		columns.binary_search(&j).map_or(0.0, |index| values[index])
+	}
+
+	/// The main diagonal; fails with [`Error::ZeroPivot`] on the first zero for the iterations that divide by it.
+	pub fn diagonal(&self) -> Result<Vec<f64>, Error> {
+		(0..self.rows.min(self.columns)).map(|i| {
+			let value = self.get(i, i);
+			if value == 0.0 { Err(Error::ZeroPivot { row: i }) } else { Ok(value) }
+		}).collect()
+	}
+
+	pub fn mul_vec(&self, x: &[f64]) -> Vec<f64> {
+		assert_eq!(x.len(), self.columns);
+		(0..self.rows).map(|i| self.row_dot(i, x)).collect()
+	}
+
+	/// `b - Ax`.
+	pub fn residual(&self, x: &[f64], b: &[f64]) -> Vec<f64> {
+		self.mul_vec(x).iter().zip(b).map(|(ax, b)| b - ax).collect()
+	}
+
+	pub fn transpose(&self) -> Self {
+		let entries: Vec<(usize, usize, f64)> = (0..self.rows).flat_map(|i| {
+			let (columns, values) = self.row(i);
+			columns.iter().zip(values).map(move |(&j, &value)| (j, i, value))
+		}).collect();
+		Self::from_triplets(self.columns, self.rows, &entries).unwrap()
+	}
+
+	pub fn is_symmetric(&self) -> bool {
+		self.rows == self.columns && *self == self.transpose()
+	}
+
+	pub fn to_dense(&self) -> DMatrix<f64> {
+		let mut matrix = DMatrix::zeros(self.rows, self.columns);
+		for i in 0..self.rows {
+			let (columns, values) = self.row(i);
+			for (&j, &value) in columns.iter().zip(values) {
+				matrix[(i, j)] = value;
+			}
+		}
+		matrix
+	}
+
+	/// Row `i` times `x`.
+	fn row_dot(&self, i: usize, x: &[f64]) -> f64 {
+		let (columns, values) = self.row(i);
+		columns.iter().zip(values).map(|(&j, value)| value * x[j]).sum()
+	}
+
+	fn check_system(&self, b: &[f64]) -> Result<Vec<f64>, Error> {
+		if self.rows != self.columns {
+			return Err(Error::DimensionMismatch { expected: self.rows, found: self.columns });
+		}
+		if b.len() != self.rows {
+			return Err(Error::DimensionMismatch { expected: self.rows, found: b.len() });
+		}
+		if self.rows == 0 {
+			return Err(Error::Empty);
+		}
+		self.diagonal()
+	}
+}
+
+/// Solves `Ax = b` by Jacobi iterations until two approximations differ by at most `epsilon`,
+/// or for at most `max_iterations`.
+///
+/// The iteration matrix is never formed: every step reads each stored value once and reuses two vectors.
+pub fn sparse_jacobi(a: &Csr, b: &[f64], epsilon: f64, max_iterations: usize) -> Result<Solution, Error> {
+	let diagonal = a.check_system(b)?;
+
+	let mut x: Vec<f64> = b.iter().zip(&diagonal).map(|(b, d)| b / d).collect();
+	let mut next = vec![0.0; x.len()];
+	let mut iterations = 0;
+	let mut error = f64::MAX;
+
+	while error > epsilon && iterations < max_iterations {
+		iterations += 1;
+		for (i, next) in next.iter_mut().enumerate() {
+			*next = x[i] + (b[i] - a.row_dot(i, &x)) / diagonal[i];
+		}
+		error = distance(&next, &x);
+		std::mem::swap(&mut x, &mut next);
+	}
+
+	Ok(Solution { x: DVector::from_vec(x), iterations, error })
+}
+
+/// Solves `Ax = b` by the Gauss–Seidel method, see [`sparse_sor`].
+pub fn sparse_gauss_seidel(a: &Csr, b: &[f64], epsilon: f64, max_iterations: usize) -> Result<Solution, Error> {
+	sparse_sor(a, b, epsilon, max_iterations, 1.0)
+}
+
+/// Solves `Ax = b` by successive over-relaxation with parameter `omega`, updating the approximation in place.
+///
+/// Stops as [`sparse_jacobi`] does; `omega = 1` gives the Gauss–Seidel method.
+pub fn sparse_sor(a: &Csr, b: &[f64], epsilon: f64, max_iterations: usize, omega: f64) -> Result<Solution, Error> {
+	let diagonal = a.check_system(b)?;
+
+	let mut x: Vec<f64> = b.iter().zip(&diagonal).map(|(b, d)| b / d).collect();
+	let mut iterations = 0;
+	let mut error = f64::MAX;
+
+	while error > epsilon && iterations < max_iterations {
+		iterations += 1;
+		let mut squares = 0.0;
+		for i in 0..x.len() {
+			let change = omega * (b[i] - a.row_dot(i, &x)) / diagonal[i];
+			x[i] += change;
+			squares += change * change;
+		}
+		error = squares.sqrt();
+	}
+
+	Ok(Solution { x: DVector::from_vec(x), iterations, error })
+}
+
+/// Euclidean distance between two vectors.
+fn distance(x: &[f64], y: &[f64]) -> f64 {
+	x.iter().zip(y).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The solution of `A x = 1` by nalgebra's dense LU.
+	fn exact(a: &Csr) -> DVector<f64> {
+		a.to_dense().lu().solve(&DVector::from_element(a.nrows(), 1.0)).unwrap()
+	}
+
+	#[test]
+	fn triplets_are_summed_and_sorted() {
+		let entries = [(1, 2, 3.0), (0, 1, 1.0), (1, 0, -2.0), (0, 1, 4.0), (2, 2, 1.0), (1, 2, -0.5), (0, 0, 2.0)];
+		let a = Csr::from_triplets(3, 3, &entries).unwrap();
+		let expected = DMatrix::from_row_slice(3, 3, &[
+			2.0, 5.0, 0.0,
+			-2.0, 0.0, 2.5,
+			0.0, 0.0, 1.0,
+		]);
+		assert_eq!(a.to_dense(), expected);
+		assert_eq!(a.nnz(), 5);
+		assert_eq!(a.row(1), (&[0, 2][..], &[-2.0, 2.5][..]));
+		assert_eq!(a, Csr::from_dense(&expected));
+		assert_eq!(a.transpose().to_dense(), expected.transpose());
+		assert!(matches!(a.diagonal(), Err(Error::ZeroPivot { row: 1 })));
+	}
+
+	#[test]
+	fn indices_out_of_range() {
+		assert!(matches!(Csr::from_triplets(2, 3, &[(2, 0, 1.0)]), Err(Error::DimensionMismatch { expected: 2, found: 3 })));
+		assert!(matches!(Csr::from_triplets(2, 3, &[(0, 0, 1.0), (1, 3, 1.0)]), Err(Error::DimensionMismatch { expected: 3, found: 4 })));
+	}
+
+	#[test]
+	fn poisson_matrix() {
+		let a = Csr::poisson_2d(4);
+		assert_eq!((a.nrows(), a.ncols(), a.nnz()), (16, 16, 16 * 5 - 4 * 4));
+		assert!(a.is_symmetric());
+		assert!(!Csr::convection_diffusion_2d(4, 0.5).is_symmetric());
+		let x: Vec<f64> = (0..16).map(|i| i as f64).collect();
+		let dense = a.to_dense() * DVector::from_vec(x.clone());
+		assert_eq!(a.mul_vec(&x), dense.as_slice());
+	}
+
+	#[test]
+	fn stationary_iterations_on_poisson() {
+		let a = Csr::poisson_2d(8);
+		let b = vec![1.0; a.nrows()];
+		let exact = exact(&a);
+		// the spectral radius of Jacobi, and the optimal relaxation it gives
+		let radius = (std::f64::consts::PI / 9.0).cos();
+		let omega = 2.0 / (1.0 + (1.0 - radius * radius).sqrt());
+
+		let jacobi = sparse_jacobi(&a, &b, 1e-10, 10_000).unwrap();
+		let seidel = sparse_gauss_seidel(&a, &b, 1e-10, 10_000).unwrap();
+		let sor = sparse_sor(&a, &b, 1e-10, 10_000, omega).unwrap();
+		for solution in [&jacobi, &seidel, &sor] {
+			assert!(solution.error <= 1e-10);
+			assert!((&solution.x - &exact).norm() < 1e-7 * exact.norm());
+		}
+		// Gauss–Seidel takes about half the steps of Jacobi, and optimal SOR far fewer
+		assert!(seidel.iterations < jacobi.iterations * 3 / 5, "{} vs {}", seidel.iterations, jacobi.iterations);
+		assert!(sor.iterations * 4 < seidel.iterations, "{} vs {}", sor.iterations, seidel.iterations);
+	}
+
+	#[test]
+	fn invalid_systems() {
+		let a = Csr::poisson_2d(3);
+		assert!(matches!(sparse_jacobi(&a, &[1.0; 4], 1e-6, 10), Err(Error::DimensionMismatch { expected: 9, found: 4 })));
+		let rectangular = Csr::from_triplets(2, 3, &[(0, 0, 1.0), (1, 1, 1.0)]).unwrap();
+		assert!(matches!(sparse_gauss_seidel(&rectangular, &[1.0; 2], 1e-6, 10), Err(Error::DimensionMismatch { .. })));
+		let zero_diagonal = Csr::from_triplets(2, 2, &[(0, 1, 1.0), (1, 0, 1.0)]).unwrap();
+		assert!(matches!(sparse_sor(&zero_diagonal, &[1.0; 2], 1e-6, 10, 1.2), Err(Error::ZeroPivot { row: 0 })));
+		assert!(matches!(sparse_jacobi(&Csr::from_triplets(0, 0, &[]).unwrap(), &[], 1e-6, 10), Err(Error::Empty)));
+	}
+}
@@ -0,0 +1,319 @@
+//! Tridiagonal systems solved by the sweep method (прогонка), also known as the Thomas algorithm.
+
+use nalgebra::DMatrix;
+use super::Error;
+
+/// A tridiagonal matrix of size `n`.
+///
+/// Row `i` reads `lower[i - 1] * x[i - 1] + diagonal[i] * x[i] + upper[i] * x[i + 1]`,
+/// so both off-diagonals hold `n - 1` values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tridiagonal {
+	lower: Vec<f64>,
+	diagonal: Vec<f64>,
+	upper: Vec<f64>,
+}
+
+impl Tridiagonal {
+	pub fn new(lower: Vec<f64>, diagonal: Vec<f64>, upper: Vec<f64>) -> Result<Self, Error> {
+		let n = diagonal.len();
+		if n == 0 {
+			return Err(Error::Empty);
+		}
+		for band in [&lower, &upper] {
+			if band.len() != n - 1 {
+				return Err(Error::DimensionMismatch { expected: n - 1, found: band.len() });
+			}
+		}
+
+		Ok(Self { lower, diagonal, upper })
+	}
+
+	/// The matrix with the same three values on every row, like the `1 4 1` of the spline slopes on equally spaced nodes.
+	pub fn constant(n: usize, lower: f64, diagonal: f64, upper: f64) -> Self {
+		assert!(n > 0, "a matrix needs at least one row");
+		Self {
+			lower: vec![lower; n - 1],
+			diagonal: vec![diagonal; n],
+			upper: vec![upper; n - 1],
+		}
+	}
+
+	pub fn size(&self) -> usize {
+		self.diagonal.len()
+	}
+
+	pub fn lower(&self) -> &[f64] {
+		&self.lower
+	}
+
+	pub fn diagonal(&self) -> &[f64] {
+		&self.diagonal
+	}
+
+	pub fn upper(&self) -> &[f64] {
+		&self.upper
+	}
+
+	/// Mutable bands `(lower, diagonal, upper)`, e.g. to set the boundary rows.
+	pub fn bands_mut(&mut self) -> (&mut [f64], &mut [f64], &mut [f64]) {
+		(&mut self.lower, &mut self.diagonal, &mut self.upper)
+	}
+
+	/// Coefficient `a[i][i - 1]`, zero for the first row.
+	fn below(&self, i: usize) -> f64 {
+		if i == 0 { 0.0 } else { self.lower[i - 1] }
+	}
+
+	/// Coefficient `a[i][i + 1]`, zero for the last row.
+	fn above(&self, i: usize) -> f64 {
+		self.upper.get(i).copied().unwrap_or(0.0)
+	}
+
+	/// `|diagonal[i]| >= |lower| + |upper|` in every row and strictly in at least one.
+	///
+	/// For such a matrix every pivot of the sweep is non-zero and the sweep is stable.
+	pub fn is_diagonally_dominant(&self) -> bool {
+		let margins: Vec<f64> = (0..self.size())
+			.map(|i| self.diagonal[i].abs() - self.below(i).abs() - self.above(i).abs())
+			.collect();
+
+		margins.iter().all(|&margin| margin >= 0.0) && margins.iter().any(|&margin| margin > 0.0)
+	}
+
+	pub fn mul_vec(&self, x: &[f64]) -> Vec<f64> {
+		assert_eq!(x.len(), self.size());
+		(0..self.size()).map(|i| {
+			let left = if i == 0 { 0.0 } else { self.lower[i - 1] * x[i - 1] };
+			let right = if i + 1 == self.size() { 0.0 } else { self.upper[i] * x[i + 1] };
+			left + self.diagonal[i] * x[i] + right
+		}).collect()
+	}
+
+	pub fn to_dense(&self) -> DMatrix<f64> {
+		let n = self.size();
+		DMatrix::from_fn(n, n, |i, j| match j as isize - i as isize {
+			-1 => self.lower[j],
+			0 => self.diagonal[i],
+			1 => self.upper[i],
+			_ => 0.0,
+		})
+	}
+
+	/// Runs the direct sweep once, so that any number of right-hand sides can be solved later.
+	///
+	/// Fails with [`Error::ZeroPivot`] when a pivot vanishes relative to the coefficients of its row.
+	pub fn factorize(&self) -> Result<Factorization, Error> {
+		let n = self.size();
+		let mut alpha = vec![0.0; n - 1];
+		let mut pivots = vec![0.0; n];
+
+		for i in 0..n {
+			let carried = if i == 0 { 0.0 } else { self.lower[i - 1] * alpha[i - 1] };
+			let pivot = self.diagonal[i] + carried;
+
+			let scale = self.diagonal[i].abs() + carried.abs();
+			if pivot.abs() <= f64::EPSILON * scale || !pivot.is_finite() {
+				return Err(Error::ZeroPivot { row: i });
+			}
+
+			pivots[i] = pivot;
+			if i + 1 < n {
+				alpha[i] = -self.upper[i] / pivot;
+			}
+		}
+
+		Ok(Factorization { lower: self.lower.clone(), alpha, pivots })
+	}
+
+	pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, Error> {
+		let mut x = rhs.to_vec();
+		self.factorize()?.solve_in_place(&mut x)?;
+		Ok(x)
+	}
+
+	/// Solves the cyclic system whose matrix also has `top_right` at `[0][n - 1]` and `bottom_left` at `[n - 1][0]`,
+	/// as met with periodic boundary conditions.
+	///
+	/// The corners are a rank-one correction `u v^T` of a tridiagonal matrix, removed with the
+	/// Sherman–Morrison formula at the cost of a second sweep.
+	pub fn solve_cyclic(&self, top_right: f64, bottom_left: f64, rhs: &[f64]) -> Result<Vec<f64>, Error> {
+		let n = self.size();
+		if rhs.len() != n {
+			return Err(Error::DimensionMismatch { expected: n, found: rhs.len() });
+		}
+
+		// for small matrices the corners fall onto the bands
+		if n <= 2 {
+			let mut matrix = self.clone();
+			if n == 1 {
+				matrix.diagonal[0] += top_right + bottom_left;
+			} else {
+				matrix.upper[0] += top_right;
+				matrix.lower[0] += bottom_left;
+			}
+			return matrix.solve(rhs);
+		}
+
+		// u = (gamma, 0, ..., bottom_left), v = (1, 0, ..., top_right / gamma)
+		let gamma = if self.diagonal[0] == 0.0 { -1.0 } else { -self.diagonal[0] };
+		let mut corrected = self.clone();
+		corrected.diagonal[0] -= gamma;
+		corrected.diagonal[n - 1] -= top_right * bottom_left / gamma;
+
+		let factorization = corrected.factorize()?;
+		let y = factorization.solve(rhs)?;
+		let mut u = vec![0.0; n];
+		u[0] = gamma;
+		u[n - 1] = bottom_left;
+		let z = factorization.solve(&u)?;
+
+		let v_dot = |w: &[f64]| w[0] + top_right / gamma * w[n - 1];
+		let denominator = 1.0 + v_dot(&z);
+		if denominator.abs() <= f64::EPSILON * (1.0 + v_dot(&z).abs()) {
+			return Err(Error::Singular);
+		}
+
+		let factor = v_dot(&y) / denominator;
+		Ok(y.iter().zip(&z).map(|(y, z)| y - factor * z).collect())
+	}
+
+	/// Solves for every column of `rhs` with a single factorization.
+	pub fn solve_columns(&self, rhs: &DMatrix<f64>) -> Result<DMatrix<f64>, Error> {
+		let factorization = self.factorize()?;
+		let mut x = rhs.clone();
+		for mut column in x.column_iter_mut() {
+			factorization.solve_in_place(column.as_mut_slice())?;
+		}
+		Ok(x)
+	}
+}
+
+/// The direct sweep of a [`Tridiagonal`] matrix: `x[i] = alpha[i] * x[i + 1] + beta[i]`.
+#[derive(Clone, Debug)]
+pub struct Factorization {
+	lower: Vec<f64>,
+	alpha: Vec<f64>,
+	pivots: Vec<f64>,
+}
+
+impl Factorization {
+	pub fn size(&self) -> usize {
+		self.pivots.len()
+	}
+
+	/// The largest `|alpha[i]|`; the reverse sweep does not amplify errors while it stays at most 1.
+	pub fn max_alpha(&self) -> f64 {
+		self.alpha.iter().fold(0.0, |max, alpha| max.max(alpha.abs()))
+	}
+
+	/// Whether [`Factorization::max_alpha`] is at most 1, which diagonal dominance guarantees.
+	pub fn is_stable(&self) -> bool {
+		self.max_alpha() <= 1.0
+	}
+
+	/// Product of the pivots.
+	pub fn determinant(&self) -> f64 {
+		self.pivots.iter().product()
+	}
+
+	/// Overwrites `rhs` with the solution, without allocating.
+	pub fn solve_in_place(&self, rhs: &mut [f64]) -> Result<(), Error> {
+		let n = self.size();
+		if rhs.len() != n {
+			return Err(Error::DimensionMismatch { expected: n, found: rhs.len() });
+		}
+
+		// direct sweep, rhs[i] becomes beta[i]
+		rhs[0] /= self.pivots[0];
+		for i in 1..n {
+			rhs[i] = (rhs[i] - self.lower[i - 1] * rhs[i - 1]) / self.pivots[i];
+		}
+
+		// reverse sweep
+		for i in (0..n - 1).rev() {
+			rhs[i] += self.alpha[i] * rhs[i + 1];
+		}
+
+		Ok(())
+	}
+
+	pub fn solve(&self, rhs: &[f64]) -> Result<Vec<f64>, Error> {
+		let mut x = rhs.to_vec();
+		self.solve_in_place(&mut x)?;
+		Ok(x)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use nalgebra::DVector;
+
+	/// A non-symmetric tridiagonal matrix with varying bands, diagonally dominant but not constant.
+	fn matrix(n: usize) -> Tridiagonal {
+		let lower = (1..n).map(|i| (i as f64).sin()).collect();
+		let diagonal = (0..n).map(|i| 4.0 + (i as f64 * 0.7).cos()).collect();
+		let upper = (1..n).map(|i| -(i as f64 * 1.3).cos()).collect();
+		Tridiagonal::new(lower, diagonal, upper).unwrap()
+	}
+
+	fn rhs(n: usize) -> Vec<f64> {
+		(0..n).map(|i| 1.0 + i as f64 * 0.25 - (i as f64).powi(2) * 0.01).collect()
+	}
+
+	#[test]
+	fn sweep_matches_lu() {
+		for n in [1, 2, 3, 10, 50] {
+			let (a, b) = (matrix(n), rhs(n));
+			let exact = a.to_dense().lu().solve(&DVector::from_vec(b.clone())).unwrap();
+			let x = a.solve(&b).unwrap();
+			assert!((DVector::from_vec(x) - exact).norm() < 1e-12, "n = {n}");
+		}
+	}
+
+	#[test]
+	fn factorization_is_reusable() {
+		let a = matrix(8);
+		let factorization = a.factorize().unwrap();
+		assert!(factorization.is_stable());
+		assert!((factorization.determinant() - a.to_dense().determinant()).abs() < 1e-9 * factorization.determinant().abs());
+
+		let rhs = DMatrix::from_fn(8, 3, |i, j| (i * 3 + j) as f64);
+		let x = a.solve_columns(&rhs).unwrap();
+		assert!((a.to_dense() * x - rhs).norm() < 1e-12);
+	}
+
+	#[test]
+	fn cyclic_sweep_matches_lu() {
+		for n in [1, 2, 3, 4, 20] {
+			let (a, b) = (matrix(n), rhs(n));
+			let (top_right, bottom_left) = (0.6, -0.9);
+
+			let mut dense = a.to_dense();
+			dense[(0, n - 1)] += top_right;
+			dense[(n - 1, 0)] += bottom_left;
+			let exact = dense.lu().solve(&DVector::from_vec(b.clone())).unwrap();
+
+			let x = a.solve_cyclic(top_right, bottom_left, &b).unwrap();
+			assert!((DVector::from_vec(x) - exact).norm() < 1e-12, "n = {n}");
+		}
+
+		// a zero first diagonal value takes the other choice of the Sherman–Morrison scale
+		let a = Tridiagonal::new(vec![1.0; 3], vec![0.0, 4.0, 4.0, 4.0], vec![1.0; 3]).unwrap();
+		let mut dense = a.to_dense();
+		dense[(0, 3)] = 1.0;
+		dense[(3, 0)] = 1.0;
+		let b = [1.0, 2.0, 3.0, 4.0];
+		let exact = dense.lu().solve(&DVector::from_row_slice(&b)).unwrap();
+		assert!((DVector::from_vec(a.solve_cyclic(1.0, 1.0, &b).unwrap()) - exact).norm() < 1e-12);
+	}
+
+	#[test]
+	fn zero_pivot_and_wrong_lengths_are_errors() {
+		let a = Tridiagonal::new(vec![1.0], vec![1.0, 1.0], vec![1.0]).unwrap();
+		assert_eq!(a.solve(&[1.0, 2.0]), Err(Error::ZeroPivot { row: 1 }));
+		assert!(matches!(matrix(3).solve(&[1.0]), Err(Error::DimensionMismatch { expected: 3, found: 1 })));
+		assert!(matches!(Tridiagonal::new(vec![], vec![1.0, 2.0], vec![1.0]), Err(Error::DimensionMismatch { .. })));
+	}
+}
@@ -0,0 +1,122 @@
+//! The Cauchy problem `y' = f(x, y)`, `y(x0) = y0` solved with a constant step `h`.
+
+/// Grid solution of the Cauchy problem.
+pub struct Solution {
+	/// Nodes `[x, y]`, starting from the initial condition.
+	pub points: Vec<[f64; 2]>,
+	/// Runge estimate of the error at every step, so `errors[i]` belongs to `points[i + 1]`.
+	pub errors: Vec<f64>,
+}
+
+/// One step of the explicit Euler method.
+pub fn euler_step(f: &impl Fn(f64, f64) -> f64, x: f64, y: f64, h: f64) -> f64 {
+	y + h * f(x, y)
+}
+
+/// One step of the fourth-order Runge–Kutta scheme from the section 4 exercise.
+pub fn runge_kutta_step(f: &impl Fn(f64, f64) -> f64, x: f64, y: f64, h: f64) -> f64 {
+	let k1 = h * f(x, y);
+	let k2 = h * f(x + h / 4.0, y + k1 / 4.0);
+	let k3 = h * f(x + h / 2.0, y + k2 / 2.0);
+	let k4 = h * f(x + h, y + k1 - 2.0 * k2 + 2.0 * k3);
+
+	y + 1.0 / 6.0 * (k1 + 4.0 * k3 + k4)
+}
+
+/// Runge estimate of the error of one `step` of order `p`: the step is repeated as two half steps
+/// and the difference is scaled by `1 / (2^p - 1)`.
+pub fn runge_error<F: Fn(f64, f64) -> f64>(step: impl Fn(&F, f64, f64, f64) -> f64, p: i32, f: &F, x: f64, y: f64, h: f64) -> f64 {
+	let y_full = step(f, x, y, h);
+	let y_other = step(f, x, y, h / 2.0);
+	let y_half = step(f, x + h / 2.0, y_other, h / 2.0);
+
+	((y_full - y_half) / (2.0_f64.powi(p) - 1.0)).abs()
+}
+
+/// Integrates from `left` while `x <= right`, applying a one-step method `step` of order `p`.
+pub fn solve<F: Fn(f64, f64) -> f64>(step: impl Fn(&F, f64, f64, f64) -> f64, p: i32, f: &F, left: f64, y0: f64, right: f64, h: f64) -> Solution {
+	let mut x0 = left;
+	let mut y0 = y0;
+
+	let mut points = vec![[x0, y0]];
+	let mut errors = Vec::new();
+
+	while x0 <= right {
+		let x1 = x0 + h;
+		let y1 = step(f, x0, y0, h);
+
+		errors.push(runge_error(&step, p, f, x0, y0, h));
+		points.push([x1, y1]);
+
+		x0 = x1;
+		y0 = y1;
+	}
+
+	Solution { points, errors }
+}
+
+/// Explicit Euler method, first order.
+pub fn euler(f: impl Fn(f64, f64) -> f64, left: f64, y0: f64, right: f64, h: f64) -> Solution {
+	solve(euler_step, 1, &f, left, y0, right, h)
+}
+
+/// The Runge–Kutta method of [`runge_kutta_step`].
+pub fn runge_kutta(f: impl Fn(f64, f64) -> f64, left: f64, y0: f64, right: f64, h: f64) -> Solution {
+	solve(runge_kutta_step, 4, &f, left, y0, right, h)
+}
+
+/// Explicit four-step Adams method, continuing the first four `points` while `x <= right`.
+pub fn adams(f: impl Fn(f64, f64) -> f64, points: &[[f64; 2]], right: f64, h: f64) -> Vec<[f64; 2]> {
+	let mut result = points[0..4].to_vec();
+
+	while result[result.len() - 1][0] <= right {
+		let last = &result[result.len() - 4..];
+
+		let [x0, y0] = last[3];
+		let [x1, y1] = last[2];
+		let [x2, y2] = last[1];
+		let [x3, y3] = last[0];
+
+		let y = y0 + h / 24.0 * (55.0 * f(x0, y0) - 59.0 * f(x1, y1) + 37.0 * f(x2, y2) - 9.0 * f(x3, y3));
+		result.push([x0 + h, y]);
+	}
+
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Ratio of the Runge estimate to the true error of two half steps, for `y' = y`, `y(0) = 1`.
+	fn estimate_ratio(step: impl Fn(&fn(f64, f64) -> f64, f64, f64, f64) -> f64, p: i32, h: f64) -> f64 {
+		let f: fn(f64, f64) -> f64 = |_, y| y;
+		let middle = step(&f, 0.0, 1.0, h / 2.0);
+		let y_half = step(&f, h / 2.0, middle, h / 2.0);
+		runge_error(&step, p, &f, 0.0, 1.0, h) / (h.exp() - y_half).abs()
+	}
+
+	#[test]
+	fn runge_error_uses_the_order_of_the_method() {
+		// with the wrong order the ratios would be far from 1, e.g. 1/3 for Euler taken as second order
+		assert!((estimate_ratio(euler_step, 1, 0.025) - 1.0).abs() < 0.05);
+		assert!((estimate_ratio(runge_kutta_step, 4, 0.025) - 1.0).abs() < 0.05);
+	}
+
+	#[test]
+	fn runge_kutta_is_fourth_order() {
+		let f = |_: f64, y: f64| y;
+		let error = |h: f64| (h.exp() - runge_kutta_step(&f, 0.0, 1.0, h)).abs();
+		let ratio = error(0.1) / error(0.05);
+		assert!((ratio - 32.0).abs() < 2.0, "{ratio}");
+	}
+
+	#[test]
+	fn euler_solution_covers_the_interval() {
+		let solution = euler(|_, y| y, 0.0, 1.0, 1.0, 0.001);
+		let &[x, y] = solution.points.last().unwrap();
+		assert!(x >= 1.0);
+		assert!((y - x.exp()).abs() < 2e-3 * x.exp());
+		assert_eq!(solution.errors.len(), solution.points.len() - 1);
+	}
+}
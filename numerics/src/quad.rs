@@ -0,0 +1,104 @@
+//! Quadrature formulas for `∫ f(x) dx` over `[a, b]`.
+
+use std::fmt::{Display, Formatter};
+
+/// Composite trapezoid rule on `n` equal intervals, second order.
+pub fn trapezoid(f: impl Fn(f64) -> f64, a: f64, b: f64, n: usize) -> f64 {
+	let h = (b - a) / n as f64;
+	let inner: f64 = (1..n).map(|i| f(a + i as f64 * h)).sum();
+
+	h * ((f(a) + f(b)) / 2.0 + inner)
+}
+
+/// Composite Simpson rule on `n` equal intervals, fourth order; `n` is rounded up to an even number.
+pub fn simpson(f: impl Fn(f64) -> f64, a: f64, b: f64, n: usize) -> f64 {
+	let n = n + n % 2;
+	let h = (b - a) / n as f64;
+	let inner: f64 = (1..n).map(|i| {
+		let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+		weight * f(a + i as f64 * h)
+	}).sum();
+
+	h / 3.0 * (f(a) + f(b) + inner)
+}
+
+/// Gauss–Legendre formula with six nodes, exact for polynomials up to degree 11.
+pub fn gauss_6(f: impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+	const NODES: [f64; 3] = [0.238_619_186_083_196_9, 0.661_209_386_466_264_5, 0.932_469_514_203_152];
+	const WEIGHTS: [f64; 3] = [0.467_913_934_572_691, 0.360_761_573_048_138_6, 0.171_324_492_379_170_3];
+
+	let center = (a + b) / 2.0;
+	let radius = (b - a) / 2.0;
+
+	radius * NODES.iter().zip(WEIGHTS).map(|(&t, w)| {
+		w * (f(center - radius * t) + f(center + radius * t))
+	}).sum::<f64>()
+}
+
+pub struct SolutionQuadrature {
+	/// Value of the rule on the finest grid.
+	pub value: f64,
+	/// Richardson extrapolation of the last two values.
+	pub refined: f64,
+	/// Number of intervals of the finest grid.
+	pub intervals: usize,
+	/// Runge estimate of the error of `value`.
+	pub error: f64,
+}
+
+/// Why [`automatic_step`] stopped without reaching its tolerance.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+	/// The error estimate was still `error` on the finest grid of `intervals`.
+	MaxHalvings { intervals: usize, error: f64 },
+	/// The rule gave NaN or infinity on `intervals`, as for an integrand with a singularity.
+	NotFinite { intervals: usize },
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::MaxHalvings { intervals, error } => write!(f, "the error estimate is still {error:e} with {intervals} intervals"),
+			Error::NotFinite { intervals } => write!(f, "the rule is not finite with {intervals} intervals, the integrand may have a singularity"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Doubles the number of intervals until the Runge estimate of the error drops below `epsilon`,
+/// at most `max_halvings` times.
+///
+/// `rule(n)` must evaluate a quadrature formula of order `p` on `n` intervals.
+pub fn automatic_step(rule: impl Fn(usize) -> f64, p: i32, epsilon: f64, max_halvings: u32) -> Result<SolutionQuadrature, Error> {
+	let denominator = 2.0_f64.powi(p) - 1.0;
+	let evaluate = |intervals: usize| {
+		let value = rule(intervals);
+		if value.is_finite() { Ok(value) } else { Err(Error::NotFinite { intervals }) }
+	};
+
+	let mut intervals = 4;
+	let mut previous = evaluate(2)?;
+	let mut halvings = 0;
+
+	loop {
+		let value = evaluate(intervals)?;
+		let error = (value - previous).abs() / denominator;
+
+		if error < epsilon {
+			return Ok(SolutionQuadrature {
+				value,
+				refined: value + (value - previous) / denominator,
+				intervals,
+				error,
+			});
+		}
+		if halvings == max_halvings {
+			return Err(Error::MaxHalvings { intervals, error });
+		}
+
+		previous = value;
+		intervals *= 2;
+		halvings += 1;
+	}
+}
@@ -0,0 +1,313 @@
+//! Iteration tables for lab reports, rendered as Markdown, LaTeX `tabular` or CSV.
+//!
+//! ```
+//! use numerics::report::{Format, Report, Table};
+//! use numerics::roots::{find_solution_simple_iterations, Point};
+//!
+//! let phi = |x: f64| x.cos();
+//! let f = |x: f64| x - x.cos();
+//! let solution = find_solution_simple_iterations(phi, f, Point::new(1.0, f), 1e-6, 1000).unwrap();
+//!
+//! let mut report = Report::new("x = cos x");
+//! report.add(Table::simple_iterations("Simple iterations", &solution));
+//! println!("{}", report.render(Format::Latex, 8));
+//! ```
+
+use crate::ode;
+use crate::roots::SolutionSimpleIterations;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+	Markdown,
+	/// A `table` environment with a `tabular` and a caption.
+	Latex,
+	Csv,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+	Integer(i64),
+	Number(f64),
+	Text(String),
+	/// A value that does not exist for this row, like the error estimate of the first iteration.
+	Empty,
+}
+
+impl From<usize> for Cell {
+	fn from(value: usize) -> Self {
+		Cell::Integer(value as i64)
+	}
+}
+
+impl From<i64> for Cell {
+	fn from(value: i64) -> Self {
+		Cell::Integer(value)
+	}
+}
+
+impl From<f64> for Cell {
+	fn from(value: f64) -> Self {
+		Cell::Number(value)
+	}
+}
+
+impl From<Option<f64>> for Cell {
+	fn from(value: Option<f64>) -> Self {
+		value.map_or(Cell::Empty, Cell::Number)
+	}
+}
+
+impl From<&str> for Cell {
+	fn from(value: &str) -> Self {
+		Cell::Text(value.to_string())
+	}
+}
+
+impl From<String> for Cell {
+	fn from(value: String) -> Self {
+		Cell::Text(value)
+	}
+}
+
+/// Mantissa and exponent of `value` when it is too small or too large for fixed notation.
+fn scientific(value: f64, precision: usize) -> Option<(String, i32)> {
+	let magnitude = value.abs();
+	if value == 0.0 || !value.is_finite() || (1e-3..1e6).contains(&magnitude) {
+		return None;
+	}
+
+	let formatted = format!("{value:.precision$e}");
+	let (mantissa, exponent) = formatted.split_once('e').unwrap();
+	Some((mantissa.to_string(), exponent.parse().unwrap()))
+}
+
+impl Cell {
+	/// Numbers get `precision` digits after the point, in scientific notation below `1e-3` and from `1e6` on.
+	pub fn render(&self, format: Format, precision: usize) -> String {
+		match self {
+			Cell::Integer(value) => value.to_string(),
+			Cell::Number(value) => match (scientific(*value, precision), format) {
+				(Some((mantissa, exponent)), Format::Latex) => format!("${mantissa} \\cdot 10^{{{exponent}}}$"),
+				(Some((mantissa, exponent)), _) => format!("{mantissa}e{exponent}"),
+				(None, _) => format!("{value:.precision$}"),
+			},
+			Cell::Text(text) => escape(text, format),
+			Cell::Empty => match format {
+				Format::Markdown | Format::Latex => "—".to_string(),
+				Format::Csv => String::new(),
+			},
+		}
+	}
+}
+
+/// `text` with the characters special to `format` escaped, and quoted in CSV when needed.
+pub fn escape(text: &str, format: Format) -> String {
+	match format {
+		Format::Markdown => text.replace('|', "\\|"),
+		Format::Latex => text.chars().map(|c| match c {
+			'\\' => "\\textbackslash{}".to_string(),
+			'&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{c}"),
+			'~' => "\\textasciitilde{}".to_string(),
+			'^' => "\\textasciicircum{}".to_string(),
+			c => c.to_string(),
+		}).collect(),
+		Format::Csv if text.contains([',', '"', '\n']) => format!("\"{}\"", text.replace('"', "\"\"")),
+		Format::Csv => text.to_string(),
+	}
+}
+
+/// A table of one method run.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+	pub caption: String,
+	pub columns: Vec<String>,
+	pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+	pub fn new(caption: &str, columns: &[&str]) -> Self {
+		Self {
+			caption: caption.to_string(),
+			columns: columns.iter().map(|column| column.to_string()).collect(),
+			rows: Vec::new(),
+		}
+	}
+
+	/// Appends a row, which must have a cell per column.
+	pub fn row(&mut self, row: Vec<Cell>) {
+		assert_eq!(row.len(), self.columns.len(), "a row of '{}' has a wrong number of cells", self.caption);
+		self.rows.push(row);
+	}
+
+	/// Iteration number, `x`, `f(x)` and the error estimate `|x_k - x_{k-1}|` of every approximation.
+	pub fn simple_iterations(caption: &str, solution: &SolutionSimpleIterations) -> Self {
+		let mut table = Self::new(caption, &["k", "x", "f(x)", "error estimate"]);
+
+		let points = solution.path.iter().chain([&solution.current]);
+		let mut previous: Option<f64> = None;
+		for (k, point) in points.enumerate() {
+			let error = previous.map(|previous| (point.x - previous).abs());
+			table.row(vec![k.into(), point.x.into(), point.y.into(), error.into()]);
+			previous = Some(point.x);
+		}
+
+		table
+	}
+
+	/// Step number, `x`, `y` and the Runge estimate of the error of every node.
+	pub fn cauchy(caption: &str, solution: &ode::Solution) -> Self {
+		let mut table = Self::new(caption, &["k", "x", "y", "Runge error"]);
+
+		let errors = [None].into_iter().chain(solution.errors.iter().copied().map(Some));
+		for (k, (&[x, y], error)) in solution.points.iter().zip(errors).enumerate() {
+			table.row(vec![k.into(), x.into(), y.into(), error.into()]);
+		}
+
+		table
+	}
+
+	pub fn render(&self, format: Format, precision: usize) -> String {
+		let header: Vec<String> = self.columns.iter().map(|column| escape(column, format)).collect();
+		let rows: Vec<Vec<String>> = self.rows.iter()
+			.map(|row| row.iter().map(|cell| cell.render(format, precision)).collect())
+			.collect();
+
+		match format {
+			Format::Markdown => {
+				let mut result = String::new();
+				if !self.caption.is_empty() {
+					result += &format!("**{}**\n\n", escape(&self.caption, format));
+				}
+				result += &format!("| {} |\n", header.join(" | "));
+				result += &format!("|{}\n", "---:|".repeat(header.len()));
+				for row in rows {
+					result += &format!("| {} |\n", row.join(" | "));
+				}
+				result
+			}
+			Format::Latex => {
+				let mut result = String::from("\\begin{table}[h]\n\\centering\n");
+				if !self.caption.is_empty() {
+					result += &format!("\\caption{{{}}}\n", escape(&self.caption, format));
+				}
+				result += &format!("\\begin{{tabular}}{{|{}}}\n\\hline\n", "r|".repeat(header.len()));
+				result += &format!("{} \\\\\n\\hline\n", header.join(" & "));
+				for row in rows {
+					result += &format!("{} \\\\\n", row.join(" & "));
+				}
+				result + "\\hline\n\\end{tabular}\n\\end{table}\n"
+			}
+			Format::Csv => {
+				let mut result = header.join(",") + "\n";
+				for row in rows {
+					result += &(row.join(",") + "\n");
+				}
+				result
+			}
+		}
+	}
+}
+
+/// Tables of several method runs under one title.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+	pub title: String,
+	pub tables: Vec<Table>,
+}
+
+impl Report {
+	pub fn new(title: &str) -> Self {
+		Self { title: title.to_string(), tables: Vec::new() }
+	}
+
+	pub fn add(&mut self, table: Table) -> &mut Self {
+		self.tables.push(table);
+		self
+	}
+
+	/// The title as a heading and the tables one after another; in CSV the tables are separated by
+	/// a blank line and preceded by their caption as a `#` comment.
+	pub fn render(&self, format: Format, precision: usize) -> String {
+		let tables: Vec<String> = self.tables.iter().map(|table| match format {
+			Format::Csv => format!("# {}\n{}", table.caption, table.render(format, precision)),
+			_ => table.render(format, precision),
+		}).collect();
+
+		let title = match format {
+			Format::Markdown => format!("# {}\n\n", escape(&self.title, format)),
+			Format::Latex => format!("\\section*{{{}}}\n\n", escape(&self.title, format)),
+			Format::Csv => format!("# {}\n\n", self.title),
+		};
+
+		title + &tables.join("\n")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn table() -> Table {
+		let mut table = Table::new("Run_1 | a&b", &["k", "x, y", "err_%"]);
+		table.row(vec![1usize.into(), 0.5.into(), None.into()]);
+		table.row(vec![2usize.into(), 1.23456e-5.into(), 2.5e7.into()]);
+		table.row(vec!["a|b".into(), (-3.0).into(), 1e-3.into()]);
+		table
+	}
+
+	#[test]
+	fn markdown() {
+		assert_eq!(table().render(Format::Markdown, 3), concat!(
+			"**Run_1 \\| a&b**\n\n",
+			"| k | x, y | err_% |\n",
+			"|---:|---:|---:|\n",
+			"| 1 | 0.500 | — |\n",
+			"| 2 | 1.235e-5 | 2.500e7 |\n",
+			"| a\\|b | -3.000 | 0.001 |\n",
+		));
+	}
+
+	#[test]
+	fn latex() {
+		assert_eq!(table().render(Format::Latex, 3), concat!(
+			"\\begin{table}[h]\n\\centering\n",
+			"\\caption{Run\\_1 | a\\&b}\n",
+			"\\begin{tabular}{|r|r|r|}\n\\hline\n",
+			"k & x, y & err\\_\\% \\\\\n\\hline\n",
+			"1 & 0.500 & — \\\\\n",
+			"2 & $1.235 \\cdot 10^{-5}$ & $2.500 \\cdot 10^{7}$ \\\\\n",
+			"a|b & -3.000 & 0.001 \\\\\n",
+			"\\hline\n\\end{tabular}\n\\end{table}\n",
+		));
+	}
+
+	#[test]
+	fn csv() {
+		assert_eq!(table().render(Format::Csv, 3), concat!(
+			"k,\"x, y\",err_%\n",
+			"1,0.500,\n",
+			"2,1.235e-5,2.500e7\n",
+			"a|b,-3.000,0.001\n",
+		));
+		assert_eq!(escape("say \"hi\"", Format::Csv), "\"say \"\"hi\"\"\"");
+	}
+
+	#[test]
+	fn report_of_several_tables() {
+		let mut report = Report::new("x_1");
+		report.add(table()).add(Table::new("empty", &["k"]));
+		let csv = report.render(Format::Csv, 3);
+		assert!(csv.starts_with("# x_1\n\n# Run_1 | a&b\nk,"));
+		assert!(csv.ends_with("\n\n# empty\nk\n"));
+		assert!(report.render(Format::Latex, 3).starts_with("\\section*{x\\_1}\n\n\\begin{table}"));
+	}
+
+	#[test]
+	fn precision_and_notation() {
+		assert_eq!(Cell::from(123456.789).render(Format::Markdown, 2), "123456.79");
+		assert_eq!(Cell::from(1e6).render(Format::Markdown, 2), "1.00e6");
+		assert_eq!(Cell::from(-9.99e-4).render(Format::Csv, 1), "-1.0e-3");
+		assert_eq!(Cell::from(0.0).render(Format::Latex, 4), "0.0000");
+		assert_eq!(Cell::from(7usize).render(Format::Latex, 4), "7");
+	}
+}
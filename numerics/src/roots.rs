@@ -0,0 +1,190 @@
+//! Root finding for scalar equations `f(x) = 0`.
+
+use std::fmt::{Display, Formatter};
+
+/// A point `(x, f(x))` visited by a method.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Point {
+	pub x: f64,
+	pub y: f64,
+}
+
+impl Point {
+	/// Evaluates `function` at `x`.
+	pub fn new(x: f64, function: impl Fn(f64) -> f64) -> Self {
+		Self {
+			x,
+			y: function(x),
+		}
+	}
+}
+
+impl Display for Point {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "x: {} | y: {}", self.x, self.y)
+	}
+}
+
+/// Why a method stopped without reaching its tolerance.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+	/// The tolerance was not reached within `iterations`; `last` is the final approximation.
+	MaxIterations { iterations: usize, last: Point },
+	/// The approximation or the function value of `iteration` is NaN or infinite.
+	NotFinite { iteration: usize },
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::MaxIterations { iterations, last } => write!(f, "no convergence after {iterations} iterations, the last approximation is x = {}", last.x),
+			Error::NotFinite { iteration } => write!(f, "iteration {iteration} gave a value that is not finite, the method diverges"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+/// Fails with [`Error::NotFinite`] unless `point` and its value are finite numbers.
+fn check_finite(point: Point, iteration: usize) -> Result<(), Error> {
+	if point.x.is_finite() && point.y.is_finite() {
+		Ok(())
+	} else {
+		Err(Error::NotFinite { iteration })
+	}
+}
+
+pub struct SolutionSimpleIterations {
+	/// Every point visited before the last one.
+	pub path: Vec<Point>,
+	pub current: Point,
+	pub iterations: usize,
+}
+
+/// Solves `x = phi(x)` by simple iterations starting from `current`.
+///
+/// `function` is only evaluated to report `f(x)` along the path. The iterations stop once two
+/// successive approximations are closer than `0.1 * epsilon`, and fail after `max_iterations`.
+pub fn find_solution_simple_iterations(
+	phi: impl Fn(f64) -> f64,
+	function: impl Fn(f64) -> f64,
+	mut current: Point,
+	epsilon: f64,
+	max_iterations: usize,
+) -> Result<SolutionSimpleIterations, Error> {
+	let corrected_epsilon = 0.1 * epsilon;
+
+	let mut path = Vec::new();
+	let mut iterations = 0;
+
+	let mut measurement_error = f64::MAX;
+
+	while measurement_error >= corrected_epsilon {
+		if iterations == max_iterations {
+			return Err(Error::MaxIterations { iterations, last: current });
+		}
+		path.push(current);
+
+		let next = phi(current.x);
+		let next_value = function(next);
+
+		measurement_error = (current.x - next).abs();
+		iterations += 1;
+
+		current = Point {
+			x: next,
+			y: next_value,
+		};
+		check_finite(current, iterations)?;
+	}
+
+	Ok(SolutionSimpleIterations {
+		path,
+		current,
+		iterations,
+	})
+}
+
+pub struct SolutionSplit {
+	/// The end of the final interval with the smaller `|f(x)|`.
+	pub current: Point,
+	/// The other end of the final interval.
+	pub previous: Point,
+	pub iterations: usize,
+}
+
+/// Halves `[left, right]` until it is shorter than `2 * epsilon`, failing after `max_iterations`.
+///
+/// `f(left)` and `f(right)` are expected to have different signs.
+pub fn find_solution_split(
+	function: impl Fn(f64) -> f64,
+	mut left: Point,
+	mut right: Point,
+	epsilon: f64,
+	max_iterations: usize,
+) -> Result<SolutionSplit, Error> {
+	let epsilon = 2.0 * epsilon;
+
+	let mut iterations = 0;
+	let mut measurement_error = f64::MAX;
+
+	while measurement_error > epsilon {
+		if iterations == max_iterations {
+			return Err(Error::MaxIterations { iterations, last: left });
+		}
+		let center = Point::new((left.x + right.x) / 2.0, &function);
+		check_finite(center, iterations + 1)?;
+
+		if left.y.is_sign_positive() == center.y.is_sign_positive() {
+			left = center;
+		} else {
+			right = center;
+		}
+
+		iterations += 1;
+		measurement_error = (left.x - right.x).abs();
+	}
+
+	Ok(if left.y.abs() < right.y.abs() {
+		SolutionSplit { current: left, previous: right, iterations }
+	} else {
+		SolutionSplit { current: right, previous: left, iterations }
+	})
+}
+
+pub struct SolutionNewtonSecant {
+	pub current: Point,
+	pub iterations: usize,
+}
+
+/// Refines a root with the secant method until two successive approximations are closer than `epsilon`,
+/// failing after `max_iterations` or when the secant is horizontal.
+pub fn find_solution_newton_secant(
+	function: impl Fn(f64) -> f64,
+	mut current: Point,
+	mut previous: Point,
+	epsilon: f64,
+	max_iterations: usize,
+) -> Result<SolutionNewtonSecant, Error> {
+	let mut iterations = 0;
+	let mut measurement_error = f64::MAX;
+
+	while measurement_error > epsilon {
+		if iterations == max_iterations {
+			return Err(Error::MaxIterations { iterations, last: current });
+		}
+		let next = current.x - (current.x - previous.x) / (current.y - previous.y) * current.y;
+
+		previous = current;
+		current = Point::new(next, &function);
+
+		iterations += 1;
+		check_finite(current, iterations)?;
+		measurement_error = (current.x - previous.x).abs();
+	}
+
+	Ok(SolutionNewtonSecant {
+		current,
+		iterations,
+	})
+}
@@ -0,0 +1,231 @@
+//! Tensor-product bicubic splines on rectangular grids.
+
+use nalgebra::{DMatrix, Matrix4, RowVector4, Vector4};
+use crate::linalg;
+use super::{check_nodes, Boundary, Error, HermiteSpline};
+
+/// Interpolant of `f(x, y)` from its values on a grid, a bicubic polynomial on every cell.
+///
+/// The derivatives at the nodes come from one-dimensional splines: `∂f/∂x` along every row of the grid,
+/// `∂f/∂y` along every column, and `∂²f/∂x∂y` along the columns of `∂f/∂x`.
+pub struct BicubicSpline {
+	x: Vec<f64>,
+	y: Vec<f64>,
+	/// `c[(a, b)]` of every cell `(i, j)` at `i * (ny - 1) + j`, the coefficient of `(x - x[i])^a (y - y[j])^b`.
+	coefficients: Vec<Matrix4<f64>>,
+}
+
+impl BicubicSpline {
+	/// `z[(i, j)]` is the value at `(x[i], y[j])`; `x` and `y` must be sorted without repetitions.
+	///
+	/// `boundary` applies to both directions; `Clamped` sets the same slopes across every edge,
+	/// which mostly makes sense as `Clamped(0.0, 0.0)` for a surface flat at its edges.
+	pub fn interpolating(x: &[f64], y: &[f64], z: &DMatrix<f64>, boundary: Boundary) -> Result<Self, Error> {
+		let (nx, ny) = (x.len(), y.len());
+		if z.nrows() != nx {
+			return Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: nx, found: z.nrows() }));
+		}
+		if z.ncols() != ny {
+			return Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: ny, found: z.ncols() }));
+		}
+		check_nodes(x)?;
+		check_nodes(y)?;
+
+		// slopes along x of every column of `values`, and along y of every row
+		let along_x = |values: &DMatrix<f64>| -> Result<DMatrix<f64>, Error> {
+			let mut slopes = DMatrix::zeros(nx, ny);
+			for j in 0..ny {
+				let column: Vec<f64> = values.column(j).iter().copied().collect();
+				let spline = HermiteSpline::interpolating(x, &column, boundary)?;
+				slopes.column_mut(j).copy_from_slice(spline.slopes());
+			}
+			Ok(slopes)
+		};
+		let along_y = |values: &DMatrix<f64>| -> Result<DMatrix<f64>, Error> {
+			let mut slopes = DMatrix::zeros(nx, ny);
+			for i in 0..nx {
+				let row: Vec<f64> = values.row(i).iter().copied().collect();
+				let spline = HermiteSpline::interpolating(y, &row, boundary)?;
+				for (j, &slope) in spline.slopes().iter().enumerate() {
+					slopes[(i, j)] = slope;
+				}
+			}
+			Ok(slopes)
+		};
+
+		let p = along_x(z)?;
+		let q = along_y(z)?;
+		let r = along_y(&p)?;
+
+		let mut coefficients = Vec::with_capacity((nx - 1) * (ny - 1));
+		for i in 0..nx - 1 {
+			for j in 0..ny - 1 {
+				// rows and columns: value at the first node, slope there, value at the second node, slope there
+				let corner = |a: usize, b: usize| {
+					let (i, j) = (i + a / 2, j + b / 2);
+					match (a % 2, b % 2) {
+						(0, 0) => z[(i, j)],
+						(1, 0) => p[(i, j)],
+						(0, 1) => q[(i, j)],
+						_ => r[(i, j)],
+					}
+				};
+				let g = Matrix4::from_fn(corner);
+				coefficients.push(hermite_to_power(x[i + 1] - x[i]) * g * hermite_to_power(y[j + 1] - y[j]).transpose());
+			}
+		}
+
+		Ok(Self { x: x.to_vec(), y: y.to_vec(), coefficients })
+	}
+
+	pub fn nodes_x(&self) -> &[f64] {
+		&self.x
+	}
+
+	pub fn nodes_y(&self) -> &[f64] {
+		&self.y
+	}
+
+	/// Value at `(x, y)`; outside the grid the edge cells are extrapolated.
+	pub fn solve(&self, x: f64, y: f64) -> f64 {
+		self.evaluate(x, y, 0, 0)
+	}
+
+	/// `∂f/∂x` at `(x, y)`.
+	pub fn partial_x(&self, x: f64, y: f64) -> f64 {
+		self.evaluate(x, y, 1, 0)
+	}
+
+	/// `∂f/∂y` at `(x, y)`.
+	pub fn partial_y(&self, x: f64, y: f64) -> f64 {
+		self.evaluate(x, y, 0, 1)
+	}
+
+	/// `∂²f/∂x∂y` at `(x, y)`.
+	pub fn partial_xy(&self, x: f64, y: f64) -> f64 {
+		self.evaluate(x, y, 1, 1)
+	}
+
+	/// Values at every point `[x, y]`.
+	pub fn eval_many(&self, points: &[[f64; 2]]) -> Vec<f64> {
+		points.iter().map(|&[x, y]| self.solve(x, y)).collect()
+	}
+
+	/// Values at every `(x[i], y[j])` as `result[(i, j)]`, finding the cell of every `x` and `y` only once.
+	pub fn eval_grid(&self, x: &[f64], y: &[f64]) -> DMatrix<f64> {
+		let rows: Vec<(usize, RowVector4<f64>)> = x.iter().map(|&x| {
+			let k = cell(&self.x, x);
+			(k, powers(x - self.x[k], 0))
+		}).collect();
+		let columns: Vec<(usize, Vector4<f64>)> = y.iter().map(|&y| {
+			let l = cell(&self.y, y);
+			(l, powers(y - self.y[l], 0).transpose())
+		}).collect();
+
+		DMatrix::from_fn(x.len(), y.len(), |i, j| {
+			let ((k, u), (l, v)) = (rows[i], columns[j]);
+			(u * self.coefficients[k * (self.y.len() - 1) + l] * v)[0]
+		})
+	}
+
+	/// Derivative of order `dx` in `x` and `dy` in `y`, at most 2 each.
+	fn evaluate(&self, x: f64, y: f64, dx: usize, dy: usize) -> f64 {
+		let (k, l) = (cell(&self.x, x), cell(&self.y, y));
+		let u = powers(x - self.x[k], dx);
+		let v = powers(y - self.y[l], dy);
+
+		(u * self.coefficients[k * (self.y.len() - 1) + l] * v.transpose())[0]
+	}
+}
+
+/// Index `k` of the interval `[nodes[k], nodes[k + 1]]` that holds `t`, the end ones extending beyond the nodes.
+fn cell(nodes: &[f64], t: f64) -> usize {
+	nodes[1..nodes.len() - 1].partition_point(|&node| node <= t)
+}
+
+/// Derivative of order `order` of `(1, u, u^2, u^3)`.
+fn powers(u: f64, order: usize) -> RowVector4<f64> {
+	match order {
+		0 => RowVector4::new(1.0, u, u * u, u * u * u),
+		1 => RowVector4::new(0.0, 1.0, 2.0 * u, 3.0 * u * u),
+		2 => RowVector4::new(0.0, 0.0, 2.0, 6.0 * u),
+		_ => RowVector4::zeros(),
+	}
+}
+
+/// Maps `(f0, s0, f1, s1)` of a cubic Hermite segment of length `h` to its power form `(c0, c1, c2, c3)`.
+fn hermite_to_power(h: f64) -> Matrix4<f64> {
+	let (h2, h3) = (h * h, h * h * h);
+	Matrix4::from_rows(&[
+		RowVector4::new(1.0, 0.0, 0.0, 0.0),
+		RowVector4::new(0.0, 1.0, 0.0, 0.0),
+		RowVector4::new(-3.0 / h2, -2.0 / h, 3.0 / h2, -1.0 / h),
+		RowVector4::new(2.0 / h3, 1.0 / h2, -2.0 / h3, 1.0 / h2),
+	])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A bicubic polynomial with every power of `x` and `y` up to 3.
+	fn f(x: f64, y: f64) -> f64 {
+		(x.powi(3) - 2.0 * x + 1.0) * (0.5 * y.powi(3) + y * y - 3.0) + x * x * y - 4.0 * x * y.powi(3)
+	}
+
+	fn f_x(x: f64, y: f64) -> f64 {
+		(3.0 * x * x - 2.0) * (0.5 * y.powi(3) + y * y - 3.0) + 2.0 * x * y - 4.0 * y.powi(3)
+	}
+
+	fn f_y(x: f64, y: f64) -> f64 {
+		(x.powi(3) - 2.0 * x + 1.0) * (1.5 * y * y + 2.0 * y) + x * x - 12.0 * x * y * y
+	}
+
+	fn f_xy(x: f64, y: f64) -> f64 {
+		(3.0 * x * x - 2.0) * (1.5 * y * y + 2.0 * y) + 2.0 * x - 12.0 * y * y
+	}
+
+	fn spline(boundary: Boundary) -> BicubicSpline {
+		let x = [-1.0, -0.4, 0.3, 0.5, 1.2, 2.0];
+		let y = [0.0, 0.7, 1.0, 1.8, 2.5];
+		let z = DMatrix::from_fn(x.len(), y.len(), |i, j| f(x[i], y[j]));
+		BicubicSpline::interpolating(&x, &y, &z, boundary).unwrap()
+	}
+
+	fn points() -> Vec<[f64; 2]> {
+		(0..=30).flat_map(|i| (0..=25).map(move |j| [-1.0 + 0.1 * i as f64, 0.1 * j as f64])).collect()
+	}
+
+	#[test]
+	fn bicubic_data_is_reproduced() {
+		let spline = spline(Boundary::NotAKnot);
+		for [x, y] in points() {
+			assert!((spline.solve(x, y) - f(x, y)).abs() < 1e-10, "at ({x}, {y})");
+			assert!((spline.partial_x(x, y) - f_x(x, y)).abs() < 1e-9, "at ({x}, {y})");
+			assert!((spline.partial_y(x, y) - f_y(x, y)).abs() < 1e-9, "at ({x}, {y})");
+			assert!((spline.partial_xy(x, y) - f_xy(x, y)).abs() < 1e-8, "at ({x}, {y})");
+		}
+	}
+
+	#[test]
+	fn grid_evaluation_matches_points() {
+		let spline = spline(Boundary::Natural);
+		let (x, y) = ([-1.5, -1.0, 0.3, 0.31, 1.9, 2.4], [-0.2, 0.0, 1.0, 2.5, 3.0]);
+		let grid = spline.eval_grid(&x, &y);
+		for (i, &x) in x.iter().enumerate() {
+			let row: Vec<[f64; 2]> = y.iter().map(|&y| [x, y]).collect();
+			assert_eq!(grid.row(i).iter().copied().collect::<Vec<_>>(), spline.eval_many(&row));
+		}
+		// natural splines still interpolate at the nodes
+		for (&x, &y) in spline.nodes_x().iter().zip(spline.nodes_y()) {
+			assert!((spline.solve(x, y) - f(x, y)).abs() < 1e-12);
+		}
+	}
+
+	#[test]
+	fn dimension_mismatch() {
+		let z = DMatrix::zeros(3, 4);
+		let result = BicubicSpline::interpolating(&[0.0, 1.0, 2.0], &[0.0, 1.0, 2.0], &z, Boundary::Natural);
+		assert!(matches!(result, Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: 3, found: 4 }))));
+	}
+}
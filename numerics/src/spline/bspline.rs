@@ -0,0 +1,310 @@
+//! Splines of any degree as combinations of B-splines over a knot vector.
+
+use crate::linalg::{self, Banded};
+use super::{check_nodes, Error};
+
+/// `Σ c[i] B[i, p](t)`, where the B-splines `B[i, p]` of degree `p` are defined by non-decreasing `knots`.
+///
+/// With `m` coefficients there are `m + p + 1` knots, and the spline is defined on `[knots[p], knots[m]]`.
+/// A knot repeated `r` times leaves `p - r` continuous derivatives there.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BSpline {
+	degree: usize,
+	knots: Vec<f64>,
+	coefficients: Vec<f64>,
+}
+
+impl BSpline {
+	pub fn new(degree: usize, knots: Vec<f64>, coefficients: Vec<f64>) -> Result<Self, Error> {
+		if coefficients.len() < degree + 1 {
+			return Err(Error::TooFewNodes { needed: degree + 1, found: coefficients.len() });
+		}
+		if knots.len() != coefficients.len() + degree + 1 {
+			return Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: coefficients.len() + degree + 1, found: knots.len() }));
+		}
+		if let Some(index) = knots.windows(2).position(|pair| pair[0] > pair[1] || pair[0].is_nan()) {
+			return Err(Error::DecreasingKnot { index: index + 1 });
+		}
+		if knots[degree] >= knots[coefficients.len()] {
+			return Err(Error::DecreasingKnot { index: coefficients.len() });
+		}
+
+		Ok(Self { degree, knots, coefficients })
+	}
+
+	/// The spline of `degree` through the points `(x[i], y[i])`.
+	///
+	/// The inner knots are averages of `degree` consecutive nodes and the ends are repeated `degree + 1` times,
+	/// which keeps the banded collocation system well conditioned.
+	/// With degree 0 the knots are the midpoints between the nodes, and the spline is a step function.
+	pub fn interpolating(x: &[f64], y: &[f64], degree: usize) -> Result<Self, Error> {
+		let n = x.len();
+		if y.len() != n {
+			return Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: n, found: y.len() }));
+		}
+		// a single node leaves the domain empty
+		let needed = (degree + 1).max(2);
+		if n < needed {
+			return Err(Error::TooFewNodes { needed, found: n });
+		}
+		check_nodes(x)?;
+
+		let mut knots = vec![x[0]; degree + 1];
+		for j in 1..n - degree {
+			let window = if degree == 0 { &x[j - 1..=j] } else { &x[j..j + degree] };
+			knots.push(window.iter().sum::<f64>() / window.len() as f64);
+		}
+		knots.extend(vec![x[n - 1]; degree + 1]);
+
+		let mut matrix = Banded::zeros(n, degree, degree);
+		for (i, &x) in x.iter().enumerate() {
+			let span = find_span(&knots, degree, n, x);
+			for (k, value) in basis_functions(&knots, degree, span, x).into_iter().enumerate() {
+				*matrix.get_mut(i, span - degree + k) = value;
+			}
+		}
+
+		let coefficients = matrix.solve(y)?;
+		Self::new(degree, knots, coefficients)
+	}
+
+	/// The spline of `degree` with the given `knots` closest to the points `(x[i], y[i])` in least squares.
+	///
+	/// The normal equations are a symmetric band system, positive definite as long as every B-spline
+	/// is non-zero at some of the `x[i]` (the Schoenberg–Whitney conditions).
+	pub fn least_squares(x: &[f64], y: &[f64], degree: usize, knots: Vec<f64>) -> Result<Self, Error> {
+		if y.len() != x.len() {
+			return Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: x.len(), found: y.len() }));
+		}
+		let m = knots.len().saturating_sub(degree + 1);
+		let spline = Self::new(degree, knots, vec![0.0; m])?;
+
+		let mut normal = Banded::zeros(m, degree, degree);
+		let mut rhs = vec![0.0; m];
+		for (&x, &y) in x.iter().zip(y) {
+			let span = spline.span(x);
+			let values = basis_functions(&spline.knots, degree, span, x);
+			for (a, &first) in values.iter().enumerate() {
+				rhs[span - degree + a] += first * y;
+				for (b, &second) in values.iter().enumerate() {
+					*normal.get_mut(span - degree + a, span - degree + b) += first * second;
+				}
+			}
+		}
+
+		let coefficients = normal.ldl()?.solve(&rhs)?;
+		Ok(Self { coefficients, ..spline })
+	}
+
+	pub fn degree(&self) -> usize {
+		self.degree
+	}
+
+	pub fn knots(&self) -> &[f64] {
+		&self.knots
+	}
+
+	pub fn coefficients(&self) -> &[f64] {
+		&self.coefficients
+	}
+
+	/// The interval `[knots[p], knots[m]]` where the B-splines sum to one.
+	pub fn domain(&self) -> (f64, f64) {
+		(self.knots[self.degree], self.knots[self.coefficients.len()])
+	}
+
+	fn span(&self, t: f64) -> usize {
+		find_span(&self.knots, self.degree, self.coefficients.len(), t)
+	}
+
+	/// Value at `t` by the de Boor algorithm; outside the domain the end polynomials are extrapolated.
+	pub fn solve(&self, t: f64) -> f64 {
+		let (p, knots) = (self.degree, &self.knots);
+		let k = self.span(t);
+
+		let mut d: Vec<f64> = self.coefficients[k - p..=k].to_vec();
+		for r in 1..=p {
+			for j in (r..=p).rev() {
+				let left = knots[j + k - p];
+				let alpha = (t - left) / (knots[j + 1 + k - r] - left);
+				d[j] = (1.0 - alpha) * d[j - 1] + alpha * d[j];
+			}
+		}
+
+		d[p]
+	}
+
+	pub fn eval_many(&self, t: &[f64]) -> Vec<f64> {
+		t.iter().map(|&t| self.solve(t)).collect()
+	}
+
+	/// The derivative, a spline of one degree lower on the same knots without the outer ones.
+	pub fn derivative(&self) -> Self {
+		let p = self.degree;
+		if p == 0 {
+			return Self { coefficients: vec![0.0; self.coefficients.len()], ..self.clone() };
+		}
+
+		let coefficients = self.coefficients.windows(2).enumerate().map(|(i, pair)| {
+			let width = self.knots[i + p + 1] - self.knots[i + 1];
+			if width == 0.0 { 0.0 } else { p as f64 * (pair[1] - pair[0]) / width }
+		}).collect();
+
+		Self {
+			degree: p - 1,
+			knots: self.knots[1..self.knots.len() - 1].to_vec(),
+			coefficients,
+		}
+	}
+
+	/// Inserts the knot `t` inside the domain by Boehm's algorithm, adding one coefficient without changing the spline.
+	///
+	/// Fails with [`Error::KnotOutsideDomain`] unless `t` lies strictly inside the domain.
+	pub fn insert_knot(&mut self, t: f64) -> Result<(), Error> {
+		let (start, end) = self.domain();
+		if !(start < t && t < end) {
+			return Err(Error::KnotOutsideDomain { knot: t });
+		}
+
+		let (p, knots) = (self.degree, &self.knots);
+		let k = self.span(t);
+		let old = &self.coefficients;
+
+		let mut coefficients = Vec::with_capacity(old.len() + 1);
+		coefficients.extend_from_slice(&old[..=k - p]);
+		for i in k - p + 1..=k {
+			let alpha = (t - knots[i]) / (knots[i + p] - knots[i]);
+			coefficients.push(alpha * old[i] + (1.0 - alpha) * old[i - 1]);
+		}
+		coefficients.extend_from_slice(&old[k..]);
+
+		self.knots.insert(k + 1, t);
+		self.coefficients = coefficients;
+		Ok(())
+	}
+}
+
+/// Knots of degree `degree` over `breakpoints`, with both ends repeated `degree + 1` times,
+/// so that the spline starts and ends at its first and last coefficient.
+pub fn clamped_knots(degree: usize, breakpoints: &[f64]) -> Vec<f64> {
+	let (first, last) = (breakpoints[0], breakpoints[breakpoints.len() - 1]);
+	let mut knots = vec![first; degree];
+	knots.extend_from_slice(breakpoints);
+	knots.extend(vec![last; degree]);
+	knots
+}
+
+/// Index `k` with `knots[k] <= t < knots[k + 1]` among the `count` B-splines, clamped to `[degree, count - 1]`.
+pub fn find_span(knots: &[f64], degree: usize, count: usize, t: f64) -> usize {
+	degree + knots[degree + 1..count].partition_point(|&knot| knot <= t)
+}
+
+/// Values at `t` of the B-splines `B[span - degree], ..., B[span]`, the only ones non-zero there,
+/// by the Cox–de Boor recursion.
+pub fn basis_functions(knots: &[f64], degree: usize, span: usize, t: f64) -> Vec<f64> {
+	let mut values = vec![0.0; degree + 1];
+	let mut left = vec![0.0; degree + 1];
+	let mut right = vec![0.0; degree + 1];
+	values[0] = 1.0;
+
+	for j in 1..=degree {
+		left[j] = t - knots[span + 1 - j];
+		right[j] = knots[span + j] - t;
+
+		let mut saved = 0.0;
+		for r in 0..j {
+			let term = values[r] / (right[r + 1] + left[j - r]);
+			values[r] = saved + right[r + 1] * term;
+			saved = left[j - r] * term;
+		}
+		values[j] = saved;
+	}
+
+	values
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn nodes() -> Vec<f64> {
+		vec![0.0, 0.4, 1.1, 1.5, 2.3, 3.0, 3.2, 4.0]
+	}
+
+	fn values() -> Vec<f64> {
+		nodes().iter().map(|&x| (1.3 * x).sin() + 0.2 * x).collect()
+	}
+
+	fn grid() -> Vec<f64> {
+		(0..=80).map(|i| 0.05 * i as f64).collect()
+	}
+
+	#[test]
+	fn interpolation_passes_through_the_nodes() {
+		for degree in 0..=3 {
+			let spline = BSpline::interpolating(&nodes(), &values(), degree).unwrap();
+			assert_eq!(spline.domain(), (0.0, 4.0));
+			for (&x, &y) in nodes().iter().zip(&values()) {
+				assert!((spline.solve(x) - y).abs() < 1e-12, "degree {degree} at {x}");
+			}
+		}
+	}
+
+	#[test]
+	fn degree_zero_is_a_step_function() {
+		let spline = BSpline::interpolating(&nodes(), &values(), 0).unwrap();
+		let (x, y) = (nodes(), values());
+		for t in grid() {
+			// the step of node `k` starts at the midpoint with the previous node
+			let k = x.windows(2).filter(|pair| (pair[0] + pair[1]) / 2.0 <= t).count();
+			assert_eq!(spline.solve(t), y[k], "at {t}");
+		}
+	}
+
+	#[test]
+	fn cubic_polynomials_are_reproduced() {
+		let cubic = |x: f64| 0.5 * x.powi(3) - 2.0 * x * x + x - 3.0;
+		let y: Vec<f64> = nodes().iter().map(|&x| cubic(x)).collect();
+		let spline = BSpline::interpolating(&nodes(), &y, 3).unwrap();
+		let derivative = spline.derivative();
+		assert_eq!(derivative.degree(), 2);
+		for t in grid() {
+			assert!((spline.solve(t) - cubic(t)).abs() < 1e-11, "at {t}");
+			assert!((derivative.solve(t) - (1.5 * t * t - 4.0 * t + 1.0)).abs() < 1e-10, "at {t}");
+		}
+
+		let knots = clamped_knots(3, &[0.0, 1.0, 2.5, 4.0]);
+		let fit = BSpline::least_squares(&nodes(), &y, 3, knots).unwrap();
+		for t in grid() {
+			assert!((fit.solve(t) - cubic(t)).abs() < 1e-10, "at {t}");
+		}
+	}
+
+	#[test]
+	fn inserting_knots_keeps_the_spline() {
+		for degree in 1..=3 {
+			let original = BSpline::interpolating(&nodes(), &values(), degree).unwrap();
+			let mut refined = original.clone();
+			for t in [0.7, 2.0, 2.0, 3.9] {
+				refined.insert_knot(t).unwrap();
+			}
+			assert_eq!(refined.knots().len(), original.knots().len() + 4);
+			assert_eq!(refined.coefficients().len(), original.coefficients().len() + 4);
+			assert_eq!(refined.domain(), original.domain());
+			for t in grid() {
+				assert!((refined.solve(t) - original.solve(t)).abs() < 1e-12, "degree {degree} at {t}");
+			}
+		}
+	}
+
+	#[test]
+	fn errors() {
+		let mut spline = BSpline::interpolating(&nodes(), &values(), 3).unwrap();
+		for t in [0.0, 4.0, -1.0, 5.0, f64::NAN] {
+			assert!(matches!(spline.insert_knot(t), Err(Error::KnotOutsideDomain { .. })), "{t}");
+		}
+		assert!(matches!(BSpline::interpolating(&[1.0], &[2.0], 0), Err(Error::TooFewNodes { needed: 2, found: 1 })));
+		assert!(matches!(BSpline::interpolating(&nodes()[..3], &values()[..3], 3), Err(Error::TooFewNodes { needed: 4, found: 3 })));
+		assert!(matches!(BSpline::new(1, vec![0.0, 0.0, 2.0, 1.0, 3.0], vec![1.0; 3]), Err(Error::DecreasingKnot { index: 3 })));
+	}
+}
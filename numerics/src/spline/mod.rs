@@ -0,0 +1,965 @@
+//! Spline interpolation.
+//!
+//! [`HermiteSpline`] stores values and slopes at the nodes and evaluates cubic Hermite segments;
+//! [`HermiteSpline::interpolating`] finds the slopes of a cubic spline for a chosen [`Boundary`],
+//! while [`HermiteSpline::monotone`] and [`HermiteSpline::akima`] choose them locally to preserve the shape of the data.
+//! Derivatives, integrals, roots and extrema of a [`HermiteSpline`] are computed exactly on every segment.
+//! [`ParametricSpline`] joins two Hermite splines, one per coordinate, into a plane curve,
+//! and [`BicubicSpline`] interpolates values on a two-dimensional grid.
+//! [`SmoothingSpline`] approximates noisy data instead of interpolating it.
+//! [`BSpline`] represents splines of any degree in the B-spline basis over a knot vector.
+//! [`parabolic_spline_coefficients`] and [`cubic_spline_coefficients`] build splines in the
+//! global power basis, which are evaluated with [`piecewise_polynomial`].
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use nalgebra::{DMatrix, DVector};
+use crate::linalg::{self, gauss, Pivoting, Tridiagonal};
+use crate::quad::gauss_6;
+
+mod bicubic;
+mod bspline;
+mod smoothing;
+
+pub use bicubic::BicubicSpline;
+pub use bspline::{basis_functions, clamped_knots, find_span, BSpline};
+pub use smoothing::SmoothingSpline;
+
+/// Value at `t` of the cubic Hermite segment with values `y0`, `y1` and slopes `s0`, `s1` at `x0`, `x1`.
+pub fn hermite(x0: f64, y0: f64, s0: f64, x1: f64, y1: f64, s1: f64, t: f64) -> f64 {
+	let hx = x1 - x0;
+
+	y0 * (t - x1).powi(2) * (2.0 * (t - x0) + hx) / hx.powi(3) +
+	y1 * (t - x0).powi(2) * (2.0 * (x1 - t) + hx) / hx.powi(3) +
+	s0 * (t - x1).powi(2) * (t - x0) / hx.powi(2) +
+	s1 * (t - x0).powi(2) * (t - x1) / hx.powi(2)
+}
+
+/// Why a spline could not be built.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+	TooFewNodes { needed: usize, found: usize },
+	/// `x[index]` is not greater than `x[index - 1]`.
+	UnsortedNodes { index: usize },
+	/// `knots[index]` is smaller than the previous knot, or the domain of a B-spline is empty.
+	DecreasingKnot { index: usize },
+	/// A knot to insert lies outside the open domain of the B-spline.
+	KnotOutsideDomain { knot: f64 },
+	/// Point `index` of a curve is the same as the previous one, so the parameter does not advance.
+	RepeatedPoint { index: usize },
+	/// Periodic conditions need equal values at both ends.
+	NotPeriodic,
+	/// The smoothing parameter is negative or not a number.
+	Smoothing { lambda: f64 },
+	Linalg(linalg::Error),
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::TooFewNodes { needed, found } => write!(f, "at least {needed} nodes are needed, found {found}"),
+			Error::UnsortedNodes { index } => write!(f, "node {index} is not greater than the previous one, the nodes must be sorted without repetitions"),
+			Error::DecreasingKnot { index } => write!(f, "knot {index} is smaller than the previous one or leaves the domain empty"),
+			Error::KnotOutsideDomain { knot } => write!(f, "the knot {knot} must lie inside the domain"),
+			Error::RepeatedPoint { index } => write!(f, "point {index} is the same as the previous one"),
+			Error::NotPeriodic => write!(f, "periodic conditions need the same value at both ends"),
+			Error::Smoothing { lambda } => write!(f, "the smoothing parameter {lambda} must be finite and non-negative"),
+			Error::Linalg(error) => write!(f, "{error}"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<linalg::Error> for Error {
+	fn from(error: linalg::Error) -> Self {
+		Error::Linalg(error)
+	}
+}
+
+/// End conditions that close the system for the slopes of a cubic spline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Boundary {
+	/// Zero second derivative at both ends.
+	Natural,
+	/// Given slopes at the first and the last node.
+	Clamped(f64, f64),
+	/// Continuous third derivative at the second and the second to last node,
+	/// so the first two and the last two segments are single cubics.
+	NotAKnot,
+	/// Equal slopes and second derivatives at both ends, for data with `y[0] = y[n - 1]`.
+	Periodic,
+}
+
+/// A local minimum or maximum of a spline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Extremum {
+	pub x: f64,
+	pub y: f64,
+	pub is_maximum: bool,
+}
+
+/// Checks that the nodes `x` are strictly increasing, as every spline needs; `NaN` is rejected as well.
+pub fn check_nodes(x: &[f64]) -> Result<(), Error> {
+	match x.windows(2).position(|pair| pair[0].partial_cmp(&pair[1]) != Some(Ordering::Less)) {
+		Some(index) => Err(Error::UnsortedNodes { index: index + 1 }),
+		None => Ok(()),
+	}
+}
+
+/// Piecewise cubic Hermite interpolant given by its nodes and the slopes at them.
+#[derive(Clone, Debug)]
+pub struct HermiteSpline {
+	x: Vec<f64>,
+	y: Vec<f64>,
+	s: Vec<f64>,
+	/// `[y, s, c2, c3]` of every segment in powers of `t - x[k]`.
+	coefficients: Vec<[f64; 4]>,
+}
+
+impl HermiteSpline {
+	/// The spline with values `y` and slopes `s` at the nodes `x`, which must be sorted without repetitions.
+	///
+	/// All three slices must have the same length of at least two.
+	pub fn new(x: &[f64], y: &[f64], s: &[f64]) -> Result<Self, Error> {
+		let n = x.len();
+		if let Some(found) = [y.len(), s.len()].into_iter().find(|&length| length != n) {
+			return Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: n, found }));
+		}
+		if n < 2 {
+			return Err(Error::TooFewNodes { needed: 2, found: n });
+		}
+		check_nodes(x)?;
+
+		let coefficients = (0..x.len() - 1).map(|k| {
+			let h = x[k + 1] - x[k];
+			let d = (y[k + 1] - y[k]) / h;
+			let (s0, s1) = (s[k], s[k + 1]);
+
+			[y[k], s0, (3.0 * d - 2.0 * s0 - s1) / h, (s0 + s1 - 2.0 * d) / (h * h)]
+		}).collect();
+
+		Ok(Self {
+			x: x.to_vec(),
+			y: y.to_vec(),
+			s: s.to_vec(),
+			coefficients,
+		})
+	}
+
+	/// The twice continuously differentiable spline through the nodes with the given end conditions.
+	///
+	/// The inner slopes solve `h[i] s[i - 1] + 2 (h[i - 1] + h[i]) s[i] + h[i - 1] s[i + 1] = 3 (h[i] d[i - 1] + h[i - 1] d[i])`,
+	/// where `h[i] = x[i + 1] - x[i]` and `d[i]` is the slope of the chord, so the nodes need not be equally spaced.
+	/// They must be sorted without repetitions, see [`check_nodes`].
+	pub fn interpolating(x: &[f64], y: &[f64], boundary: Boundary) -> Result<Self, Error> {
+		let needed = match boundary {
+			Boundary::NotAKnot => 4,
+			Boundary::Periodic => 3,
+			_ => 2,
+		};
+		let d = chords(x, y, needed)?;
+		let n = x.len();
+		let h: Vec<f64> = x.windows(2).map(|pair| pair[1] - pair[0]).collect();
+
+		if let Boundary::Periodic = boundary {
+			if (y[n - 1] - y[0]).abs() > 1e-9 * y.iter().fold(1.0, |max: f64, y| max.max(y.abs())) {
+				return Err(Error::NotPeriodic);
+			}
+
+			// the unknowns are s[0..n - 1] with s[n - 1] = s[0], and the neighbours wrap around
+			let m = n - 1;
+			let previous = |i: usize| (i + m - 1) % m;
+			let matrix = Tridiagonal::new(
+				(1..m).map(|i| h[i]).collect(),
+				(0..m).map(|i| 2.0 * (h[previous(i)] + h[i])).collect(),
+				(0..m - 1).map(|i| h[previous(i)]).collect(),
+			)?;
+			let rhs: Vec<f64> = (0..m).map(|i| 3.0 * (h[i] * d[previous(i)] + h[previous(i)] * d[i])).collect();
+
+			let mut s = matrix.solve_cyclic(h[0], h[m - 2], &rhs)?;
+			s.push(s[0]);
+			return Self::new(x, y, &s);
+		}
+
+		let mut lower = vec![0.0; n - 1];
+		let mut diagonal = vec![1.0; n];
+		let mut upper = vec![0.0; n - 1];
+		let mut rhs = vec![0.0; n];
+
+		for i in 1..n - 1 {
+			lower[i - 1] = h[i];
+			diagonal[i] = 2.0 * (h[i - 1] + h[i]);
+			upper[i] = h[i - 1];
+			rhs[i] = 3.0 * (h[i] * d[i - 1] + h[i - 1] * d[i]);
+		}
+
+		let last = n - 1;
+		match boundary {
+			Boundary::Natural => {
+				// S'' = 0 on the end segments: 2 s[0] + s[1] = 3 d[0] and s[n - 2] + 2 s[n - 1] = 3 d[n - 2]
+				diagonal[0] = 2.0;
+				upper[0] = 1.0;
+				rhs[0] = 3.0 * d[0];
+				lower[last - 1] = 1.0;
+				diagonal[last] = 2.0;
+				rhs[last] = 3.0 * d[last - 1];
+			}
+			Boundary::Clamped(start, end) => {
+				rhs[0] = start;
+				rhs[last] = end;
+			}
+			Boundary::NotAKnot => {
+				// S''' equal on both sides of x[1], with s[2] eliminated through the equation of x[1]
+				let (h0, h1) = (h[0], h[1]);
+				diagonal[0] = h1;
+				upper[0] = h0 + h1;
+				rhs[0] = (h1 * (3.0 * h0 + 2.0 * h1) * d[0] + h0 * h0 * d[1]) / (h0 + h1);
+
+				let (h0, h1) = (h[last - 1], h[last - 2]);
+				lower[last - 1] = h0 + h1;
+				diagonal[last] = h1;
+				rhs[last] = (h1 * (3.0 * h0 + 2.0 * h1) * d[last - 1] + h0 * h0 * d[last - 2]) / (h0 + h1);
+			}
+			Boundary::Periodic => unreachable!(),
+		}
+
+		let s = Tridiagonal::new(lower, diagonal, upper)?.solve(&rhs)?;
+		Self::new(x, y, &s)
+	}
+
+	/// Monotone piecewise cubic interpolant (PCHIP) by the Fritsch–Carlson method.
+	///
+	/// It is monotone wherever the data are and has no extrema between the nodes besides those of the data,
+	/// at the cost of a discontinuous second derivative. The slopes start as the mean of the neighbouring chords,
+	/// zero at a local extremum, and are scaled down where they would make a segment overshoot.
+	pub fn monotone(x: &[f64], y: &[f64]) -> Result<Self, Error> {
+		let d = chords(x, y, 2)?;
+		let n = x.len();
+
+		let mut s = vec![0.0; n];
+		s[0] = d[0];
+		s[n - 1] = d[n - 2];
+		for i in 1..n - 1 {
+			if d[i - 1] * d[i] > 0.0 {
+				s[i] = (d[i - 1] + d[i]) / 2.0;
+			}
+		}
+
+		for i in 0..n - 1 {
+			if d[i] == 0.0 {
+				s[i] = 0.0;
+				s[i + 1] = 0.0;
+				continue;
+			}
+
+			let (alpha, beta) = (s[i] / d[i], s[i + 1] / d[i]);
+			let radius = alpha.hypot(beta);
+			if radius > 3.0 {
+				let tau = 3.0 / radius;
+				s[i] = tau * alpha * d[i];
+				s[i + 1] = tau * beta * d[i];
+			}
+		}
+
+		Self::new(x, y, &s)
+	}
+
+	/// Akima spline, whose slope at a node weighs the two chords on either side by how much the other side bends.
+	///
+	/// A single outlier only affects the nearby segments, so it wiggles less than a cubic spline,
+	/// while being only once continuously differentiable. Two extra chords are extrapolated at each end.
+	pub fn akima(x: &[f64], y: &[f64]) -> Result<Self, Error> {
+		let d = chords(x, y, 2)?;
+		let n = x.len();
+		if n == 2 {
+			return Self::new(x, y, &[d[0], d[0]]);
+		}
+
+		// e[i + 2] = d[i]
+		let mut e = Vec::with_capacity(n + 3);
+		e.extend([3.0 * d[0] - 2.0 * d[1], 2.0 * d[0] - d[1]]);
+		e.extend(&d);
+		e.extend([2.0 * d[n - 2] - d[n - 3], 3.0 * d[n - 2] - 2.0 * d[n - 3]]);
+
+		let s: Vec<f64> = (0..n).map(|i| {
+			let (left, right) = ((e[i + 1] - e[i]).abs(), (e[i + 3] - e[i + 2]).abs());
+			if left + right == 0.0 {
+				(e[i + 1] + e[i + 2]) / 2.0
+			} else {
+				(right * e[i + 1] + left * e[i + 2]) / (left + right)
+			}
+		}).collect();
+
+		Self::new(x, y, &s)
+	}
+
+	/// Slopes at the ends of the parabolas through the first three and the last three nodes,
+	/// for data whose derivative is unknown; with two nodes both are the slope of the chord.
+	pub fn estimated_end_slopes(x: &[f64], y: &[f64]) -> [f64; 2] {
+		let n = x.len();
+		let chord = |i: usize| (y[i + 1] - y[i]) / (x[i + 1] - x[i]);
+		if n < 3 {
+			return [chord(0), chord(0)];
+		}
+
+		let (h0, h1) = (x[1] - x[0], x[2] - x[1]);
+		let first = ((2.0 * h0 + h1) * chord(0) - h0 * chord(1)) / (h0 + h1);
+
+		let (h0, h1) = (x[n - 1] - x[n - 2], x[n - 2] - x[n - 3]);
+		let last = ((2.0 * h0 + h1) * chord(n - 2) - h0 * chord(n - 3)) / (h0 + h1);
+
+		[first, last]
+	}
+
+	/// The nodes `x`.
+	pub fn nodes(&self) -> &[f64] {
+		&self.x
+	}
+
+	pub fn values(&self) -> &[f64] {
+		&self.y
+	}
+
+	pub fn slopes(&self) -> &[f64] {
+		&self.s
+	}
+
+	/// Value of the spline at `t`; outside the nodes the end segments are extrapolated.
+	pub fn solve(&self, t: f64) -> f64 {
+		let k = self.segment(t);
+		horner(&self.coefficients[k], t - self.x[k])
+	}
+
+	/// Values at every point of `t`, much faster than [`HermiteSpline::solve`] one by one when `t` is sorted.
+	///
+	/// A point in the same or the next segment as the previous one is found in constant time,
+	/// any other by binary search, so unsorted points are fine too.
+	pub fn eval_many(&self, t: &[f64]) -> Vec<f64> {
+		let last = self.x.len() - 2;
+		let mut k = 0;
+
+		t.iter().map(|&t| {
+			let inside = |k: usize| (k == 0 || t >= self.x[k]) && (k == last || t < self.x[k + 1]);
+			if !inside(k) {
+				k = if k < last && inside(k + 1) { k + 1 } else { self.segment(t) };
+			}
+			horner(&self.coefficients[k], t - self.x[k])
+		}).collect()
+	}
+
+	/// First derivative at `t`.
+	pub fn derivative(&self, t: f64) -> f64 {
+		let k = self.segment(t);
+		let [_, s, c2, c3] = self.cubic(k);
+		let u = t - self.x[k];
+
+		s + u * (2.0 * c2 + u * 3.0 * c3)
+	}
+
+	/// Second derivative at `t`; at an inner node it is taken from the segment on the right.
+	pub fn second_derivative(&self, t: f64) -> f64 {
+		let k = self.segment(t);
+		let [_, _, c2, c3] = self.cubic(k);
+
+		2.0 * c2 + 6.0 * c3 * (t - self.x[k])
+	}
+
+	/// Exact `∫ S(t) dt` over `[a, b]`, negative when `a > b`.
+	pub fn integral(&self, a: f64, b: f64) -> f64 {
+		if a > b {
+			return -self.integral(b, a);
+		}
+
+		self.pieces(a, b).map(|(k, left, right)| {
+			let [y, s, c2, c3] = self.cubic(k);
+			let antiderivative = |u: f64| u * (y + u * (s / 2.0 + u * (c2 / 3.0 + u * c3 / 4.0)));
+			antiderivative(right - self.x[k]) - antiderivative(left - self.x[k])
+		}).sum()
+	}
+
+	/// Length of the graph over `[a, b]`, `∫ sqrt(1 + S'(t)^2) dt`.
+	///
+	/// The integrand is not a polynomial, so it is integrated by the six-point Gauss rule on every segment.
+	pub fn arc_length(&self, a: f64, b: f64) -> f64 {
+		let (a, b) = if a <= b { (a, b) } else { (b, a) };
+
+		self.pieces(a, b).map(|(k, left, right)| {
+			let [_, s, c2, c3] = self.cubic(k);
+			let x = self.x[k];
+			gauss_6(|t| {
+				let u = t - x;
+				let slope = s + u * (2.0 * c2 + u * 3.0 * c3);
+				(1.0 + slope * slope).sqrt()
+			}, left, right)
+		}).sum()
+	}
+
+	/// Every point of `[x[0], x[n - 1]]` where the spline changes sign or vanishes exactly, in ascending order.
+	///
+	/// Every segment is split into monotone parts at the zeros of its derivative,
+	/// and a sign change on a part is narrowed down by bisection to the last bit.
+	pub fn roots(&self) -> Vec<f64> {
+		let mut roots = Vec::new();
+		let mut push = |root: f64| {
+			if roots.last() != Some(&root) {
+				roots.push(root);
+			}
+		};
+
+		for k in 0..self.x.len() - 1 {
+			let x = self.x[k];
+			let cubic = self.cubic(k);
+			let value = |u: f64| horner(&cubic, u);
+
+			let mut bounds = vec![0.0];
+			bounds.extend(self.critical_points(k));
+			bounds.push(self.x[k + 1] - x);
+
+			for (index, part) in bounds.windows(2).enumerate() {
+				let (mut left, mut right) = (part[0], part[1]);
+				let first = if index == 0 { self.y[k] } else { value(left) };
+				let last = if index + 2 == bounds.len() { self.y[k + 1] } else { value(right) };
+
+				if first == 0.0 {
+					push(x + left);
+				}
+				if first * last >= 0.0 {
+					continue;
+				}
+
+				loop {
+					let middle = (left + right) / 2.0;
+					if middle <= left || middle >= right {
+						break;
+					}
+					if (value(middle) < 0.0) == (first < 0.0) {
+						left = middle;
+					} else {
+						right = middle;
+					}
+				}
+				push(x + (left + right) / 2.0);
+			}
+		}
+
+		if self.y[self.y.len() - 1] == 0.0 {
+			push(self.x[self.x.len() - 1]);
+		}
+
+		roots
+	}
+
+	/// Local minima and maxima on `[x[0], x[n - 1]]`, where the derivative changes sign, in ascending order.
+	///
+	/// The ends of the interval are not included.
+	pub fn extrema(&self) -> Vec<Extremum> {
+		let mut extrema = Vec::new();
+		let mut push = |x: f64, curvature: f64| {
+			if curvature != 0.0 {
+				extrema.push(Extremum { x, y: self.solve(x), is_maximum: curvature < 0.0 });
+			}
+		};
+
+		for k in 0..self.x.len() - 1 {
+			// a zero slope at a node is an extremum if the curvature has the same sign on both sides
+			if k > 0 && self.s[k] == 0.0 {
+				let [_, _, c2, c3] = self.cubic(k - 1);
+				let left = 2.0 * c2 + 6.0 * c3 * (self.x[k] - self.x[k - 1]);
+				let right = 2.0 * self.cubic(k)[2];
+				if left * right > 0.0 {
+					push(self.x[k], right);
+				}
+			}
+
+			let [_, _, c2, c3] = self.cubic(k);
+			for u in self.critical_points(k) {
+				push(self.x[k] + u, 2.0 * c2 + 6.0 * c3 * u);
+			}
+		}
+
+		extrema
+	}
+
+	/// Index `k` of the segment `[x[k], x[k + 1]]` that holds `t`; the end segments extend beyond the nodes.
+	fn segment(&self, t: f64) -> usize {
+		self.x[1..self.x.len() - 1].partition_point(|&x| x <= t)
+	}
+
+	/// Coefficients `[y, s, c2, c3]` of segment `k` as `y + s u + c2 u^2 + c3 u^3` with `u = t - x[k]`.
+	fn cubic(&self, k: usize) -> [f64; 4] {
+		self.coefficients[k]
+	}
+
+	/// Zeros of the derivative of segment `k` strictly inside it where the derivative changes sign,
+	/// as offsets from `x[k]` in ascending order.
+	fn critical_points(&self, k: usize) -> Vec<f64> {
+		let h = self.x[k + 1] - self.x[k];
+		let [_, s, c2, c3] = self.cubic(k);
+		// 3 c3 u^2 + 2 c2 u + s = 0
+		let (a, b, c) = (3.0 * c3, 2.0 * c2, s);
+
+		let mut points = if a.abs() <= f64::EPSILON * (b.abs() + c.abs() / h) {
+			if b == 0.0 { vec![] } else { vec![-c / b] }
+		} else {
+			let discriminant = b * b - 4.0 * a * c;
+			if discriminant <= 0.0 {
+				vec![]
+			} else {
+				let q = -(b + b.signum() * discriminant.sqrt()) / 2.0;
+				vec![q / a, c / q]
+			}
+		};
+
+		points.retain(|&u| u > 0.0 && u < h);
+		points.sort_by(f64::total_cmp);
+		points
+	}
+
+	/// Parts `(k, left, right)` of `[a, b]` split at the inner nodes, with the segment `k` of each.
+	fn pieces(&self, a: f64, b: f64) -> impl Iterator<Item = (usize, f64, f64)> + '_ {
+		let inner = &self.x[1..self.x.len() - 1];
+		let mut bounds = vec![a];
+		bounds.extend(inner.iter().copied().filter(|&x| x > a && x < b));
+		bounds.push(b);
+
+		(0..bounds.len() - 1).map(move |i| (self.segment(bounds[i]), bounds[i], bounds[i + 1]))
+	}
+}
+
+/// Slopes of the chords between adjacent nodes, after checking that there are at least `needed` sorted nodes.
+fn chords(x: &[f64], y: &[f64], needed: usize) -> Result<Vec<f64>, Error> {
+	let n = x.len();
+	if y.len() != n {
+		return Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: n, found: y.len() }));
+	}
+	if n < needed {
+		return Err(Error::TooFewNodes { needed, found: n });
+	}
+	check_nodes(x)?;
+
+	Ok(x.windows(2).zip(y.windows(2)).map(|(x, y)| (y[1] - y[0]) / (x[1] - x[0])).collect())
+}
+
+/// Value of `c[0] + c[1] u + c[2] u^2 + ...`.
+fn horner(c: &[f64], u: f64) -> f64 {
+	c.iter().rev().fold(0.0, |result, c| result * u + c)
+}
+
+/// How the parameter of a [`ParametricSpline`] advances from one point to the next.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Parametrization {
+	/// By the distance between the points.
+	ChordLength,
+	/// By the square root of the distance, which avoids cusps and loops at sharp turns.
+	Centripetal,
+}
+
+/// Plane curve `(x(t), y(t))` through the given points, with a cubic spline for each coordinate.
+pub struct ParametricSpline {
+	x: HermiteSpline,
+	y: HermiteSpline,
+	closed: bool,
+}
+
+impl ParametricSpline {
+	/// An open curve has not-a-knot ends, or natural ones for fewer than four points.
+	/// A closed curve returns to the first point, with periodic conditions there.
+	pub fn new(points: &[[f64; 2]], parametrization: Parametrization, closed: bool) -> Result<Self, Error> {
+		let needed = if closed { 3 } else { 2 };
+		if points.len() < needed {
+			return Err(Error::TooFewNodes { needed, found: points.len() });
+		}
+
+		let count = points.len();
+		let mut points = points.to_vec();
+		if closed {
+			points.push(points[0]);
+		}
+
+		let exponent = match parametrization {
+			Parametrization::ChordLength => 1.0,
+			Parametrization::Centripetal => 0.5,
+		};
+		let mut t = vec![0.0];
+		for (index, pair) in points.windows(2).enumerate() {
+			let step = (pair[1][0] - pair[0][0]).hypot(pair[1][1] - pair[0][1]).powf(exponent);
+			if step == 0.0 {
+				return Err(Error::RepeatedPoint { index: (index + 1) % count });
+			}
+			t.push(t[index] + step);
+		}
+
+		let boundary = match (closed, points.len()) {
+			(true, _) => Boundary::Periodic,
+			(false, 4..) => Boundary::NotAKnot,
+			(false, _) => Boundary::Natural,
+		};
+		let x: Vec<f64> = points.iter().map(|point| point[0]).collect();
+		let y: Vec<f64> = points.iter().map(|point| point[1]).collect();
+
+		Ok(Self {
+			x: HermiteSpline::interpolating(&t, &x, boundary)?,
+			y: HermiteSpline::interpolating(&t, &y, boundary)?,
+			closed,
+		})
+	}
+
+	pub fn is_closed(&self) -> bool {
+		self.closed
+	}
+
+	/// Values of the parameter at the points, starting from zero; a closed curve has the first point again at the end.
+	pub fn parameters(&self) -> &[f64] {
+		self.x.nodes()
+	}
+
+	/// The first and the last value of the parameter.
+	pub fn range(&self) -> (f64, f64) {
+		let t = self.parameters();
+		(t[0], t[t.len() - 1])
+	}
+
+	pub fn point(&self, t: f64) -> [f64; 2] {
+		[self.x.solve(t), self.y.solve(t)]
+	}
+
+	/// Derivative `(x'(t), y'(t))`, the direction of the curve.
+	pub fn tangent(&self, t: f64) -> [f64; 2] {
+		[self.x.derivative(t), self.y.derivative(t)]
+	}
+
+	/// `amount + 1` points at equal steps of the parameter from the first point to the last, e.g. for drawing.
+	pub fn sample(&self, amount: usize) -> Vec<[f64; 2]> {
+		let (begin, end) = self.range();
+		let t: Vec<f64> = (0..=amount).map(|i| begin + (end - begin) * i as f64 / amount as f64).collect();
+
+		self.x.eval_many(&t).into_iter().zip(self.y.eval_many(&t)).map(|(x, y)| [x, y]).collect()
+	}
+
+	/// Length of the curve by the six-point Gauss rule on every segment.
+	pub fn length(&self) -> f64 {
+		self.parameters().windows(2).map(|pair| {
+			gauss_6(|t| {
+				let [dx, dy] = self.tangent(t);
+				dx.hypot(dy)
+			}, pair[0], pair[1])
+		}).sum()
+	}
+}
+
+/// Value at `x` of a piecewise polynomial of `degree` written in the global power basis.
+///
+/// `coefficients` holds `degree + 1` coefficients per interval between adjacent `points`,
+/// highest power first. Outside the nodes the result is zero.
+pub fn piecewise_polynomial(points: &[[f64; 2]], coefficients: &[f64], degree: usize, x: f64) -> f64 {
+	for (index, points) in points.windows(2).enumerate() {
+		let left = points[0][0];
+		let right = points[1][0];
+
+		if (x >= left) && (x <= right) {
+			let coefficients = &coefficients[index * (degree + 1)..(index + 1) * (degree + 1)];
+			return coefficients.iter().fold(0.0, |result, c| result * x + c);
+		}
+	}
+
+	0.0
+}
+
+/// At least two sorted nodes, as the splines in the power basis need.
+fn check_power_basis_nodes(points: &[[f64; 2]]) -> Result<(), Error> {
+	if points.len() < 2 {
+		return Err(Error::TooFewNodes { needed: 2, found: points.len() });
+	}
+	check_nodes(&points.iter().map(|point| point[0]).collect::<Vec<_>>())
+}
+
+/// Coefficients `[a, b, c]` of `a * x^2 + b * x + c` on every interval of the parabolic spline.
+///
+/// The spline is continuous with its first derivative, and its derivative at the first node
+/// equals `first_derivative`.
+pub fn parabolic_spline_coefficients(points: &[[f64; 2]], first_derivative: f64) -> Result<Vec<f64>, Error> {
+	check_power_basis_nodes(points)?;
+	let mut variables_matrix = Vec::default();
+	let mut free_matrix = Vec::default();
+
+	let num_unknown_variables = points.windows(2).len();
+
+	for (index, points) in points.windows(2).enumerate() {
+		for &[x, y] in points {
+			variables_matrix.extend_from_slice(&[vec![0.0; index * 3], vec![x.powi(2), x, 1.0], vec![0.0; (num_unknown_variables - index - 1) * 3]].concat());
+			free_matrix.push(y);
+		}
+	}
+
+	for (index, &[x, _]) in points[1..points.len() - 1].iter().enumerate() {
+		variables_matrix.extend_from_slice(&[vec![0.0; index * 3], vec![2.0 * x, 1.0, 0.0], vec![-2.0 * x, -1.0, 0.0], vec![0.0; (num_unknown_variables - index - 2) * 3]].concat());
+		free_matrix.push(0.0);
+	}
+
+	{
+		let [x, _] = points[0];
+		variables_matrix.extend_from_slice(&[vec![2.0 * x, 1.0, 0.0], vec![0.0; (num_unknown_variables - 1) * 3]].concat());
+		free_matrix.push(first_derivative);
+	}
+
+	let variables_matrix = DMatrix::from_vec(num_unknown_variables * 3, num_unknown_variables * 3, variables_matrix).transpose();
+	let free_matrix = DVector::from_vec(free_matrix);
+	let result = gauss(&variables_matrix, &free_matrix, Pivoting::Partial)?;
+
+	Ok(result.as_slice().to_vec())
+}
+
+/// Coefficients `[a, b, c, d]` of `a * x^3 + b * x^2 + c * x + d` on every interval of the natural cubic spline.
+pub fn cubic_spline_coefficients(points: &[[f64; 2]]) -> Result<Vec<f64>, Error> {
+	check_power_basis_nodes(points)?;
+	let mut variables_matrix = Vec::default();
+	let mut free_matrix = Vec::default();
+	let num_unknown_variables = points.windows(2).len();
+
+	for (index, points) in points.windows(2).enumerate() {
+		for &[x, y] in points {
+			variables_matrix.extend_from_slice(&[vec![0.0; index * 4], vec![x.powi(3), x.powi(2), x, 1.0], vec![0.0; (num_unknown_variables - index - 1) * 4]].concat());
+			free_matrix.push(y);
+		}
+	}
+
+	for (index, &[x, _]) in points[1..points.len() - 1].iter().enumerate() {
+		variables_matrix.extend_from_slice(&[vec![0.0; index * 4], vec![3.0 * x.powi(2), 2.0 * x, 1.0, 0.0], vec![-3.0 * x.powi(2), -2.0 * x, -1.0, 0.0], vec![0.0; (num_unknown_variables - index - 2) * 4]].concat());
+		free_matrix.push(0.0);
+	}
+
+	for (index, &[x, _]) in points[1..points.len() - 1].iter().enumerate() {
+		variables_matrix.extend_from_slice(&[vec![0.0; index * 4], vec![3.0 * 2.0 * x, 2.0, 0.0, 0.0], vec![-3.0 * 2.0 * x, -2.0, 0.0, 0.0], vec![0.0; (num_unknown_variables - index - 2) * 4]].concat());
+		free_matrix.push(0.0);
+	}
+
+	{
+		let &[x, _] = points.first().unwrap();
+		variables_matrix.extend_from_slice(&[vec![3.0 * 2.0 * x, 2.0, 0.0, 0.0], vec![0.0; (num_unknown_variables - 1) * 4]].concat());
+		free_matrix.push(0.0);
+	}
+
+	{
+		let &[x, _] = points.last().unwrap();
+		variables_matrix.extend_from_slice(&[vec![0.0; (num_unknown_variables - 1) * 4], vec![3.0 * 2.0 * x, 2.0, 0.0, 0.0]].concat());
+		free_matrix.push(0.0);
+	}
+
+	let variables_matrix = DMatrix::from_vec(num_unknown_variables * 4, num_unknown_variables * 4, variables_matrix).transpose();
+	let free_matrix = DVector::from_vec(free_matrix);
+	let result = gauss(&variables_matrix, &free_matrix, Pivoting::Partial)?;
+
+	Ok(result.as_slice().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// `(x + 0.7)(x - 0.3)(x - 1.2)` and its derivatives and antiderivative.
+	fn p(x: f64) -> f64 {
+		(x + 0.7) * (x - 0.3) * (x - 1.2)
+	}
+
+	fn dp(x: f64) -> f64 {
+		3.0 * x * x - 1.6 * x - 0.69
+	}
+
+	fn d2p(x: f64) -> f64 {
+		6.0 * x - 1.6
+	}
+
+	fn antiderivative(x: f64) -> f64 {
+		x.powi(4) / 4.0 - 0.8 * x.powi(3) / 3.0 - 0.69 * x * x / 2.0 + 0.252 * x
+	}
+
+	/// Unevenly spaced nodes on `[-1, 2]`.
+	fn nodes() -> Vec<f64> {
+		vec![-1.0, -0.8, -0.3, 0.1, 0.25, 0.9, 1.0, 1.6, 2.0]
+	}
+
+	fn spline(boundary: Boundary) -> HermiteSpline {
+		let x = nodes();
+		let y: Vec<f64> = x.iter().map(|&x| p(x)).collect();
+		HermiteSpline::interpolating(&x, &y, boundary).unwrap()
+	}
+
+	fn grid() -> impl Iterator<Item = f64> {
+		(0..=60).map(|i| -1.0 + 0.05 * i as f64)
+	}
+
+	#[test]
+	fn cubics_are_reproduced() {
+		for boundary in [Boundary::Clamped(dp(-1.0), dp(2.0)), Boundary::NotAKnot] {
+			let spline = spline(boundary);
+			for t in grid() {
+				assert!((spline.solve(t) - p(t)).abs() < 1e-12, "{boundary:?} at {t}");
+				assert!((spline.derivative(t) - dp(t)).abs() < 1e-11, "{boundary:?} at {t}");
+				assert!((spline.second_derivative(t) - d2p(t)).abs() < 1e-10, "{boundary:?} at {t}");
+			}
+			let points: Vec<f64> = grid().collect();
+			let values: Vec<f64> = points.iter().map(|&t| spline.solve(t)).collect();
+			assert_eq!(spline.eval_many(&points), values);
+		}
+	}
+
+	#[test]
+	fn integrals_are_exact_on_cubics() {
+		let spline = spline(Boundary::NotAKnot);
+		for (a, b) in [(-1.0, 2.0), (-0.45, 1.3), (0.9, 1.0), (1.7, -0.2)] {
+			let exact = antiderivative(b) - antiderivative(a);
+			assert!((spline.integral(a, b) - exact).abs() < 1e-12, "[{a}, {b}]");
+		}
+	}
+
+	#[test]
+	fn roots_and_extrema_of_a_cubic() {
+		let spline = spline(Boundary::Clamped(dp(-1.0), dp(2.0)));
+		let roots = spline.roots();
+		assert_eq!(roots.len(), 3, "{roots:?}");
+		for (root, exact) in roots.iter().zip([-0.7, 0.3, 1.2]) {
+			assert!((root - exact).abs() < 1e-14, "{root} vs {exact}");
+		}
+
+		// p' = 0 at (1.6 ± √(1.6² + 12 · 0.69)) / 6
+		let discriminant = (1.6f64 * 1.6 + 12.0 * 0.69).sqrt();
+		let extrema = spline.extrema();
+		assert_eq!(extrema.len(), 2, "{extrema:?}");
+		assert!(extrema[0].is_maximum && !extrema[1].is_maximum);
+		assert!((extrema[0].x - (1.6 - discriminant) / 6.0).abs() < 1e-8);
+		assert!((extrema[1].x - (1.6 + discriminant) / 6.0).abs() < 1e-8);
+	}
+
+	#[test]
+	fn arc_length_of_a_line() {
+		let x = nodes();
+		let y: Vec<f64> = x.iter().map(|&x| 2.0 * x + 1.0).collect();
+		let spline = HermiteSpline::interpolating(&x, &y, Boundary::Natural).unwrap();
+		assert!((spline.arc_length(-1.0, 2.0) - 3.0 * 5f64.sqrt()).abs() < 1e-12);
+	}
+
+	#[test]
+	fn monotone_spline_does_not_overshoot_a_step() {
+		let x = [0.0, 0.5, 1.2, 1.5, 2.5, 3.0];
+		let y = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+		let pchip = HermiteSpline::monotone(&x, &y).unwrap();
+		let natural = HermiteSpline::interpolating(&x, &y, Boundary::Natural).unwrap();
+
+		let t: Vec<f64> = (0..=300).map(|i| 0.01 * i as f64).collect();
+		let values = pchip.eval_many(&t);
+		assert!(values.iter().all(|&value| (0.0..=1.0).contains(&value)), "{values:?}");
+		assert!(values.windows(2).all(|pair| pair[1] >= pair[0]));
+		for (&x, &y) in x.iter().zip(&y) {
+			assert_eq!(pchip.solve(x), y);
+		}
+		// the cubic spline rings around the step
+		assert!(t.iter().any(|&t| !(0.0..=1.0).contains(&natural.solve(t))));
+	}
+
+	#[test]
+	fn monotone_spline_stays_monotone_on_steep_data() {
+		let x = [0.0, 0.1, 0.2, 1.0, 1.1, 3.0, 3.05];
+		let y = [-2.0, -1.9, 5.0, 5.1, 9.0, 9.2, 20.0];
+		let pchip = HermiteSpline::monotone(&x, &y).unwrap();
+		for pair in x.windows(2) {
+			let t: Vec<f64> = (0..=50).map(|i| pair[0] + (pair[1] - pair[0]) * i as f64 / 50.0).collect();
+			assert!(pchip.eval_many(&t).windows(2).all(|values| values[1] >= values[0]), "on [{}, {}]", pair[0], pair[1]);
+			assert!(t.iter().all(|&t| pchip.derivative(t) >= -1e-12));
+		}
+	}
+
+	#[test]
+	fn akima_reproduces_a_line() {
+		let x = nodes();
+		let line = |x: f64| 1.5 - 0.75 * x;
+		let y: Vec<f64> = x.iter().map(|&x| line(x)).collect();
+		let akima = HermiteSpline::akima(&x, &y).unwrap();
+		assert!(akima.slopes().iter().all(|&slope| (slope + 0.75).abs() < 1e-14));
+		for t in grid() {
+			assert!((akima.solve(t) - line(t)).abs() < 1e-14, "at {t}");
+		}
+		let two = HermiteSpline::akima(&x[..2], &y[..2]).unwrap();
+		assert!(two.slopes().iter().all(|&slope| (slope + 0.75).abs() < 1e-14));
+	}
+
+	fn curve_points() -> Vec<[f64; 2]> {
+		vec![[0.0, 0.0], [1.0, 2.0], [3.0, 2.5], [4.0, 0.5], [2.5, -1.0], [1.0, -0.5]]
+	}
+
+	#[test]
+	fn parametric_spline_passes_through_the_points() {
+		let points = curve_points();
+		for parametrization in [Parametrization::ChordLength, Parametrization::Centripetal] {
+			for closed in [false, true] {
+				let curve = ParametricSpline::new(&points, parametrization, closed).unwrap();
+				let t = curve.parameters();
+				assert_eq!(t.len(), points.len() + closed as usize);
+				for (&t, point) in t.iter().zip(&points) {
+					let [x, y] = curve.point(t);
+					assert!((x - point[0]).abs() < 1e-12 && (y - point[1]).abs() < 1e-12, "{parametrization:?} at {t}");
+				}
+			}
+		}
+
+		// the parameter advances by the distance, or by its square root
+		let distance = 5f64.sqrt();
+		let chord = ParametricSpline::new(&points, Parametrization::ChordLength, false).unwrap();
+		let centripetal = ParametricSpline::new(&points, Parametrization::Centripetal, false).unwrap();
+		assert!((chord.parameters()[1] - distance).abs() < 1e-15);
+		assert!((centripetal.parameters()[1] - distance.sqrt()).abs() < 1e-15);
+	}
+
+	#[test]
+	fn closed_curve_joins_smoothly() {
+		for parametrization in [Parametrization::ChordLength, Parametrization::Centripetal] {
+			let curve = ParametricSpline::new(&curve_points(), parametrization, true).unwrap();
+			assert!(curve.is_closed());
+			let (begin, end) = curve.range();
+			let (start, finish) = (curve.point(begin), curve.point(end));
+			let (first, last) = (curve.tangent(begin), curve.tangent(end));
+			for k in 0..2 {
+				assert!((start[k] - finish[k]).abs() < 1e-12);
+				assert!((first[k] - last[k]).abs() < 1e-10, "{parametrization:?}: {first:?} vs {last:?}");
+			}
+			let sample = curve.sample(40);
+			assert_eq!(sample.len(), 41);
+			assert!((sample[0][0] - sample[40][0]).abs() < 1e-12 && (sample[0][1] - sample[40][1]).abs() < 1e-12);
+		}
+	}
+
+	#[test]
+	fn parametric_spline_errors() {
+		let points = curve_points();
+		let closed = ParametricSpline::new(&points[..2], Parametrization::ChordLength, true);
+		assert!(matches!(closed, Err(Error::TooFewNodes { needed: 3, found: 2 })));
+		let open = ParametricSpline::new(&points[..1], Parametrization::ChordLength, false);
+		assert!(matches!(open, Err(Error::TooFewNodes { needed: 2, found: 1 })));
+		assert!(ParametricSpline::new(&points[..2], Parametrization::Centripetal, false).is_ok());
+
+		let repeated = [[0.0, 0.0], [1.0, 1.0], [1.0, 1.0]];
+		assert!(matches!(ParametricSpline::new(&repeated, Parametrization::ChordLength, false), Err(Error::RepeatedPoint { index: 2 })));
+		// closing the curve repeats the first point
+		let loop_back = [[0.0, 0.0], [1.0, 1.0], [2.0, 0.0], [0.0, 0.0]];
+		assert!(matches!(ParametricSpline::new(&loop_back, Parametrization::Centripetal, true), Err(Error::RepeatedPoint { index: 0 })));
+	}
+
+	#[test]
+	fn length_of_a_straight_curve() {
+		let points = [[0.0, 0.0], [1.0, 2.0], [1.5, 3.0], [3.0, 6.0]];
+		let curve = ParametricSpline::new(&points, Parametrization::ChordLength, false).unwrap();
+		assert!((curve.length() - 45f64.sqrt()).abs() < 1e-12);
+	}
+
+	#[test]
+	fn hermite_spline_from_slopes() {
+		let spline = HermiteSpline::new(&[0.0, 1.0, 3.0], &[1.0, 2.0, 0.0], &[0.0, 1.0, -1.0]).unwrap();
+		assert_eq!((spline.solve(1.0), spline.derivative(1.0)), (2.0, 1.0));
+		assert!((spline.derivative(3.0) + 1.0).abs() < 1e-14);
+
+		assert!(matches!(HermiteSpline::new(&[], &[], &[]), Err(Error::TooFewNodes { needed: 2, found: 0 })));
+		assert!(matches!(HermiteSpline::new(&[0.0, 1.0], &[1.0], &[0.0, 0.0]), Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: 2, found: 1 }))));
+		assert!(matches!(HermiteSpline::new(&[0.0, 1.0], &[1.0, 2.0], &[0.0]), Err(Error::Linalg(linalg::Error::DimensionMismatch { expected: 2, found: 1 }))));
+		assert!(matches!(HermiteSpline::new(&[1.0, 0.0], &[1.0, 2.0], &[0.0, 0.0]), Err(Error::UnsortedNodes { index: 1 })));
+	}
+}
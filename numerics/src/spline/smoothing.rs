@@ -0,0 +1,272 @@
+//! Cubic smoothing splines for noisy data, by the method of Reinsch.
+
+use crate::linalg::Banded;
+use super::{chords, Error, HermiteSpline};
+
+/// The natural cubic spline `g` minimising `Σ (y[i] - g(x[i]))^2 + λ ∫ g''(t)^2 dt`.
+///
+/// With `λ = 0` it interpolates the data, and as `λ` grows it tends to the least-squares line.
+/// The second derivatives at the inner nodes solve a pentadiagonal system `(R + λ Q^T Q) γ = Q^T y`,
+/// factorized as a [`Banded`] matrix.
+pub struct SmoothingSpline {
+	spline: HermiteSpline,
+	lambda: f64,
+	residual: f64,
+	degrees_of_freedom: f64,
+}
+
+impl SmoothingSpline {
+	/// Needs at least three nodes, and fails with [`Error::Smoothing`] unless `lambda` is finite and non-negative.
+	pub fn new(x: &[f64], y: &[f64], lambda: f64) -> Result<Self, Error> {
+		if !(lambda >= 0.0 && lambda.is_finite()) {
+			return Err(Error::Smoothing { lambda });
+		}
+		chords(x, y, 3)?;
+		System::new(x).smooth(y, lambda)
+	}
+
+	/// The spline whose `λ` minimises the generalized cross-validation score, see [`SmoothingSpline::gcv`].
+	///
+	/// `λ` is searched over twelve orders of magnitude around the scale of the nodes, first on a coarse grid
+	/// and then by golden-section search around the best point.
+	pub fn cross_validated(x: &[f64], y: &[f64]) -> Result<Self, Error> {
+		chords(x, y, 3)?;
+		let system = System::new(x);
+		let scale = system.lambda_scale();
+		let score = |exponent: f64| system.smooth(y, scale * 10f64.powf(exponent)).map(|spline| spline.gcv());
+
+		const STEP: f64 = 0.25;
+		let mut best = (-6.0, f64::INFINITY);
+		for i in 0..=48 {
+			let exponent = -6.0 + STEP * i as f64;
+			let gcv = score(exponent)?;
+			if gcv < best.1 {
+				best = (exponent, gcv);
+			}
+		}
+
+		// every step keeps one of the inner points and its score, and only scores the new one
+		let ratio = (5f64.sqrt() - 1.0) / 2.0;
+		let (mut left, mut right) = (best.0 - STEP, best.0 + STEP);
+		let mut first = (right - ratio * (right - left), 0.0);
+		let mut second = (left + ratio * (right - left), 0.0);
+		first.1 = score(first.0)?;
+		second.1 = score(second.0)?;
+		for _ in 0..40 {
+			if first.1 < second.1 {
+				right = second.0;
+				second = first;
+				first.0 = right - ratio * (right - left);
+				first.1 = score(first.0)?;
+			} else {
+				left = first.0;
+				first = second;
+				second.0 = left + ratio * (right - left);
+				second.1 = score(second.0)?;
+			}
+		}
+
+		let refined = system.smooth(y, scale * 10f64.powf((left + right) / 2.0))?;
+		if refined.gcv() <= best.1 {
+			Ok(refined)
+		} else {
+			system.smooth(y, scale * 10f64.powf(best.0))
+		}
+	}
+
+	pub fn spline(&self) -> &HermiteSpline {
+		&self.spline
+	}
+
+	pub fn into_spline(self) -> HermiteSpline {
+		self.spline
+	}
+
+	pub fn lambda(&self) -> f64 {
+		self.lambda
+	}
+
+	/// `Σ (y[i] - g(x[i]))^2`.
+	pub fn residual(&self) -> f64 {
+		self.residual
+	}
+
+	/// Trace of the matrix taking `y` to the smoothed values, from `n` when interpolating down to 2 for a line.
+	pub fn degrees_of_freedom(&self) -> f64 {
+		self.degrees_of_freedom
+	}
+
+	/// Generalized cross-validation score `n * residual / (n - degrees of freedom)^2`,
+	/// which estimates the error of predicting a left-out point.
+	pub fn gcv(&self) -> f64 {
+		let n = self.spline.nodes().len() as f64;
+		n * self.residual / (n - self.degrees_of_freedom).powi(2)
+	}
+}
+
+/// The matrices of Reinsch's algorithm, which only depend on the nodes.
+struct System {
+	x: Vec<f64>,
+	h: Vec<f64>,
+	/// Column `j` of `Q` holds `q[j]` at rows `j`, `j + 1` and `j + 2`.
+	q: Vec<[f64; 3]>,
+	/// `R` with the bandwidth of `Q^T Q`.
+	r: Banded,
+	q_t_q: Banded,
+}
+
+impl System {
+	fn new(x: &[f64]) -> Self {
+		let h: Vec<f64> = x.windows(2).map(|pair| pair[1] - pair[0]).collect();
+		let m = x.len() - 2;
+
+		let q: Vec<[f64; 3]> = (0..m).map(|j| [1.0 / h[j], -1.0 / h[j] - 1.0 / h[j + 1], 1.0 / h[j + 1]]).collect();
+
+		let mut r = Banded::zeros(m, 2, 2);
+		let mut q_t_q = Banded::zeros(m, 2, 2);
+		for j in 0..m {
+			*r.get_mut(j, j) = (h[j] + h[j + 1]) / 3.0;
+			if j + 1 < m {
+				*r.get_mut(j, j + 1) = h[j + 1] / 6.0;
+				*r.get_mut(j + 1, j) = h[j + 1] / 6.0;
+			}
+
+			// columns j and k of Q overlap on rows k..j + 2
+			for k in j..(j + 3).min(m) {
+				let product: f64 = (k..j + 3).map(|row| q[j][row - j] * q[k][row - k]).sum();
+				*q_t_q.get_mut(j, k) = product;
+				*q_t_q.get_mut(k, j) = product;
+			}
+		}
+
+		Self { x: x.to_vec(), h, q, r, q_t_q }
+	}
+
+	/// `tr R / tr Q^T Q`, around which `λ` starts to matter.
+	fn lambda_scale(&self) -> f64 {
+		let trace = |matrix: &Banded| (0..matrix.size()).map(|j| matrix.get(j, j)).sum::<f64>();
+		trace(&self.r) / trace(&self.q_t_q)
+	}
+
+	fn smooth(&self, y: &[f64], lambda: f64) -> Result<SmoothingSpline, Error> {
+		let (n, m) = (self.x.len(), self.x.len() - 2);
+
+		let mut matrix = self.r.clone();
+		for j in 0..m {
+			for k in j.saturating_sub(2)..(j + 3).min(m) {
+				*matrix.get_mut(j, k) += lambda * self.q_t_q.get(j, k);
+			}
+		}
+		let factorization = matrix.ldl()?;
+
+		let q_t_y: Vec<f64> = (0..m).map(|j| (0..3).map(|t| self.q[j][t] * y[j + t]).sum()).collect();
+		let gamma = factorization.solve(&q_t_y)?;
+
+		// g = y - λ Q γ
+		let mut g = y.to_vec();
+		for j in 0..m {
+			for t in 0..3 {
+				g[j + t] -= lambda * self.q[j][t] * gamma[j];
+			}
+		}
+
+		// second derivatives at all nodes, zero at the ends
+		let mut second = vec![0.0; n];
+		second[1..n - 1].copy_from_slice(&gamma);
+
+		let h = &self.h;
+		let mut slopes: Vec<f64> = (0..n - 1).map(|i| (g[i + 1] - g[i]) / h[i] - h[i] * (2.0 * second[i] + second[i + 1]) / 6.0).collect();
+		slopes.push((g[n - 1] - g[n - 2]) / h[n - 2] + h[n - 2] * (second[n - 2] + 2.0 * second[n - 1]) / 6.0);
+
+		// tr A = n - λ tr(M^-1 Q^T Q), with M^-1 only needed within the band of Q^T Q
+		let inverse = factorization.inverse_band();
+		let trace: f64 = (0..m).flat_map(|j| (j.saturating_sub(2)..(j + 3).min(m)).map(move |k| (j, k)))
+			.map(|(j, k)| inverse.get(j, k) * self.q_t_q.get(k, j))
+			.sum();
+
+		Ok(SmoothingSpline {
+			spline: HermiteSpline::new(&self.x, &g, &slopes)?,
+			lambda,
+			residual: y.iter().zip(&g).map(|(y, g)| (y - g).powi(2)).sum(),
+			degrees_of_freedom: n as f64 - lambda * trace,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::spline::Boundary;
+
+	fn nodes() -> Vec<f64> {
+		(0..25).map(|i| 0.2 * i as f64 + 0.05 * (i as f64 * 1.7).sin()).collect()
+	}
+
+	/// `sin x` with uniform noise in `[-0.1, 0.1)` from a fixed linear congruential sequence.
+	fn noisy() -> Vec<f64> {
+		let mut state = 12345u64;
+		nodes().iter().map(|&x| {
+			state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+			x.sin() + 0.2 * ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5)
+		}).collect()
+	}
+
+	#[test]
+	fn zero_lambda_interpolates() {
+		let (x, y) = (nodes(), noisy());
+		let smoothing = SmoothingSpline::new(&x, &y, 0.0).unwrap();
+		let natural = HermiteSpline::interpolating(&x, &y, Boundary::Natural).unwrap();
+		assert!(smoothing.residual() < 1e-24);
+		assert!((smoothing.degrees_of_freedom() - x.len() as f64).abs() < 1e-9);
+		for i in 0..=96 {
+			let t = 0.05 * i as f64;
+			assert!((smoothing.spline().solve(t) - natural.solve(t)).abs() < 1e-10, "at {t}");
+		}
+	}
+
+	#[test]
+	fn large_lambda_gives_the_least_squares_line() {
+		let (x, y) = (nodes(), noisy());
+		let n = x.len() as f64;
+		let (mean_x, mean_y) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+		let slope = x.iter().zip(&y).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>()
+			/ x.iter().map(|x| (x - mean_x).powi(2)).sum::<f64>();
+
+		let smoothing = SmoothingSpline::new(&x, &y, 1e12).unwrap();
+		assert!((smoothing.degrees_of_freedom() - 2.0).abs() < 1e-6);
+		for &t in &x {
+			let line = mean_y + slope * (t - mean_x);
+			assert!((smoothing.spline().solve(t) - line).abs() < 1e-6, "at {t}");
+		}
+	}
+
+	#[test]
+	fn degrees_of_freedom_decrease_with_lambda() {
+		let (x, y) = (nodes(), noisy());
+		let degrees: Vec<f64> = [0.0, 1e-4, 1e-2, 1.0, 1e2, 1e4, 1e8].iter()
+			.map(|&lambda| SmoothingSpline::new(&x, &y, lambda).unwrap().degrees_of_freedom())
+			.collect();
+		assert!((degrees[0] - 25.0).abs() < 1e-9);
+		assert!(degrees.windows(2).all(|pair| pair[1] < pair[0]), "{degrees:?}");
+		assert!(degrees[degrees.len() - 1] > 2.0 && degrees[degrees.len() - 1] < 2.01, "{degrees:?}");
+	}
+
+	#[test]
+	fn cross_validation_beats_the_grid() {
+		let (x, y) = (nodes(), noisy());
+		let best = SmoothingSpline::cross_validated(&x, &y).unwrap();
+		for exponent in -8..=8 {
+			let other = SmoothingSpline::new(&x, &y, 10f64.powi(exponent)).unwrap();
+			assert!(best.gcv() <= other.gcv() * (1.0 + 1e-9), "λ = 1e{exponent}");
+		}
+		// smoother than the data, but not a line
+		assert!(best.degrees_of_freedom() > 2.5 && best.degrees_of_freedom() < 20.0, "{}", best.degrees_of_freedom());
+	}
+
+	#[test]
+	fn invalid_lambda() {
+		for lambda in [-1.0, f64::NAN, f64::INFINITY] {
+			assert!(matches!(SmoothingSpline::new(&nodes(), &noisy(), lambda), Err(Error::Smoothing { .. })));
+		}
+	}
+}
@@ -11,7 +11,6 @@ fn function_2(x: f64, y: f64) -> f64 {
 
 fn main() {
     println!("Hello, world!");
-    let path = "C:/Users/Mi/Desktop/Курс 2 Семестр 2/ЧМ (ЗАЧЁТ С ОЦЕНКОЙ) Численные методы/Раздел 1/Задача 3/images/2.2.png";
 
     let mut plot = Plot::new();
 
@@ -37,8 +36,4 @@ fn main() {
     plot.add_trace(trace_1);
     plot.add_trace(trace_2);
     plot.show();
-
-    loop {
-
-    }
 }
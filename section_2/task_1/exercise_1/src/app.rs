@@ -1,41 +1,209 @@
 use eframe::egui::{Context, Ui};
 use eframe::{egui, Frame};
-use egui_plot::{Line, Plot};
+use egui_plot::{Legend, Line, Plot, Points};
+use numerics::spline::{Boundary, HermiteSpline, SmoothingSpline};
+use serde::{Deserialize, Serialize};
 use crate::model::Model;
 
-#[derive(Default)]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct App{
+    /// CSV or TSV file with the nodes; empty for the exercise data.
+    path: String,
+    #[serde(skip)]
+    error: Option<String>,
+    #[serde(skip)]
     model: Model,
+
+    show_clamped: bool,
+    /// Slopes of the clamped spline at the ends as set by hand; `None` for those of the data.
+    end_slopes: Option<[f64; 2]>,
+    show_natural: bool,
+    show_not_a_knot: bool,
+    show_periodic: bool,
+    show_monotone: bool,
+    show_akima: bool,
+    show_smoothing: bool,
+    /// Smoothing parameter, from interpolation at 0 to the least-squares line as it grows.
+    lambda: f64,
+
+    /// A spline or the reason it cannot be built, for every end condition and shape-preserving method.
+    #[serde(skip)]
+    splines: Vec<(&'static str, Result<HermiteSpline, String>)>,
+    /// Degrees of freedom and cross-validation score of the smoothing spline.
+    #[serde(skip)]
+    smoothing_summary: String,
 }
 
 impl eframe::App for App {
-    fn update(&mut self, ctx: &Context, frame: &mut Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            self.render_plot(ui);
-        });
+    fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+        egui::TopBottomPanel::bottom("state").show(ctx, |ui| app_state::file_ui(ui, self));
+        egui::CentralPanel::default().show(ctx, |ui| self.ui(ui));
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        app_state::save(storage, self);
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        let mut app = Self {
+            path: String::new(),
+            error: None,
+            model: Model::default(),
+
+            show_clamped: true,
+            end_slopes: None,
+            show_natural: false,
+            show_not_a_knot: false,
+            show_periodic: false,
+            show_monotone: false,
+            show_akima: false,
+            show_smoothing: false,
+            lambda: 1e-3,
+
+            splines: Vec::new(),
+            smoothing_summary: String::new(),
+        };
+        app.generate_splines();
+        app
+    }
+}
+
+impl app_state::State for App {
+    fn restore(&mut self) {
+        self.load();
     }
 }
 
 impl App {
-    fn render_plot(&self, ui: &mut Ui){
-        Plot::new("my_plot").view_aspect(2.0).show(ui, |plot_ui| {
-            let function = self.generate_function();
-            plot_ui.line(function);
-        });
+    /// The spline through the nodes of a CSV or TSV file instead of the exercise data.
+    pub fn with_file(path: &str) -> Self {
+        let mut app = Self { path: path.to_string(), ..Self::default() };
+        app.load();
+        app
     }
 
-    fn generate_function(&self) -> Line {
-        let amount = 10000;
-        let step = 2.0 / amount as f64;
-        let mut points = Vec::with_capacity(amount + 1);
+    /// Builds the model from `path` and `end_slopes`, keeping the current one if the file cannot be used.
+    fn load(&mut self) {
+        if self.path.is_empty() {
+            self.model = Model::exercise(self.end_slopes);
+            self.error = None;
+        } else {
+            match Model::read(self.path.as_ref(), self.end_slopes) {
+                Ok(model) => {
+                    self.model = model;
+                    self.error = None;
+                }
+                Err(error) => self.error = Some(error),
+            }
+        }
+
+        self.generate_splines();
+    }
+
+    fn generate_splines(&mut self) {
+        let [start, end] = self.model.end_slopes;
+        let conditions = [
+            ("clamped", Boundary::Clamped(start, end)),
+            ("natural", Boundary::Natural),
+            ("not-a-knot", Boundary::NotAKnot),
+            ("periodic", Boundary::Periodic),
+        ];
 
-        for i in 0..(amount + 1) {
-            let x = -1.0 + (i as f64) * step;
-            let y = self.model.solve(x);
+        self.splines = conditions.into_iter().map(|(name, boundary)| (name, self.model.spline(boundary))).collect();
+        self.splines.push(("monotone", self.model.monotone()));
+        self.splines.push(("Akima", self.model.akima()));
 
-            points.push([x, y]);
+        let smoothing = self.model.smoothing(self.lambda);
+        self.smoothing_summary = match &smoothing {
+            Ok(smoothing) => format!("degrees of freedom: {:.2}, GCV: {:.3e}", smoothing.degrees_of_freedom(), smoothing.gcv()),
+            Err(_) => String::new(),
+        };
+        self.splines.push(("smoothing", smoothing.map(SmoothingSpline::into_spline)));
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("data file");
+            ui.text_edit_singleline(&mut self.path).on_hover_text("x y pairs, one per line, separated by commas, tabs or spaces");
+            if ui.button("load").clicked() {
+                self.end_slopes = None;
+                self.load();
+            }
+            if ui.button("exercise data").clicked() {
+                self.path.clear();
+                self.end_slopes = None;
+                self.load();
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_clamped, "clamped");
+            let [mut start, mut end] = self.model.end_slopes;
+            let mut changed = ui.add(egui::DragValue::new(&mut start).speed(0.01).prefix("S'(a) = ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut end).speed(0.01).prefix("S'(b) = ")).changed();
+            if changed {
+                self.end_slopes = Some([start, end]);
+                self.model.end_slopes = [start, end];
+                self.generate_splines();
+            }
+        });
+        ui.checkbox(&mut self.show_natural, "natural, S'' = 0 at the ends");
+        ui.checkbox(&mut self.show_not_a_knot, "not-a-knot");
+        ui.checkbox(&mut self.show_periodic, "periodic");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_monotone, "monotone (Fritsch–Carlson)");
+            ui.checkbox(&mut self.show_akima, "Akima");
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_smoothing, "smoothing");
+            let mut changed = ui.add(egui::Slider::new(&mut self.lambda, 0.0..=1e6).logarithmic(true).smallest_positive(1e-9).text("λ")).changed();
+            if ui.button("GCV").on_hover_text("choose λ by generalized cross-validation").clicked() {
+                if let Ok(smoothing) = self.model.cross_validated() {
+                    self.lambda = smoothing.lambda();
+                    changed = true;
+                }
+            }
+            if changed {
+                self.generate_splines();
+            }
+            ui.label(&self.smoothing_summary);
+        });
+
+        for (name, spline) in &self.splines {
+            if let Err(error) = spline {
+                ui.colored_label(egui::Color32::RED, format!("{name}: {error}"));
+            }
         }
 
-        Line::new(points)
+        self.render_plot(ui);
     }
-}
\ No newline at end of file
+
+    fn render_plot(&self, ui: &mut Ui) {
+        let shown = [self.show_clamped, self.show_natural, self.show_not_a_knot, self.show_periodic, self.show_monotone, self.show_akima, self.show_smoothing];
+
+        Plot::new("my_plot").view_aspect(2.0).legend(Legend::default()).show(ui, |plot_ui| {
+            for ((name, spline), shown) in self.splines.iter().zip(shown) {
+                if let (Ok(spline), true) = (spline, shown) {
+                    plot_ui.line(self.generate_function(spline).name(name));
+                }
+            }
+            plot_ui.points(Points::new(self.model.nodes()).radius(4.0).name("nodes"));
+        });
+    }
+
+    fn generate_function(&self, spline: &HermiteSpline) -> Line {
+        let amount = 10000;
+        let (begin, end) = self.model.range();
+        let step = (end - begin) / amount as f64;
+        let x: Vec<f64> = (0..(amount + 1)).map(|i| begin + (i as f64) * step).collect();
+        let y = spline.eval_many(&x);
+
+        Line::new(x.into_iter().zip(y).map(|(x, y)| [x, y]).collect::<Vec<_>>())
+    }
+}
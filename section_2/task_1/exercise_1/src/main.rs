@@ -1,14 +1,11 @@
 use eframe::egui;
-use crate::app::App;
-
-mod app;
-mod model;
-
+use section_2_task_1::App;
 
+/// Optionally takes a CSV or TSV file with the nodes as the only argument.
 fn main() {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]),
+        viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]).with_app_id("section_2_task_1"),
         ..Default::default()
     };
 
@@ -16,7 +13,10 @@ fn main() {
         "My egui App",
         options,
         Box::new(|cc| {
-            Box::<App>::default()
+            match std::env::args().nth(1) {
+                Some(path) => Box::new(App::with_file(&path)),
+                None => Box::new(app_state::load::<App>(cc)),
+            }
         }),
     ).expect("egui error");
 }
\ No newline at end of file
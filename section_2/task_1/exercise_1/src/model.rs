@@ -1,88 +1,86 @@
+use std::path::Path;
+use numerics::io::read_points;
+use numerics::spline::{check_nodes, Boundary, HermiteSpline, SmoothingSpline};
+
 const LENGTH: usize = 11;
 const X: [f64; LENGTH] = [-1.0, -0.8, -0.6, -0.4, -0.2, 0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
 const Y: [f64; LENGTH] = [0.0384615, 0.0588235, 0.1, 0.2, 0.5, 1.0, 0.5, 0.2, 0.1, 0.0588235, 0.0384615];
-const D: [f64; LENGTH] = [0.0739645, 0.923078, 2.11765, 6.0, 12.0, 0.0, -12.0, -6.0, -2.11765, -0.923078, -0.0739645];
+/// Derivative of `1 / (1 + 25 x^2)` at the ends.
+const END_SLOPES: [f64; 2] = [0.0739645, -0.0739645];
 
+/// Nodes of the spline and the slopes at its ends, exact or estimated from the data.
 pub struct Model {
-    s: Vec<f64>,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    pub end_slopes: [f64; 2],
 }
 
 
 impl Model {
-    pub fn new() -> Self {
-        Self {
-            s: solve_s(),
-        }
-    }
-
-    pub fn solve(&self, t: f64) -> f64 {
-        let mut i = 1;
-        while t >= X[i] && i < (LENGTH - 1) {
-            i += 1;
+    /// Model of `points`, which must be sorted by `x` without repetitions but may be spaced unevenly.
+    ///
+    /// Without `end_slopes` the slopes at the ends are estimated from the data.
+    pub fn new(points: &[[f64; 2]], end_slopes: Option<[f64; 2]>) -> Result<Self, String> {
+        if points.len() < 2 {
+            return Err("at least two nodes are needed".to_string());
         }
 
-        let x0 = X[i - 1];
-        let y0 = Y[i - 1];
-        let s0 = self.s[i - 1];
-
-        let x1 = X[i];
-        let y1 = Y[i];
-        let s1 = self.s[i];
-
-        let hx = x1 - x0;
+        let x: Vec<f64> = points.iter().map(|point| point[0]).collect();
+        let y: Vec<f64> = points.iter().map(|point| point[1]).collect();
+        check_nodes(&x).map_err(|error| error.to_string())?;
+        let end_slopes = end_slopes.unwrap_or_else(|| HermiteSpline::estimated_end_slopes(&x, &y));
 
-        let pl =
-            y0 * (t - x1).powi(2) * (2.0 * (t - x0) + hx) / hx.powi(3) +
-            y1 * (t - x0).powi(2) * (2.0 * (x1 - t) + hx) / hx.powi(3) +
-            s0 * (t - x1).powi(2) * (t - x0) / hx.powi(2) +
-            s1 * (t - x0).powi(2) * (t - x1) / hx.powi(2);
-        pl
+        Ok(Self { x, y, end_slopes })
     }
-}
 
-impl Default for Model {
-    fn default() -> Self {
-        Self::new()
+    /// Model of the exercise data, with its exact end slopes unless others are given.
+    pub fn exercise(end_slopes: Option<[f64; 2]>) -> Self {
+        let points: Vec<[f64; 2]> = X.iter().zip(Y).map(|(&x, y)| [x, y]).collect();
+        Self::new(&points, Some(end_slopes.unwrap_or(END_SLOPES))).unwrap()
     }
-}
-
-fn solve_s() -> Vec<f64> {
-    // direct sweep method
-    let mut a = vec![1.0; LENGTH];
-    let mut b = vec![4.0; LENGTH];
-    let mut c = vec![1.0; LENGTH];
 
-    a[LENGTH - 1] = 0.0;
-    c[0] = 0.0;
-
-    b[LENGTH - 1] = 1.0;
-    b[0] = 1.0;
-
-    let mut alpha = vec![0.0; LENGTH];
-    let mut betta = vec![0.0; LENGTH];
+    /// Model of the `x y` pairs of a CSV or TSV file, see [`Model::new`] for `end_slopes`.
+    pub fn read(path: &Path, end_slopes: Option<[f64; 2]>) -> Result<Self, String> {
+        let points = read_points(path).map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::new(&points, end_slopes).map_err(|error| format!("{}: {error}", path.display()))
+    }
 
-    alpha[0] = -c[0]/b[0];
-    betta[0] = D[0]/b[0];
+    /// The cubic spline through the nodes with the given end conditions.
+    pub fn spline(&self, boundary: Boundary) -> Result<HermiteSpline, String> {
+        HermiteSpline::interpolating(&self.x, &self.y, boundary).map_err(|error| error.to_string())
+    }
 
-    for i in 1..LENGTH - 1 {
-        alpha[i] = -c[i]/(b[i] + a[i] * alpha[i - 1]);
-        betta[i] = (D[i] - a[i] * betta[i - 1]) / (b[i] + a[i] * alpha[i - 1]);
+    /// The monotone Fritsch–Carlson interpolant, which does not overshoot the peak.
+    pub fn monotone(&self) -> Result<HermiteSpline, String> {
+        HermiteSpline::monotone(&self.x, &self.y).map_err(|error| error.to_string())
     }
 
-    // reverse sweep method
-    betta[LENGTH - 1] = (D[LENGTH - 1] - a[LENGTH - 1] * betta[LENGTH - 2]) / (b[LENGTH - 1] + a[LENGTH - 1] * alpha[LENGTH - 2]);
+    pub fn akima(&self) -> Result<HermiteSpline, String> {
+        HermiteSpline::akima(&self.x, &self.y).map_err(|error| error.to_string())
+    }
 
-    let mut s = vec![0.0; LENGTH];
-    s[LENGTH - 1] = betta[LENGTH - 1];
+    /// The smoothing spline for noisy data, which no longer passes through the nodes.
+    pub fn smoothing(&self, lambda: f64) -> Result<SmoothingSpline, String> {
+        SmoothingSpline::new(&self.x, &self.y, lambda).map_err(|error| error.to_string())
+    }
 
-    for i in (0..LENGTH - 1).rev() {
-        s[i] = alpha[i] * s[i + 1] + betta[i];
+    /// The smoothing spline with `λ` chosen by generalized cross-validation.
+    pub fn cross_validated(&self) -> Result<SmoothingSpline, String> {
+        SmoothingSpline::cross_validated(&self.x, &self.y).map_err(|error| error.to_string())
     }
 
-    for i in &s {
-        println!("{i}");
+    pub fn nodes(&self) -> Vec<[f64; 2]> {
+        self.x.iter().zip(&self.y).map(|(&x, &y)| [x, y]).collect()
     }
 
-    s
+    /// The first and the last node.
+    pub fn range(&self) -> (f64, f64) {
+        (self.x[0], self.x[self.x.len() - 1])
+    }
 }
 
+impl Default for Model {
+    fn default() -> Self {
+        Self::exercise(None)
+    }
+}
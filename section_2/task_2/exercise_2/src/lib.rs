@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+use nalgebra::{DMatrix, DVector};
+use numerics::io;
+use numerics::linalg::{Diagnostics, Lu, Pivoting};
+
+pub const A: [f64;49] = [
+    11.8336,	0.109449,	0.470703,	0.535582,	0.583178,	0.293942,	0.165154,
+    0.601258,	12.3133,	0.771123,	0.778574,	0.0236104,	0.922757,	0.992454,
+    0.350409,	0.0450047,	8.52039,	0.633756,	0.642208,	0.389875,	0.664971,
+    0.843882,	0.56904,	0.398212,	4.57977,	0.673513,	0.419507,	0.587398,
+    0.008335,	0.942441,	0.771263,	0.147503,	15.4716,	0.898747,	0.332963,
+    0.204548,	0.839035,	0.250388,	0.238638,	0.616616,	14.1895,	0.404504,
+    0.402517,	0.516192,	0.292009,	0.349202,	0.185556,	0.603218,	1.50995
+];
+
+pub const B: [f64;7] = [0.772658, 0.642185, 0.815048, 0.47394, 0.452691, 0.283226, 0.984643];
+
+pub fn system() -> (DMatrix<f64>, DVector<f64>) {
+    let a = DMatrix::from_vec(7, 7, Vec::from(A)).transpose();
+    let b = DVector::from_vec(Vec::from(B));
+    (a, b)
+}
+
+/// Prints the norms and the spectral radius of the Jacobi iteration matrix of `a`,
+/// with a warning for every reason why the iterations might not converge.
+pub fn print_diagnostics(a: &DMatrix<f64>) -> Result<Diagnostics, String> {
+    let diagnostics = Diagnostics::new(a).map_err(|error| error.to_string())?;
+
+    println!("iteration matrix norms: inf {:.6}, 1 {:.6}, 2 {:.6}, frobenius {:.6}",
+        diagnostics.norm_inf, diagnostics.norm_1, diagnostics.norm_2, diagnostics.frobenius);
+    println!("spectral radius: {:.6}", diagnostics.spectral_radius);
+    println!("diagonal dominance: {:?}", diagnostics.dominance);
+    for warning in diagnostics.warnings() {
+        println!("warning: {warning}");
+    }
+    Ok(diagnostics)
+}
+
+/// Prints the distance from `x` to the solution by LU with partial pivoting,
+/// along with the determinant and the estimated condition number of `a`.
+pub fn print_direct_check(a: &DMatrix<f64>, b: &DVector<f64>, x: &DVector<f64>) -> Result<(), String> {
+    let lu = Lu::new(a, Pivoting::Partial).map_err(|error| error.to_string())?;
+    let direct = lu.solve(b).map_err(|error| error.to_string())?;
+
+    println!("distance to the LU solution: {}", (x - &direct).norm());
+    println!("determinant: {}, condition number estimate: {}", lu.determinant(), lu.condition());
+    Ok(())
+}
+
+/// Files of a system given on the command line as `matrix [rhs]`, in the Matrix Market (`.mtx`) or CSV format.
+///
+/// Without `rhs` the matrix file holds the augmented matrix `[A | b]`.
+pub struct Files {
+    pub matrix: PathBuf,
+    pub rhs: Option<PathBuf>,
+}
+
+impl Files {
+    /// The files of the command line arguments, if any.
+    pub fn from_args() -> Option<Self> {
+        Self::new(std::env::args_os().skip(1).map(PathBuf::from))
+    }
+
+    /// The files of the first one or two `paths`, if any.
+    pub fn new(mut paths: impl Iterator<Item = PathBuf>) -> Option<Self> {
+        paths.next().map(|matrix| Self { matrix, rhs: paths.next() })
+    }
+
+    pub fn read(&self) -> Result<(DMatrix<f64>, DVector<f64>), String> {
+        io::read_system(&self.matrix, self.rhs.as_deref()).map_err(|error| format!("{}: {error}", self.matrix.display()))
+    }
+
+    /// Writes the solution and the residual of `method` next to the matrix and in its format,
+    /// e.g. `A.seidel.x.mtx` and `A.seidel.residual.mtx` for `A.mtx`.
+    pub fn write(&self, method: &str, x: &DVector<f64>, residual: &DVector<f64>) -> Result<Vec<PathBuf>, String> {
+        let extension = self.matrix.extension().and_then(|extension| extension.to_str()).unwrap_or("csv");
+        let path = |name: &str| self.matrix.with_extension(format!("{method}.{name}.{extension}"));
+        let write = |path: &Path, vector: &DVector<f64>| io::write_vector(path, vector).map_err(|error| format!("{}: {error}", path.display()));
+
+        let paths = [path("x"), path("residual")];
+        write(&paths[0], x)?;
+        write(&paths[1], residual)?;
+        Ok(Vec::from(paths))
+    }
+}
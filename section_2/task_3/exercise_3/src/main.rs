@@ -1,95 +1,103 @@
-use nalgebra::{DMatrix, DVector, Dyn, Matrix, OMatrix, SMatrix, VecStorage, Vector};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use nalgebra::{DMatrix, DVector};
+use numerics::linalg::{best_relaxation, jacobi_form, optimal_relaxation, relaxation_sweep, seidel_method, spectral_radius};
+use section_2_task_2::{print_diagnostics, print_direct_check, system, Files};
 
-const A: [f64;49] = [
-    11.8336,	0.109449,	0.470703,	0.535582,	0.583178,	0.293942,	0.165154,
-    0.601258,	12.3133,	0.771123,	0.778574,	0.0236104,	0.922757,	0.992454,
-    0.350409,	0.0450047,	8.52039,	0.633756,	0.642208,	0.389875,	0.664971,
-    0.843882,	0.56904,	0.398212,	4.57977,	0.673513,	0.419507,	0.587398,
-    0.008335,	0.942441,	0.771263,	0.147503,	15.4716,	0.898747,	0.332963,
-    0.204548,	0.839035,	0.250388,	0.238638,	0.616616,	14.1895,	0.404504,
-    0.402517,	0.516192,	0.292009,	0.349202,	0.185556,	0.603218,	1.50995
-];
+const EPSILON: f64 = 0.5e-8;
+const OMEGA: f64 = 1.5;
+/// Values of ω in `(0, 2)` tried by the sweep.
+const SWEEP_POINTS: usize = 39;
+const SWEEP_LIMIT: usize = 10_000;
 
-const B: [f64;7] = [0.772658, 0.642185, 0.815048, 0.47394, 0.452691, 0.283226, 0.984643];
+/// Iterations of the relaxation method for ω over `(0, 2)`, and the optimal ω predicted from the Jacobi method.
+fn sweep(a: &DMatrix<f64>, b: &DVector<f64>) -> Result<(), String> {
+    let omegas: Vec<f64> = (1..=SWEEP_POINTS).map(|k| 2.0 * k as f64 / (SWEEP_POINTS + 1) as f64).collect();
+    let points = relaxation_sweep(a, b, EPSILON, SWEEP_LIMIT, &omegas).map_err(|error| error.to_string())?;
 
-fn seidel_method(omega: f64) {
-    let a = DMatrix::from_vec(7, 7, Vec::from(A)).transpose().normalize();
-    let b = DVector::from_vec(Vec::from(B));
+    println!("omega  iters");
+    for point in &points {
+        let iterations = if point.converged { point.iterations.to_string() } else { format!("> {SWEEP_LIMIT}") };
+        println!("{:.3}  {iterations}", point.omega);
+    }
 
-    let g = {
-        let mut g = a.clone();
-        for i in 0..7 {
-            for j in 0..7 {
-                if i == j {
-                    g[(i, j)] = 0.0;
-                } else {
-                    g[(i, j)] = -(a[(i,j)] / a[(i,i)]);
-                }
-            }
-        }
-        g
-    };
+    let rho = spectral_radius(&jacobi_form(a, b).0);
+    println!("\nspectral radius of the Jacobi iteration matrix: {rho}");
+    match optimal_relaxation(rho) {
+        Some(omega) => match seidel_method(a, b, EPSILON, omega) {
+            Ok(solution) => println!("theoretical omega 2 / (1 + sqrt(1 - rho^2)): {omega} ({} iters)", solution.iterations),
+            Err(error) => println!("theoretical omega 2 / (1 + sqrt(1 - rho^2)): {omega} ({error})"),
+        },
+        None => println!("theoretical omega: none, the Jacobi method diverges"),
+    }
+    match best_relaxation(&points) {
+        Some(best) => println!("empirical omega: {} ({} iters)", best.omega, best.iterations),
+        None => println!("empirical omega: none converged"),
+    }
+    Ok(())
+}
 
-    let f = {
-        let mut f = DVector::<f64>::zeros(7);
-        for i in 0..7 {
-            f[i] = b[i] / a[(i,i)];
+/// Solves the system of the exercise, or the one of the files `matrix [rhs]` given as arguments,
+/// whose solution and residual are then written next to the matrix.
+///
+/// With `--sweep` as the first argument it compares the number of iterations for ω over `(0, 2)` instead.
+fn main() -> ExitCode {
+    let mut args = std::env::args_os().skip(1).peekable();
+    let sweep_mode = args.next_if(|arg| arg == "--sweep").is_some();
+    let files = Files::new(args.map(PathBuf::from));
+    let (a, b) = match files.as_ref().map(Files::read) {
+        Some(Ok(system)) => system,
+        Some(Err(error)) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
         }
-        f
+        None => system(),
     };
+    let a = a.normalize();
+    if let Err(error) = print_diagnostics(&a) {
+        eprintln!("{error}");
+        return ExitCode::FAILURE;
+    }
 
-    let epsilon = 0.5 * 10.0_f64.powi(-8);
-    let norm = g.norm();
-    let epsilon = ((1.0 - norm) / norm) * epsilon;
-
-    let mut iters = 0;
-    let mut error = f64::MAX;
-    let mut x = g.clone() * f.clone() + f.clone();
+    if sweep_mode {
+        return match sweep(&a, &b) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
 
-    while error > epsilon {
-        iters += 1;
-        let mut next = x.clone();
+    let solution = match seidel_method(&a, &b, EPSILON, OMEGA) {
+        Ok(solution) => solution,
+        Err(error) => {
+            eprintln!("relaxation with omega {OMEGA}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let x = &solution.x;
+    let residual = &b - &a * x;
 
-        for i in 0..7 {
-            next[i] = {
-                let s1 = {
-                    let mut sum = 0.0;
-                    if i >= 1 {
-                        for j in 0..i - 1 {
-                            sum += g[(i,j)] * next[j];
-                        }
-                        sum
-                    } else {
-                        0.0
-                    }
-                };
+    println!("x: {x}");
+    println!("a*x: {}", &a * x);
 
-                let s2 = {
-                    let mut sum = 0.0;
-                    for j in i..7 {
-                        sum += g[(i,j)] * x[j];
-                    }
-                    sum
-                };
+    println!("iters: {}", solution.iterations);
+    println!("error: {}", solution.error);
+    println!("residual vector norm: {}", residual.norm());
+    if let Err(error) = print_direct_check(&a, &b, x) {
+        println!("no direct solution: {error}");
+    }
 
-                s1 + s2 + f[i]
+    if let Some(files) = files {
+        match files.write("relaxation", x, &residual) {
+            Ok(paths) => paths.iter().for_each(|path| println!("written {}", path.display())),
+            Err(error) => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
             }
         }
-
-        let next = next.clone() + (omega - 1.0) * (next - x.clone());
-        error = (next.clone() - x).norm();
-        x = next;
     }
 
-    println!("x: {x}");
-    println!("a*x: {}", a.clone() * x.clone());
-
-    println!("iters: {iters}");
-    println!("error: {error}");
-    println!("residual vector norm: {}", (b - a*x).norm());
-}
-
-fn main() {
-    seidel_method(1.5);
-    println!("Hello, world!");
+    ExitCode::SUCCESS
 }
@@ -1,8 +1,13 @@
+use std::rc::Rc;
 use eframe::Frame;
 use egui::{Context, Ui};
 use egui_plot::{Line, Plot, PlotPoints, Points};
+use numerics::interp::{lagrange, nearest, newton, piecewise_lagrange};
+use serde::{Deserialize, Serialize};
 use crate::model::*;
 
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct App{
 	show_lines: bool,
 	show_original: bool,
@@ -10,31 +15,32 @@ pub struct App{
 	show_lagrange_3: bool,
 	show_piecewise_3: bool,
 	show_newton: bool,
+	#[serde(skip)]
 	points: [[f64; 2]; 5],
 	x: f64,
+
+	#[serde(skip)]
+	function: Rc<dyn Fn(f64) -> f64>,
+	#[serde(skip)]
+	a: f64,
+	#[serde(skip)]
+	b: f64,
 }
 
 impl eframe::App for App {
-	fn update(&mut self, ctx: &Context, frame: &mut Frame) {
-		egui::CentralPanel::default().show(ctx, |ui| {
-			ui.style_mut().spacing.slider_width = (1200.0);
-
-			let lagrange_2_error = (self.lagrange_2(self.x) - f(self.x)).abs();
-			let lagrange_3_error = (self.lagrange_3(self.x) - f(self.x)).abs();
-			let newton_error = (self.newton(self.x) - f(self.x)).abs();
-
-			ui.checkbox(&mut self.show_lines, "show lines");
-			ui.checkbox(&mut self.show_original, "show original");
-			ui.checkbox(&mut self.show_lagrange_2, format!("show lagrange n2: {}", lagrange_2_error));
-			ui.checkbox(&mut self.show_lagrange_3, format!("show lagrange n3: {}", lagrange_3_error));
-			ui.checkbox(&mut self.show_piecewise_3, format!("show piecewise n3: {}", lagrange_3_error));
-			ui.checkbox(&mut self.show_newton, format!("show newton: {}", newton_error));
-
-			ui.add(egui::Slider::new(&mut self.x, self.points[0][0]..=self.points[4][0]).text("x").drag_value_speed(10.0_f64.powi(-5)));
+	fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+		egui::TopBottomPanel::bottom("state").show(ctx, |ui| app_state::file_ui(ui, self));
+		egui::CentralPanel::default().show(ctx, |ui| self.ui(ui));
+	}
 
+	fn save(&mut self, storage: &mut dyn eframe::Storage) {
+		app_state::save(storage, self);
+	}
+}
 
-			self.render_plot(ui);
-		});
+impl app_state::State for App {
+	fn restore(&mut self) {
+		self.x = self.x.clamp(self.a, self.b);
 	}
 }
 
@@ -47,13 +53,45 @@ impl Default for App {
 			show_lagrange_3: true,
 			show_newton: true,
 			show_piecewise_3: true,
-			points: get_points(),
+			points: get_points(f, A, B),
 			x: 0.0,
+
+			function: Rc::new(f),
+			a: A,
+			b: B,
 		}
 	}
 }
 
 impl App {
+	/// Interpolates `function` on `[a, b]` instead of the exercise function.
+	pub fn set_function(&mut self, function: Rc<dyn Fn(f64) -> f64>, a: f64, b: f64) {
+		self.points = get_points(&*function, a, b);
+		self.x = self.x.clamp(a, b);
+		self.function = function;
+		self.a = a;
+		self.b = b;
+	}
+
+	pub fn ui(&mut self, ui: &mut Ui) {
+		ui.style_mut().spacing.slider_width = 1200.0;
+
+		let lagrange_2_error = (self.lagrange_2(self.x) - (self.function)(self.x)).abs();
+		let lagrange_3_error = (self.lagrange_3(self.x) - (self.function)(self.x)).abs();
+		let newton_error = (self.newton(self.x) - (self.function)(self.x)).abs();
+
+		ui.checkbox(&mut self.show_lines, "show lines");
+		ui.checkbox(&mut self.show_original, "show original");
+		ui.checkbox(&mut self.show_lagrange_2, format!("show lagrange n2: {}", lagrange_2_error));
+		ui.checkbox(&mut self.show_lagrange_3, format!("show lagrange n3: {}", lagrange_3_error));
+		ui.checkbox(&mut self.show_piecewise_3, format!("show piecewise n3: {}", lagrange_3_error));
+		ui.checkbox(&mut self.show_newton, format!("show newton: {}", newton_error));
+
+		ui.add(egui::Slider::new(&mut self.x, self.points[0][0]..=self.points[4][0]).text("x").drag_value_speed(10.0_f64.powi(-5)));
+
+		self.render_plot(ui);
+	}
+
 	fn render_plot(&self, ui: &mut Ui) {
 		Plot::new("my_plot").show(ui, |plot_ui| {
 			let nodes = self.generate_nodes();
@@ -116,18 +154,18 @@ impl App {
 		let amount = 5000;
 		let mut points = Vec::with_capacity(amount);
 
-		let step = (B - A) / amount as f64;
+		let step = (self.b - self.a) / amount as f64;
 
 		for i in 0..amount {
-			let x = A + step * i as f64;
-			points.push([x, f(x)]);
+			let x = self.a + step * i as f64;
+			points.push([x, (self.function)(x)]);
 		}
 
 		Line::new(PlotPoints::new(points))
 	}
 
 	fn generate_original_point(&self) -> Points {
-		let points = PlotPoints::new([[self.x, f(self.x)]].to_vec());
+		let points = PlotPoints::new([[self.x, (self.function)(self.x)]].to_vec());
 		let points = Points::new(points);
 		points.radius(5.0)
 	}
@@ -139,19 +177,7 @@ impl App {
 	}
 
 	fn lagrange_2(&self, x: f64) -> f64 {
-		let mut points = self.points.clone();
-		points.sort_by(|[a, _], [b,_]| {
-			(a - x).abs().total_cmp(&(b - x).abs())
-		});
-
-		let mut points = Vec::from(&points[0..=1]);
-		points.sort_by(|[a,_], [b,_]| a.total_cmp(b));
-
-
-		let a = points[0][1] * (x - points[1][0]) / (points[0][0] - points[1][0]);
-		let b = points[1][1] * (x - points[0][0]) / (points[1][0] - points[0][0]);
-
-		a + b
+		lagrange(&nearest(&self.points, x, 2), x)
 	}
 
 	fn generate_lagrange_2_line(&self) -> Line {
@@ -165,20 +191,7 @@ impl App {
 	}
 
 	fn lagrange_3(&self, x: f64) -> f64 {
-		let mut points = self.points.clone();
-
-		points.sort_by(|[a, _], [b,_]| {
-			(a - x).abs().total_cmp(&(b - x).abs())
-		});
-
-		let mut points = Vec::from(&points[0..=2]);
-		points.sort_by(|[a,_], [b,_]| a.total_cmp(b));
-
-		let a = points[0][1] * (x - points[1][0]) * (x - points[2][0]) / (points[0][0] - points[1][0]) / (points[0][0] - points[2][0]);
-		let b = points[1][1] * (x - points[0][0]) * (x - points[2][0]) / (points[1][0] - points[0][0]) / (points[1][0] - points[2][0]);
-		let c = points[2][1] * (x - points[0][0]) * (x - points[1][0]) / (points[2][0] - points[0][0]) / (points[2][0] - points[1][0]);
-
-		a + b + c
+		lagrange(&nearest(&self.points, x, 3), x)
 	}
 
 	fn generate_lagrange_3_point(&self) -> Points {
@@ -191,10 +204,10 @@ impl App {
 		let amount = 5000;
 		let mut points = Vec::with_capacity(amount);
 
-		let step = (B - A) / amount as f64;
+		let step = (self.b - self.a) / amount as f64;
 
 		for i in 0..amount {
-			let x = A + step * i as f64;
+			let x = self.a + step * i as f64;
 			points.push([x, self.lagrange_3(x)]);
 		}
 
@@ -202,7 +215,7 @@ impl App {
 	}
 
 	fn newton(&self, x: f64) -> f64 {
-		newton_solve(&self.points, x)
+		newton(&self.points, x)
 	}
 
 	fn newton_point(&self) -> Points {
@@ -215,10 +228,10 @@ impl App {
 		let amount = 1000;
 		let mut points = Vec::with_capacity(amount);
 
-		let step = (B - A) / amount as f64;
+		let step = (self.b - self.a) / amount as f64;
 
 		for i in 0..amount {
-			let x = A + step * i as f64;
+			let x = self.a + step * i as f64;
 			points.push([x, self.newton(x)]);
 		}
 
@@ -226,19 +239,7 @@ impl App {
 	}
 
 	fn piecewise_3(&self, x: f64) -> f64 {
-		let mut points = self.points.clone().to_vec();
-
-		if x <= 0.0 {
-			points = points[0..=2].to_vec();
-		} else {
-			points = points[2..=4].to_vec();
-		}
-
-		let a = points[0][1] * (x - points[1][0]) * (x - points[2][0]) / (points[0][0] - points[1][0]) / (points[0][0] - points[2][0]);
-		let b = points[1][1] * (x - points[0][0]) * (x - points[2][0]) / (points[1][0] - points[0][0]) / (points[1][0] - points[2][0]);
-		let c = points[2][1] * (x - points[0][0]) * (x - points[1][0]) / (points[2][0] - points[0][0]) / (points[2][0] - points[1][0]);
-
-		a + b + c
+		piecewise_lagrange(&self.points, x, 3)
 	}
 
 	fn generate_piecewise_3_point(&self) -> Points {
@@ -251,32 +252,13 @@ impl App {
 		let amount = 5000;
 		let mut points = Vec::with_capacity(amount);
 
-		let step = (B - A) / amount as f64;
+		let step = (self.b - self.a) / amount as f64;
 
 		for i in 0..amount {
-			let x = A + step * i as f64;
+			let x = self.a + step * i as f64;
 			points.push([x, self.piecewise_3(x)]);
 		}
 
 		Line::new(PlotPoints::new(points))
 	}
 }
-
-fn newton(points: &[[f64; 2]]) -> f64 {
-	if points.len() == 1 {
-		return points[0][1];
-	}
-
-	let a = newton(&points[1..points.len()]) - newton(&points[0..points.len() - 1]);
-	let b = points.last().unwrap()[0] - points.first().unwrap()[0];
-
-	a / b
-}
-
-fn newton_solve(points: &[[f64; 2]], x: f64) -> f64 {
-	newton(&points[0..=0])
-	 + newton(&points[0..=1]) * (x - points[0][0])
-	 + newton(&points[0..=2]) * (x - points[0][0]) * (x - points[1][0])
-	 + newton(&points[0..=3]) * (x - points[0][0]) * (x - points[1][0]) * (x - points[2][0])
-	 + newton(&points[0..=4]) * (x - points[0][0]) * (x - points[1][0]) * (x - points[2][0]) * (x - points[3][0])
-}
\ No newline at end of file
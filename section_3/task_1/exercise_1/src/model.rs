@@ -8,11 +8,11 @@ pub fn f(x: f64) -> f64 {
 	-(3.0 * x.powi(2) + x + 3.0) / (0.5 * x.powi(2) + PI / 4.0).tan().powi(3)
 }
 
-pub fn get_points() -> [[f64; 2]; N] {
-	let step = (B - A) / (N - 1) as f64;
+pub fn get_points(f: impl Fn(f64) -> f64, a: f64, b: f64) -> [[f64; 2]; N] {
+	let step = (b - a) / (N - 1) as f64;
 	let mut points = [[0.0, 0.0]; N];
 	for (index, value) in points.iter_mut().enumerate() {
-		let x = A + step * (index as f64);
+		let x = a + step * (index as f64);
 		let y = f(x);
 		*value = [x, y];
 	}
@@ -1,73 +1,63 @@
-use std::env::var;
-use std::ops::Not;
-use std::process::exit;
+use std::rc::Rc;
 use eframe::Frame;
 use egui::{Context, Ui};
 use egui_plot::{Line, Plot, PlotPoints, Points};
-use nalgebra::{DMatrix, DVector};
-use rand::Rng;
+use numerics::interp::{random_nodes, uniform_nodes};
+use numerics::spline::{cubic_spline_coefficients, parabolic_spline_coefficients, piecewise_polynomial};
+use serde::{Deserialize, Serialize};
 use crate::model::*;
 
 
 
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct App {
 	random: bool,
+	seed: u64,
 	amount: usize,
+	#[serde(skip)]
 	points: Vec<[f64; 2]>,
+	#[serde(skip)]
 	cubic_spline_coefficients: Vec<f64>,
+	#[serde(skip)]
 	parabolic_spline_coefficients: Vec<f64>,
+	/// Why the splines could not be built for the current nodes.
+	#[serde(skip)]
+	error: Option<String>,
 
 
 	show_original: bool,
 	show_cubic: bool,
 	show_parabolic: bool,
 	first_derivative: f64,
+
+	#[serde(skip)]
+	function: Rc<dyn Fn(f64) -> f64>,
+	#[serde(skip)]
+	derivative: Rc<dyn Fn(f64) -> f64>,
+	#[serde(skip)]
+	a: f64,
+	#[serde(skip)]
+	b: f64,
 }
 
 
 impl eframe::App for App {
-	fn update(&mut self, ctx: &Context, frame: &mut Frame) {
-		egui::CentralPanel::default().show(ctx, |ui| {
-			ui.style_mut().spacing.slider_width = (1200.0);
-
-			ui.horizontal(|ui|{
-				if ui.add(egui::Slider::new(&mut self.amount, 3..=16).text("amount of points")).changed() {
-					self.generate_coefficients();
-				}
-			});
-
-			ui.horizontal(|ui|{
-				if ui.add(egui::Slider::new(&mut self.first_derivative, -5.0..=5.0).text("first derivative")).changed() {
-					self.parabolic_spline_coefficients = generate_parabolic_spline_coefficients(&self.points, self.first_derivative);
-				}
-			});
-
-
-			if ui.button("regenerate").clicked() {
-				self.generate_coefficients();
-			}
-
-			if ui.button("use real derivatives").clicked() {
-				self.first_derivative = f_d(self.points[0][0]);
-				self.parabolic_spline_coefficients = generate_parabolic_spline_coefficients(&self.points, self.first_derivative);
-			}
-
-			if ui.button("use pseudo derivatives").clicked() {
-				let dx = self.points[1][0] - self.points[0][0];
-				let dy = self.points[1][1] - self.points[0][1];
-
-				self.first_derivative = dy / dx;
-				self.parabolic_spline_coefficients = generate_parabolic_spline_coefficients(&self.points, self.first_derivative);
-			}
-
-			ui.checkbox(&mut self.random, "generate points in random places");
+	fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+		egui::TopBottomPanel::bottom("state").show(ctx, |ui| app_state::file_ui(ui, self));
+		egui::CentralPanel::default().show(ctx, |ui| self.ui(ui));
+	}
 
-			ui.checkbox(&mut self.show_original, "show original");
-			ui.checkbox(&mut self.show_cubic, "show cubic");
-			ui.checkbox(&mut self.show_parabolic, "show parabolic");
+	fn save(&mut self, storage: &mut dyn eframe::Storage) {
+		app_state::save(storage, self);
+	}
+}
 
-			self.render_plot(ui);
-		});
+impl app_state::State for App {
+	fn restore(&mut self) {
+		// an imported state may hold any amount, but the slider and the splines need at least three nodes
+		self.amount = self.amount.clamp(3, 16);
+		self.generate_coefficients();
 	}
 }
 
@@ -75,27 +65,96 @@ impl Default for App {
 	fn default() -> Self {
 		let amount = 4;
 		let random = false;
-		let points = generate_points(amount);
-		let cubic_spline_coefficients = generate_cubic_spline_coefficients(&points);
-		let parabolic_spline_coefficients = generate_parabolic_spline_coefficients(&points, 0.0);
+		let points = uniform_nodes(f, A, B, amount);
 
-		Self {
+		let mut app = Self {
 			random,
+			seed: 0,
 			amount,
 			points,
-			cubic_spline_coefficients,
-			parabolic_spline_coefficients,
+			cubic_spline_coefficients: Vec::new(),
+			parabolic_spline_coefficients: Vec::new(),
+			error: None,
 
 			show_original: true,
 			show_cubic: true,
 			show_parabolic: true,
 
 			first_derivative: 0.0,
-		}
+
+			function: Rc::new(f),
+			derivative: Rc::new(f_d),
+			a: A,
+			b: B,
+		};
+		app.generate_coefficients();
+		app
 	}
 }
 
 impl App {
+	/// Builds the splines for `function` on `[a, b]` instead of the exercise function.
+	///
+	/// `derivative` is used by the "use real derivatives" button.
+	pub fn set_function(&mut self, function: Rc<dyn Fn(f64) -> f64>, derivative: Rc<dyn Fn(f64) -> f64>, a: f64, b: f64) {
+		self.function = function;
+		self.derivative = derivative;
+		self.a = a;
+		self.b = b;
+		self.generate_coefficients();
+	}
+
+	pub fn ui(&mut self, ui: &mut Ui) {
+		ui.style_mut().spacing.slider_width = 1200.0;
+
+		ui.horizontal(|ui|{
+			if ui.add(egui::Slider::new(&mut self.amount, 3..=16).text("amount of points")).changed() {
+				self.generate_coefficients();
+			}
+		});
+
+		ui.horizontal(|ui|{
+			if ui.add(egui::Slider::new(&mut self.first_derivative, -5.0..=5.0).text("first derivative")).changed() {
+				self.update_parabolic_spline();
+			}
+		});
+
+
+		ui.horizontal(|ui| {
+			if ui.button("regenerate").clicked() {
+				self.seed += 1;
+				self.generate_coefficients();
+			}
+			if ui.add(egui::DragValue::new(&mut self.seed).prefix("seed ")).changed() {
+				self.generate_coefficients();
+			}
+		});
+
+		if ui.button("use real derivatives").clicked() {
+			self.first_derivative = (self.derivative)(self.a);
+			self.update_parabolic_spline();
+		}
+
+		if ui.button("use pseudo derivatives").clicked() {
+			if let [[x0, y0], [x1, y1], ..] = self.points[..] {
+				self.first_derivative = (y1 - y0) / (x1 - x0);
+				self.update_parabolic_spline();
+			}
+		}
+
+		ui.checkbox(&mut self.random, "generate points in random places");
+
+		ui.checkbox(&mut self.show_original, "show original");
+		ui.checkbox(&mut self.show_cubic, "show cubic");
+		ui.checkbox(&mut self.show_parabolic, "show parabolic");
+
+		if let Some(error) = &self.error {
+			ui.colored_label(egui::Color32::RED, error);
+		}
+
+		self.render_plot(ui);
+	}
+
 	fn render_plot(&self, ui: &mut Ui) {
 		Plot::new("my_plot").show(ui, |plot_ui| {
 			if self.show_original {
@@ -103,12 +162,12 @@ impl App {
 				plot_ui.line(original_line);
 			}
 
-			if self.show_parabolic {
+			if self.show_parabolic && !self.parabolic_spline_coefficients.is_empty() {
 				let parabolic_spline_line = self.generate_parabolic_spline_line();
 				plot_ui.line(parabolic_spline_line);
 			}
 
-			if self.show_cubic {
+			if self.show_cubic && !self.cubic_spline_coefficients.is_empty() {
 				let cubic_spline_line = self.generate_cubic_spline_line();
 				plot_ui.line(cubic_spline_line);
 			}
@@ -120,26 +179,35 @@ impl App {
 
 	fn generate_coefficients(&mut self) {
 		if self.random {
-			self.points = generate_random_points(self.amount);
+			self.points = random_nodes(&*self.function, self.a, self.b, self.amount, self.seed);
 		} else {
-			self.points = generate_points(self.amount);
+			self.points = uniform_nodes(&*self.function, self.a, self.b, self.amount);
 		}
-		self.cubic_spline_coefficients = generate_cubic_spline_coefficients(&self.points);
-		self.parabolic_spline_coefficients = generate_parabolic_spline_coefficients(&self.points, self.first_derivative);
+		let splines = cubic_spline_coefficients(&self.points)
+			.and_then(|cubic| Ok((cubic, parabolic_spline_coefficients(&self.points, self.first_derivative)?)));
+		(self.cubic_spline_coefficients, self.parabolic_spline_coefficients, self.error) = match splines {
+			Ok((cubic, parabolic)) => (cubic, parabolic, None),
+			Err(error) => (Vec::new(), Vec::new(), Some(error.to_string())),
+		};
+	}
+
+	/// Rebuilds the parabolic spline after its first derivative changed.
+	fn update_parabolic_spline(&mut self) {
+		// the system only depends on the nodes, whose errors generate_coefficients reported
+		self.parabolic_spline_coefficients = parabolic_spline_coefficients(&self.points, self.first_derivative).unwrap_or_default();
 	}
 
 	fn generate_original_line(&self) -> Line {
 		let points_amount = 5000;
 
-		let begin = self.points.first().unwrap()[0];
-		let end = self.points.last().unwrap()[0];
+		let (begin, end) = (self.a, self.b);
 
 		let delta = end - begin;
 		let step = delta / (points_amount - 1) as f64;
 
 		Line::new(PlotPoints::new((0..points_amount).map(|index| {
 			let x = (index as f64) * step + begin;
-			let y = f(x);
+			let y = (self.function)(x);
 			[x, y]
 		}).collect()))
 	}
@@ -149,38 +217,17 @@ impl App {
 	}
 
 	fn parabolic_spline(&self, x: f64) -> f64 {
-		for (index, points) in self.points.windows(2).enumerate() {
-			let left = points[0][0];
-			let right = points[1][0];
-
-			if (x >= left) && (x <= right) {
-				let &[a, b, c] = &self.parabolic_spline_coefficients[index * 3 .. (index + 1) * 3] else {panic!()};
-				return a * x.powi(2) + b * x + c;
-			}
-		}
-
-		0.0
+		piecewise_polynomial(&self.points, &self.parabolic_spline_coefficients, 2, x)
 	}
 
 	fn cubic_spline(&self, x: f64) -> f64 {
-		for (index, points) in self.points.windows(2).enumerate() {
-			let left = points[0][0];
-			let right = points[1][0];
-
-			if (x >= left) && (x <= right) {
-				let &[a, b, c, d] = &self.cubic_spline_coefficients[index * 4 .. (index + 1) * 4] else {panic!()};
-				return a * x.powi(3) + b * x.powi(2) + c * x + d;
-			}
-		}
-
-		0.0
+		piecewise_polynomial(&self.points, &self.cubic_spline_coefficients, 3, x)
 	}
 
 	fn generate_parabolic_spline_line(&self) -> Line {
 		let points_amount = 5000;
 
-		let begin = self.points.first().unwrap()[0];
-		let end = self.points.last().unwrap()[0];
+		let (begin, end) = (self.a, self.b);
 
 		let delta = end - begin;
 		let step = delta / (points_amount - 1) as f64;
@@ -195,8 +242,7 @@ impl App {
 	fn generate_cubic_spline_line(&self) -> Line {
 		let points_amount = 5000;
 
-		let begin = self.points.first().unwrap()[0];
-		let end = self.points.last().unwrap()[0];
+		let (begin, end) = (self.a, self.b);
 
 		let delta = end - begin;
 		let step = delta / (points_amount - 1) as f64;
@@ -208,115 +254,3 @@ impl App {
 		}).collect()))
 	}
 }
-
-fn generate_points(amount: usize) -> Vec<[f64; 2]> {
-	let delta = B - A;
-	let step = delta / (amount - 1) as f64;
-
-	(0..amount).map(|index| {
-		let x = (index as f64) * step + A;
-		let y = f(x);
-		[x, y]
-	}).collect()
-}
-
-fn generate_random_points(amount: usize) -> Vec<[f64; 2]> {
-	let mut rng = rand::thread_rng();
-
-	let mut points: Vec<_> = (0..amount - 2).map(|index| {
-		let x = rng.gen_range(A..B);
-		let y = f(x);
-		[x, y]
-	}).collect();
-	points.push([A, f(A)]);
-	points.push([B, f(B)]);
-	points.sort_by(|[a, _], [b, _]| a.partial_cmp(b).unwrap());
-
-	points
-}
-
-fn generate_parabolic_spline_coefficients(points: &[[f64; 2]], first_derivative: f64) -> Vec<f64> {
-	let mut variables_matrix = Vec::default();
-	let mut free_matrix = Vec::default();
-
-	let num_unknown_variables = points.windows(2).len();
-
-	for (index, points) in points.windows(2).enumerate() {
-		let variables: Vec<_> = points.iter().map(|&[x, y]| vec![vec![0.0; index * 3], vec![x.powi(2), x, 1.0], vec![0.0; (num_unknown_variables - index - 1) * 3]].concat()).collect();
-		let free: Vec<_> = points.iter().map(|&[x, y]| y).collect();
-
-		variables_matrix.extend_from_slice(&variables.concat());
-		free_matrix.extend_from_slice(&free);
-	}
-
-	for (index, &[x, y]) in points[1..points.len() - 1].iter().enumerate() {
-		let variables: Vec<_> = vec![vec![0.0; index * 3], vec![2.0 * x, 1.0, 0.0], vec![- 2.0 * x, - 1.0, 0.0], vec![0.0; (num_unknown_variables - index - 2) * 3]].concat();
-		let free: Vec<_> = vec![0.0];
-
-		variables_matrix.extend_from_slice(&variables);
-		free_matrix.extend_from_slice(&free);
-	}
-
-	{
-		let [x, y] = points[0];
-		variables_matrix.extend_from_slice(&vec![vec![2.0 * x, 1.0, 0.0], vec![0.0; (num_unknown_variables - 1) * 3]].concat());
-		free_matrix.extend_from_slice(&vec![first_derivative]);
-	}
-
-
-	let variables_matrix = DMatrix::from_vec(num_unknown_variables * 3, num_unknown_variables * 3, variables_matrix).transpose();
-	let free_matrix = DVector::from_vec(free_matrix);
-	let result = variables_matrix.try_inverse().unwrap() * free_matrix;
-
-	result.as_slice().to_vec()
-}
-
-fn generate_cubic_spline_coefficients(points: &[[f64; 2]]) -> Vec<f64> {
-	let mut variables_matrix = Vec::default();
-	let mut free_matrix = Vec::default();
-	let num_unknown_variables = points.windows(2).len();
-
-	for (index, points) in points.windows(2).enumerate() {
-		let variables: Vec<_> = points.iter().map(|&[x, y]| vec![vec![0.0; index * 4], vec![x.powi(3), x.powi(2), x, 1.0], vec![0.0; (num_unknown_variables - index - 1) * 4]].concat()).collect();
-		let free: Vec<_> = points.iter().map(|&[x, y]| y).collect();
-
-		variables_matrix.extend_from_slice(&variables.concat());
-		free_matrix.extend_from_slice(&free);
-	}
-
-	for (index, &[x, y]) in points[1..points.len() - 1].iter().enumerate() {
-		let variables: Vec<_> = vec![vec![0.0; index * 4], vec![3.0 * x.powi(2), 2.0 * x, 1.0, 0.0], vec![-3.0 * x.powi(2), -2.0 * x, -1.0, 0.0], vec![0.0; (num_unknown_variables - index - 2) * 4]].concat();
-		let free: Vec<_> = vec![0.0];
-
-		variables_matrix.extend_from_slice(&variables);
-		free_matrix.extend_from_slice(&free);
-	}
-
-	for (index, &[x, y]) in points[1..points.len() - 1].iter().enumerate() {
-		let variables: Vec<_> = vec![vec![0.0; index * 4], vec![3.0 * 2.0 * x, 2.0, 0.0, 0.0], vec![-3.0 * 2.0 * x, -2.0, 0.0, 0.0], vec![0.0; (num_unknown_variables - index - 2) * 4]].concat();
-		let free: Vec<_> = vec![0.0];
-
-		variables_matrix.extend_from_slice(&variables);
-		free_matrix.extend_from_slice(&free);
-	}
-
-	{
-		let &[x, y] = points.first().unwrap();
-		variables_matrix.extend_from_slice(&vec![vec![3.0 * 2.0 * x, 2.0, 0.0, 0.0], vec![0.0; (num_unknown_variables - 1) * 4]].concat());
-		free_matrix.extend_from_slice(&vec![0.0]);
-	}
-
-	{
-		let &[x, y] = points.last().unwrap();
-		variables_matrix.extend_from_slice(&vec![vec![0.0; (num_unknown_variables - 1) * 4], vec![3.0 * 2.0 * x, 2.0, 0.0, 0.0]].concat());
-		free_matrix.extend_from_slice(&vec![0.0]);
-	}
-
-	let variables_matrix = DMatrix::from_vec(num_unknown_variables * 4, num_unknown_variables * 4, variables_matrix).transpose();
-	let free_matrix = DVector::from_vec(free_matrix);
-	let result = variables_matrix.try_inverse().unwrap() * free_matrix;
-
-	result.as_slice().to_vec()
-}
-
-
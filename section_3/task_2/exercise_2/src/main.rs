@@ -1,12 +1,9 @@
-use crate::app::App;
-
-mod model;
-mod app;
+use section_3_task_2::App;
 
 fn main() {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]),
+        viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]).with_app_id("section_3_task_2"),
         ..Default::default()
     };
 
@@ -14,7 +11,7 @@ fn main() {
         "My egui App",
         options,
         Box::new(|cc| {
-            Box::<App>::default()
+            Box::new(app_state::load::<App>(cc))
         }),
     ).expect("egui error");
 }
@@ -1,9 +1,13 @@
 use eframe::Frame;
 use egui::{Context, Ui};
 use egui_plot::{Line, Plot, PlotPoints, Points};
-use crate::model::{f, ORIGINAL};
+use serde::{Deserialize, Serialize};
+use crate::model::{euler_solution, ORIGINAL};
 
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct App {
+	#[serde(skip)]
 	points: Vec<[f64; 2]>,
 
 	show_euler: bool,
@@ -12,23 +16,22 @@ pub struct App {
 
 
 impl eframe::App for App {
-	fn update(&mut self, ctx: &Context, frame: &mut Frame) {
-		egui::CentralPanel::default().show(ctx, |ui| {
-			ui.style_mut().spacing.slider_width = (1200.0);
-
-			ui.checkbox(&mut self.show_euler, "show euler");
-			ui.checkbox(&mut self.show_original, "show original");
-
-			self.render_plot(ui);
+	fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+		egui::TopBottomPanel::bottom("state").show(ctx, |ui| app_state::file_ui(ui, self));
+		egui::CentralPanel::default().show(ctx, |ui| self.ui(ui));
+	}
 
-		});
+	fn save(&mut self, storage: &mut dyn eframe::Storage) {
+		app_state::save(storage, self);
 	}
 }
 
+impl app_state::State for App {}
+
 impl Default for App {
 	fn default() -> Self {
 		Self {
-			points: generate_euler_points(),
+			points: euler_solution().points,
 			show_euler: true,
 			show_original: true,
 		}
@@ -36,6 +39,16 @@ impl Default for App {
 }
 
 impl App {
+	pub fn ui(&mut self, ui: &mut Ui) {
+		ui.style_mut().spacing.slider_width = 1200.0;
+
+		ui.checkbox(&mut self.show_euler, "show euler");
+		ui.checkbox(&mut self.show_original, "show original");
+
+		self.render_plot(ui);
+
+	}
+
 	fn render_plot(&self, ui: &mut Ui) {
 		Plot::new("my_plot").show(ui, |plot_ui| {
 			let euler_line = self.generate_euler_line();
@@ -73,32 +86,3 @@ impl App {
 		Points::new(ORIGINAL.to_vec()).radius(6.0)
 	}
 }
-
-
-fn generate_euler_points() -> Vec<[f64; 2]> {
-	let p = 2;
-	let h = 0.05;
-	let left = 1.0;
-	let right = 1.5;
-
-	let mut x0 = left;
-	let mut y0 = 0.0;
-
-	let mut result = vec![[x0, y0]];
-
-	while x0 <= right {
-		let x1 = x0 + h;
-		let y1 = y0 + h * f(x0, y0);
-
-		let y_other = y0 + h / 2.0 * f(x0, y0);
-		let y_half = y_other + h / 2.0 * f(x0 + h / 2.0, y_other);
-		let r = ((y1 - y_half) / (2.0_f64.powi(p) - 1.0)).abs();
-		println!("{:?}: {r}", [x1, y1]);
-
-		result.push([x1, y1]);
-		x0 = x1;
-		y0 = y1;
-	}
-
-	result
-}
@@ -0,0 +1,4 @@
+pub mod app;
+pub mod model;
+
+pub use app::App;
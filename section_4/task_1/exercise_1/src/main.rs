@@ -1,14 +1,13 @@
-use crate::app::App;
-
-pub mod model;
-mod app;
-
-
+use numerics::report::{Format, Table};
+use section_4_task_1::model::euler_solution;
+use section_4_task_1::App;
 
 fn main() {
 	env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+	print!("{}", Table::cauchy("Euler method", &euler_solution()).render(Format::Markdown, 7));
+
 	let options = eframe::NativeOptions {
-		viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]),
+		viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]).with_app_id("section_4_task_1"),
 		..Default::default()
 	};
 
@@ -16,7 +15,7 @@ fn main() {
 		"My egui App",
 		options,
 		Box::new(|cc| {
-			Box::<App>::default()
+			Box::new(app_state::load::<App>(cc))
 		}),
 	).expect("egui error");
 }
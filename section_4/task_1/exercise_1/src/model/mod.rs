@@ -1,3 +1,5 @@
+use numerics::ode::{euler, Solution};
+
 pub fn f(x: f64, y: f64) -> f64 {
 	(x.powi(2) * y.powi(2) - (2.0 * x + 1.0) * y + 1.0) / x
 }
@@ -16,3 +18,9 @@ pub const ORIGINAL: [[f64; 2]; 11] = [
 	[1.45,0.214031],
 	[1.5,0.222222]
 ];
+
+
+/// The Euler method on `[1, 1.5]` with step 0.05 from `y(1) = 0`.
+pub fn euler_solution() -> Solution {
+	euler(f, 1.0, 0.0, 1.5, 0.05)
+}
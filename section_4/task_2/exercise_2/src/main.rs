@@ -1,14 +1,14 @@
-use crate::app::App;
-
-pub mod model;
-mod app;
-
-
+use numerics::report::{Format, Table};
+use section_4_task_2::model::{euler_solution, runge_kutta_solution};
+use section_4_task_2::App;
 
 fn main() {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+    println!("{}", Table::cauchy("Euler method", &euler_solution()).render(Format::Markdown, 7));
+    println!("{}", Table::cauchy("Runge–Kutta method", &runge_kutta_solution()).render(Format::Markdown, 7));
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]),
+        viewport: egui::ViewportBuilder::default().with_inner_size([320.0, 240.0]).with_app_id("section_4_task_2"),
         ..Default::default()
     };
 
@@ -16,7 +16,7 @@ fn main() {
         "My egui App",
         options,
         Box::new(|cc| {
-            Box::<App>::default()
+            Box::new(app_state::load::<App>(cc))
         }),
     ).expect("egui error");
 }
@@ -1,3 +1,5 @@
+use numerics::ode::{euler, runge_kutta, Solution};
+
 pub fn f(x: f64, y: f64) -> f64 {
 	(x.powi(2) * y.powi(2) - (2.0 * x + 1.0) * y + 1.0) / x
 }
@@ -16,3 +18,14 @@ pub const ORIGINAL: [[f64; 2]; 11] = [
 	[1.5,0.222222]
 ];
 
+pub const H: f64 = 0.05;
+pub const LEFT: f64 = 1.0;
+pub const RIGHT: f64 = 1.5;
+
+pub fn euler_solution() -> Solution {
+	euler(f, LEFT, 0.0, RIGHT, H)
+}
+
+pub fn runge_kutta_solution() -> Solution {
+	runge_kutta(f, LEFT, 0.0, RIGHT, H)
+}
@@ -0,0 +1,167 @@
+use eframe::Frame;
+use egui::{Context, Ui};
+use serde::{Deserialize, Serialize};
+use crate::controls::Controls;
+use crate::curves::CurvesView;
+use crate::linsys::LinearSystemsView;
+use crate::roots::RootsView;
+use crate::surfaces::SurfaceView;
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+enum Tab {
+	Roots,
+	LinearSystems,
+	Interpolation,
+	Splines,
+	Curves,
+	Surfaces,
+	Odes,
+}
+
+impl Tab {
+	const ALL: [Tab; 7] = [Tab::Roots, Tab::LinearSystems, Tab::Interpolation, Tab::Splines, Tab::Curves, Tab::Surfaces, Tab::Odes];
+
+	fn name(self) -> &'static str {
+		match self {
+			Tab::Roots => "Root finding",
+			Tab::LinearSystems => "Linear systems",
+			Tab::Interpolation => "Interpolation",
+			Tab::Splines => "Splines",
+			Tab::Curves => "Curves",
+			Tab::Surfaces => "Surfaces",
+			Tab::Odes => "ODEs",
+		}
+	}
+}
+
+/// One window with a tab per section; the tabs host the views of the exercise apps.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workbench {
+	tab: Tab,
+	controls: Controls,
+	/// Whether the exercise apps follow the shared function instead of their own.
+	use_shared_function: bool,
+
+	roots: RootsView,
+	linear_systems: LinearSystemsView,
+	interpolation: section_3_task_1::App,
+	/// `false` shows the section 2 Hermite spline, `true` the section 3 parabolic and cubic splines.
+	power_splines: bool,
+	hermite_spline: section_2_task_1::App,
+	splines: section_3_task_2::App,
+	curves: CurvesView,
+	surfaces: SurfaceView,
+	/// `false` shows the section 4 Euler method, `true` Runge–Kutta and Adams.
+	higher_order: bool,
+	euler: section_4_task_1::App,
+	runge_kutta: section_4_task_2::App,
+}
+
+impl Default for Workbench {
+	fn default() -> Self {
+		Self {
+			tab: Tab::Roots,
+			controls: Controls::default(),
+			use_shared_function: false,
+
+			roots: RootsView::default(),
+			linear_systems: LinearSystemsView::default(),
+			interpolation: section_3_task_1::App::default(),
+			power_splines: true,
+			hermite_spline: section_2_task_1::App::default(),
+			splines: section_3_task_2::App::default(),
+			curves: CurvesView::default(),
+			surfaces: SurfaceView::default(),
+			higher_order: true,
+			euler: section_4_task_1::App::default(),
+			runge_kutta: section_4_task_2::App::default(),
+		}
+	}
+}
+
+impl eframe::App for Workbench {
+	fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+		egui::SidePanel::left("sections").show(ctx, |ui| {
+			for tab in Tab::ALL {
+				ui.selectable_value(&mut self.tab, tab, tab.name());
+			}
+
+			ui.separator();
+			self.render_controls(ui);
+		});
+
+		egui::TopBottomPanel::bottom("state").show(ctx, |ui| app_state::file_ui(ui, self));
+		egui::CentralPanel::default().show(ctx, |ui| self.render_tab(ui));
+	}
+
+	fn save(&mut self, storage: &mut dyn eframe::Storage) {
+		app_state::save(storage, self);
+	}
+}
+
+impl app_state::State for Workbench {
+	fn restore(&mut self) {
+		self.controls.restore();
+		self.hermite_spline.restore();
+		self.interpolation.restore();
+		self.splines.restore();
+		if self.use_shared_function {
+			self.share_function();
+		}
+	}
+}
+
+impl Workbench {
+	fn render_controls(&mut self, ui: &mut Ui) {
+		let changed = self.controls.ui(ui);
+		let toggled = ui.checkbox(&mut self.use_shared_function, "use in interpolation and splines").changed();
+
+		if (changed || toggled) && self.use_shared_function {
+			self.share_function();
+		} else if toggled {
+			self.interpolation = section_3_task_1::App::default();
+			self.splines = section_3_task_2::App::default();
+		}
+	}
+
+	fn share_function(&mut self) {
+		let (a, b) = (self.controls.a, self.controls.b);
+		self.interpolation.set_function(self.controls.function(), a, b);
+		self.splines.set_function(self.controls.function(), self.controls.derivative(), a, b);
+	}
+
+	fn render_tab(&mut self, ui: &mut Ui) {
+		match self.tab {
+			Tab::Roots => self.roots.ui(ui, &self.controls),
+			Tab::LinearSystems => self.linear_systems.ui(ui, &self.controls),
+			Tab::Interpolation => self.interpolation.ui(ui),
+			Tab::Splines => {
+				ui.horizontal(|ui| {
+					ui.selectable_value(&mut self.power_splines, false, "Hermite (section 2)");
+					ui.selectable_value(&mut self.power_splines, true, "Parabolic and cubic (section 3)");
+				});
+
+				if self.power_splines {
+					self.splines.ui(ui);
+				} else {
+					self.hermite_spline.ui(ui);
+				}
+			}
+			Tab::Curves => self.curves.ui(ui),
+			Tab::Surfaces => self.surfaces.ui(ui),
+			Tab::Odes => {
+				ui.horizontal(|ui| {
+					ui.selectable_value(&mut self.higher_order, false, "Euler (task 1)");
+					ui.selectable_value(&mut self.higher_order, true, "Runge–Kutta and Adams (task 2)");
+				});
+
+				if self.higher_order {
+					self.runge_kutta.ui(ui);
+				} else {
+					self.euler.ui(ui);
+				}
+			}
+		}
+	}
+}
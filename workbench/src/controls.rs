@@ -0,0 +1,99 @@
+use std::rc::Rc;
+use egui::{Color32, Ui};
+use numerics::expr::Expr;
+use serde::{Deserialize, Serialize};
+
+/// Function, interval and tolerance shared by the tabs of the workbench.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Controls {
+	source: String,
+	#[serde(skip)]
+	error: Option<String>,
+	#[serde(skip)]
+	function: Rc<dyn Fn(f64) -> f64>,
+	pub a: f64,
+	pub b: f64,
+	pub epsilon: f64,
+}
+
+impl Default for Controls {
+	fn default() -> Self {
+		let source = "(x - 1)^3 + 0.5 * exp(x)".to_string();
+		let expr = Expr::parse(&source, &["x"]).unwrap();
+
+		Self {
+			source,
+			error: None,
+			function: Rc::new(move |x| expr.eval(&[x])),
+			a: 0.0,
+			b: 0.5,
+			epsilon: 1e-9,
+		}
+	}
+}
+
+impl Controls {
+	/// Parses the saved source again.
+	pub fn restore(&mut self) {
+		self.parse();
+	}
+
+	/// Replaces the function when the source is valid, and reports whether it was.
+	fn parse(&mut self) -> bool {
+		match Expr::parse(&self.source, &["x"]) {
+			Ok(expr) => {
+				self.function = Rc::new(move |x| expr.eval(&[x]));
+				self.error = None;
+				true
+			}
+			Err(error) => {
+				self.error = Some(error.to_string());
+				false
+			}
+		}
+	}
+
+	/// Text of the function as typed.
+	pub fn source(&self) -> &str {
+		&self.source
+	}
+
+	pub fn function(&self) -> Rc<dyn Fn(f64) -> f64> {
+		self.function.clone()
+	}
+
+	/// Central difference approximation of the derivative of the function.
+	pub fn derivative(&self) -> Rc<dyn Fn(f64) -> f64> {
+		let function = self.function.clone();
+		Rc::new(move |x| {
+			let h = 1e-6 * x.abs().max(1.0);
+			(function(x + h) - function(x - h)) / (2.0 * h)
+		})
+	}
+
+	/// Shows the controls and returns whether the function or the interval changed.
+	pub fn ui(&mut self, ui: &mut Ui) -> bool {
+		let mut changed = false;
+
+		ui.label("f(x) =");
+		if ui.text_edit_singleline(&mut self.source).changed() {
+			changed |= self.parse();
+		}
+		if let Some(error) = &self.error {
+			ui.colored_label(Color32::RED, error);
+		}
+
+		ui.horizontal(|ui| {
+			changed |= ui.add(egui::DragValue::new(&mut self.a).speed(0.01).prefix("a: ")).changed();
+			changed |= ui.add(egui::DragValue::new(&mut self.b).speed(0.01).prefix("b: ")).changed();
+		});
+		if self.b <= self.a {
+			ui.colored_label(Color32::RED, "the interval must have a < b");
+		}
+
+		ui.add(egui::Slider::new(&mut self.epsilon, 1e-12..=1e-1).logarithmic(true).text("tolerance"));
+
+		changed && self.error.is_none() && self.a < self.b
+	}
+}
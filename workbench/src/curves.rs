@@ -0,0 +1,116 @@
+use egui::Ui;
+use egui_plot::{Line, Plot, PlotPoint, Points};
+use numerics::spline::{ParametricSpline, Parametrization};
+use serde::{Deserialize, Serialize};
+
+/// Distance in pixels within which the pointer grabs a point.
+const GRAB_RADIUS: f32 = 10.0;
+
+/// Editor of a parametric spline curve: click to add a point, drag to move it, right click to remove it.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct CurvesView {
+	points: Vec<[f64; 2]>,
+	centripetal: bool,
+	closed: bool,
+	show_tangents: bool,
+
+	/// The point under the pointer in the previous frame, which keeps the plot from panning when it is dragged.
+	#[serde(skip)]
+	hovered: Option<usize>,
+	#[serde(skip)]
+	dragged: Option<usize>,
+}
+
+impl Default for CurvesView {
+	fn default() -> Self {
+		Self {
+			points: vec![[0.0, 0.0], [2.0, 3.0], [5.0, 4.0], [7.0, 1.0], [9.0, 2.0]],
+			centripetal: true,
+			closed: false,
+			show_tangents: false,
+
+			hovered: None,
+			dragged: None,
+		}
+	}
+}
+
+impl CurvesView {
+	pub fn ui(&mut self, ui: &mut Ui) {
+		ui.horizontal(|ui| {
+			ui.selectable_value(&mut self.centripetal, false, "chord length");
+			ui.selectable_value(&mut self.centripetal, true, "centripetal");
+			ui.separator();
+			ui.checkbox(&mut self.closed, "closed");
+			ui.checkbox(&mut self.show_tangents, "tangents");
+			if ui.button("clear").clicked() {
+				self.points.clear();
+			}
+		});
+		ui.label("click to add a point, drag a point to move it, right click to remove it");
+
+		let parametrization = if self.centripetal { Parametrization::Centripetal } else { Parametrization::ChordLength };
+		let curve = ParametricSpline::new(&self.points, parametrization, self.closed);
+		match &curve {
+			Ok(curve) => { ui.label(format!("length: {:.4}", curve.length())); }
+			Err(error) => { ui.label(error.to_string()); }
+		}
+
+		let response = Plot::new("curves_plot")
+			.data_aspect(1.0)
+			.include_x(0.0).include_x(10.0).include_y(0.0).include_y(5.0)
+			.allow_drag(self.hovered.is_none() && self.dragged.is_none())
+			.allow_double_click_reset(false)
+			.show(ui, |plot_ui| {
+				if let Ok(curve) = &curve {
+					plot_ui.line(Line::new(curve.sample(1000)).name("curve"));
+
+					if self.show_tangents {
+						for &t in curve.parameters() {
+							let ([x, y], [dx, dy]) = (curve.point(t), curve.tangent(t));
+							let scale = 0.5 / dx.hypot(dy).max(f64::EPSILON);
+							plot_ui.line(Line::new(vec![[x, y], [x + scale * dx, y + scale * dy]]).color(egui::Color32::GRAY));
+						}
+					}
+				}
+				plot_ui.points(Points::new(self.points.clone()).radius(5.0).name("points"));
+
+				let pointer = plot_ui.pointer_coordinate();
+				let hovered = plot_ui.response().hover_pos().and_then(|position| {
+					self.points.iter().enumerate()
+						.map(|(index, &[x, y])| (index, plot_ui.screen_from_plot(PlotPoint::new(x, y)).distance(position)))
+						.filter(|&(_, distance)| distance <= GRAB_RADIUS)
+						.min_by(|a, b| a.1.total_cmp(&b.1))
+						.map(|(index, _)| index)
+				});
+				(pointer, hovered)
+			});
+
+		let (pointer, hovered) = response.inner;
+		let response = response.response;
+		self.hovered = hovered;
+
+		if response.drag_started() {
+			self.dragged = hovered;
+		}
+		if let (Some(index), Some(pointer)) = (self.dragged, pointer) {
+			self.points[index] = [pointer.x, pointer.y];
+		}
+		if response.drag_stopped() {
+			self.dragged = None;
+		}
+
+		if response.clicked() && hovered.is_none() {
+			if let Some(pointer) = pointer {
+				self.points.push([pointer.x, pointer.y]);
+			}
+		}
+		if response.secondary_clicked() {
+			if let Some(index) = hovered {
+				self.points.remove(index);
+				self.hovered = None;
+			}
+		}
+	}
+}
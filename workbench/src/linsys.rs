@@ -0,0 +1,223 @@
+use egui::{Color32, Ui};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints, Points, VLine};
+use nalgebra::{DMatrix, DVector};
+use numerics::linalg::{
+	best_relaxation, jacobi_form, optimal_relaxation, relaxation_sweep, seidel_method, simple_iterations_method, spectral_radius,
+	Diagnostics, Error, Solution, SweepPoint,
+};
+use serde::{Deserialize, Serialize};
+use crate::controls::Controls;
+
+/// Iterations after which a run of the sweep counts as not converged.
+const SWEEP_LIMIT: usize = 2000;
+
+/// The section 2 system solved by simple iterations, the Seidel method and relaxation.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinearSystemsView {
+	omega: f64,
+	/// Values of ω in the sweep over `(0, 2)`.
+	sweep_points: usize,
+
+	/// Rebuilt when the tolerance or ω change.
+	#[serde(skip)]
+	results: Option<Results>,
+	/// Rebuilt when the tolerance or the number of points change.
+	#[serde(skip)]
+	sweep: Option<Sweep>,
+}
+
+/// Diagnostics of the system and its solution by every method, for one tolerance and ω.
+struct Results {
+	epsilon: f64,
+	omega: f64,
+	diagnostics: Result<Diagnostics, Error>,
+	/// Empty unless the diagnostics succeeded.
+	solutions: Vec<(&'static str, Result<Solution, Error>)>,
+}
+
+impl Results {
+	fn new(a: &DMatrix<f64>, b: &DVector<f64>, epsilon: f64, omega: f64) -> Self {
+		let diagnostics = Diagnostics::new(a);
+		let solutions = match diagnostics {
+			Ok(_) => vec![
+				("simple iterations", simple_iterations_method(a, b, epsilon)),
+				("seidel", seidel_method(a, b, epsilon, 1.0)),
+				("relaxation", seidel_method(a, b, epsilon, omega)),
+			],
+			Err(_) => Vec::new(),
+		};
+
+		Self { epsilon, omega, diagnostics, solutions }
+	}
+}
+
+/// Iterations of the relaxation method against ω, and the optimal ω predicted from the Jacobi method.
+struct Sweep {
+	epsilon: f64,
+	points: Vec<SweepPoint>,
+	/// Spectral radius of the Jacobi iteration matrix.
+	rho: f64,
+	theoretical: Option<f64>,
+	empirical: Option<SweepPoint>,
+}
+
+impl Sweep {
+	fn new(a: &DMatrix<f64>, b: &DVector<f64>, epsilon: f64, count: usize) -> Self {
+		let omegas: Vec<f64> = (1..=count).map(|k| 2.0 * k as f64 / (count + 1) as f64).collect();
+		// the system has passed the diagnostics, which is all the sweep checks
+		let points = relaxation_sweep(a, b, epsilon, SWEEP_LIMIT, &omegas).unwrap_or_default();
+		let rho = spectral_radius(&jacobi_form(a, b).0);
+
+		Self { epsilon, empirical: best_relaxation(&points), points, rho, theoretical: optimal_relaxation(rho) }
+	}
+}
+
+impl Default for LinearSystemsView {
+	fn default() -> Self {
+		Self {
+			omega: 1.5,
+			sweep_points: 79,
+			results: None,
+			sweep: None,
+		}
+	}
+}
+
+impl LinearSystemsView {
+	pub fn ui(&mut self, ui: &mut Ui, controls: &Controls) {
+		let (a, b) = section_2_task_2::system();
+
+		self.refresh(&a, &b, controls.epsilon);
+		let Some(results) = &self.results else { return };
+		match &results.diagnostics {
+			Ok(diagnostics) => diagnostics_ui(ui, diagnostics),
+			Err(error) => {
+				ui.colored_label(Color32::RED, error.to_string());
+				return;
+			}
+		}
+
+		if ui.add(egui::Slider::new(&mut self.omega, 0.05..=1.95).text("relaxation parameter ω")).changed() {
+			self.refresh(&a, &b, controls.epsilon);
+		}
+		let Some(results) = &self.results else { return };
+
+		egui::Grid::new("linsys_grid").striped(true).show(ui, |ui| {
+			ui.label("method");
+			ui.label("iterations");
+			ui.label("error");
+			ui.label("residual norm");
+			ui.end_row();
+
+			for (name, solution) in &results.solutions {
+				ui.label(*name);
+				match solution {
+					Ok(solution) => {
+						ui.label(solution.iterations.to_string());
+						ui.label(format!("{:e}", solution.error));
+						ui.label(format!("{:e}", residual(&a, &b, solution)));
+					}
+					Err(error) => {
+						ui.colored_label(Color32::RED, error.to_string());
+					}
+				}
+				ui.end_row();
+			}
+		});
+
+		if let Some((_, Ok(relaxation))) = results.solutions.last() {
+			ui.label(format!("x: {:?}", relaxation.x.as_slice()));
+		}
+
+		let charts: Vec<BarChart> = results.solutions.iter().enumerate().filter_map(|(offset, (name, solution))| {
+			let solution = solution.as_ref().ok()?;
+			let bars = solution.x.iter().enumerate().map(|(i, &x)| {
+				Bar::new(i as f64 + 0.25 * offset as f64, x).width(0.25)
+			}).collect();
+			Some(BarChart::new(bars).name(*name))
+		}).collect();
+
+		egui::CollapsingHeader::new("ω sweep").default_open(true).show(ui, |ui| {
+			self.sweep_ui(ui, &a, &b, controls.epsilon);
+		});
+
+		Plot::new("linsys_plot").legend(Legend::default()).show(ui, |plot_ui| {
+			for chart in charts {
+				plot_ui.bar_chart(chart);
+			}
+		});
+	}
+
+	/// Solves the system again when the tolerance or ω changed since the last time.
+	fn refresh(&mut self, a: &DMatrix<f64>, b: &DVector<f64>, epsilon: f64) {
+		if self.results.as_ref().is_none_or(|results| results.epsilon != epsilon || results.omega != self.omega) {
+			self.results = Some(Results::new(a, b, epsilon, self.omega));
+		}
+	}
+
+	/// Plot of the iterations of the relaxation method against ω, with the theoretical and the empirical optimum.
+	fn sweep_ui(&mut self, ui: &mut Ui, a: &DMatrix<f64>, b: &DVector<f64>, epsilon: f64) {
+		let changed = ui.add(egui::Slider::new(&mut self.sweep_points, 9..=399).text("values of ω")).changed();
+		if changed || self.sweep.as_ref().is_none_or(|sweep| sweep.epsilon != epsilon) {
+			self.sweep = Some(Sweep::new(a, b, epsilon, self.sweep_points));
+		}
+		let Some(sweep) = &self.sweep else { return };
+
+		ui.label(format!("spectral radius of the Jacobi iteration matrix ρ = {:.6}", sweep.rho));
+		ui.horizontal(|ui| {
+			match sweep.theoretical {
+				Some(omega) => ui.label(format!("theoretical ω = 2 / (1 + √(1 - ρ²)) = {omega:.4}")),
+				None => ui.colored_label(Color32::RED, "ρ ≥ 1: no theoretical ω"),
+			};
+			if let Some(omega) = sweep.theoretical {
+				if ui.button("use").clicked() {
+					self.omega = omega.clamp(0.05, 1.95);
+				}
+			}
+		});
+		ui.horizontal(|ui| {
+			match sweep.empirical {
+				Some(best) => ui.label(format!("empirical ω = {:.4} with {} iterations", best.omega, best.iterations)),
+				None => ui.colored_label(Color32::RED, format!("no ω converged within {SWEEP_LIMIT} iterations")),
+			};
+			if let Some(best) = sweep.empirical {
+				if ui.button("use").clicked() {
+					self.omega = best.omega.clamp(0.05, 1.95);
+				}
+			}
+		});
+
+		let converged: Vec<[f64; 2]> = sweep.points.iter().filter(|point| point.converged).map(|point| [point.omega, point.iterations as f64]).collect();
+		let failed: Vec<[f64; 2]> = sweep.points.iter().filter(|point| !point.converged).map(|point| [point.omega, SWEEP_LIMIT as f64]).collect();
+
+		Plot::new("sweep_plot").legend(Legend::default()).height(220.0).include_x(0.0).include_x(2.0).include_y(0.0).show(ui, |plot_ui| {
+			plot_ui.line(Line::new(PlotPoints::from(converged)).name("iterations"));
+			if !failed.is_empty() {
+				plot_ui.points(Points::new(failed).radius(3.0).color(Color32::RED).name("not converged"));
+			}
+			if let Some(omega) = sweep.theoretical {
+				plot_ui.vline(VLine::new(omega).name("theoretical ω"));
+			}
+			if let Some(best) = sweep.empirical {
+				plot_ui.vline(VLine::new(best.omega).name("empirical ω"));
+			}
+			plot_ui.vline(VLine::new(self.omega).color(Color32::GRAY).name("current ω"));
+		});
+	}
+}
+
+/// Norms and spectral radius of the Jacobi iteration matrix, and why convergence might not be guaranteed.
+fn diagnostics_ui(ui: &mut Ui, diagnostics: &Diagnostics) {
+	ui.label(format!(
+		"iteration matrix: ‖G‖∞ = {:.4}, ‖G‖₁ = {:.4}, ‖G‖₂ = {:.4}, ‖G‖F = {:.4}, ρ(G) ≈ {:.4}, diagonal dominance: {:?}",
+		diagnostics.norm_inf, diagnostics.norm_1, diagnostics.norm_2, diagnostics.frobenius, diagnostics.spectral_radius, diagnostics.dominance,
+	));
+	for warning in diagnostics.warnings() {
+		ui.colored_label(ui.visuals().warn_fg_color, warning);
+	}
+}
+
+fn residual(a: &DMatrix<f64>, b: &DVector<f64>, solution: &Solution) -> f64 {
+	(b - a * &solution.x).norm()
+}
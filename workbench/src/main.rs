@@ -0,0 +1,24 @@
+use crate::app::Workbench;
+
+mod app;
+mod controls;
+mod curves;
+mod linsys;
+mod roots;
+mod surfaces;
+
+fn main() {
+	env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
+	let options = eframe::NativeOptions {
+		viewport: egui::ViewportBuilder::default().with_inner_size([1280.0, 800.0]).with_app_id("workbench"),
+		..Default::default()
+	};
+
+	eframe::run_native(
+		"Numerical methods workbench",
+		options,
+		Box::new(|cc| {
+			Box::new(app_state::load::<Workbench>(cc))
+		}),
+	).expect("egui error");
+}
@@ -0,0 +1,129 @@
+use egui::Ui;
+use egui_plot::{Line, Plot, PlotPoints, Points};
+use numerics::roots::{self, find_solution_newton_secant, find_solution_split, Point, SolutionNewtonSecant, SolutionSplit};
+use serde::{Deserialize, Serialize};
+use crate::controls::Controls;
+
+/// Iterations of either method before it gives up.
+const MAX_ITERATIONS: usize = 10_000;
+
+/// Bisection down to a coarse interval followed by the secant method, as in section 1.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct RootsView {
+	split_epsilon: f64,
+
+	/// Rebuilt when the function, the interval or either tolerance change.
+	#[serde(skip)]
+	search: Option<Search>,
+}
+
+/// Outcome of both methods for the controls it was computed with.
+struct Search {
+	source: String,
+	a: f64,
+	b: f64,
+	epsilon: f64,
+	split_epsilon: f64,
+	outcome: Outcome,
+}
+
+enum Outcome {
+	EmptyInterval,
+	SameSign,
+	Found(SolutionSplit, SolutionNewtonSecant),
+	Failed(roots::Error),
+}
+
+impl Search {
+	fn new(controls: &Controls, split_epsilon: f64) -> Self {
+		let f = controls.function();
+		let left = Point::new(controls.a, &*f);
+		let right = Point::new(controls.b, &*f);
+
+		let outcome = if controls.a >= controls.b {
+			Outcome::EmptyInterval
+		} else if left.y.is_sign_positive() == right.y.is_sign_positive() {
+			Outcome::SameSign
+		} else {
+			let solution = find_solution_split(&*f, left, right, split_epsilon, MAX_ITERATIONS).and_then(|split| {
+				let secant = find_solution_newton_secant(&*f, split.current, split.previous, controls.epsilon, MAX_ITERATIONS)?;
+				Ok((split, secant))
+			});
+			match solution {
+				Ok((split, secant)) => Outcome::Found(split, secant),
+				Err(error) => Outcome::Failed(error),
+			}
+		};
+
+		Self {
+			source: controls.source().to_string(),
+			a: controls.a,
+			b: controls.b,
+			epsilon: controls.epsilon,
+			split_epsilon,
+			outcome,
+		}
+	}
+
+	fn is_current(&self, controls: &Controls, split_epsilon: f64) -> bool {
+		self.source == controls.source()
+			&& self.a == controls.a
+			&& self.b == controls.b
+			&& self.epsilon == controls.epsilon
+			&& self.split_epsilon == split_epsilon
+	}
+}
+
+impl Default for RootsView {
+	fn default() -> Self {
+		Self {
+			split_epsilon: 1e-2,
+			search: None,
+		}
+	}
+}
+
+impl RootsView {
+	pub fn ui(&mut self, ui: &mut Ui, controls: &Controls) {
+		ui.add(egui::Slider::new(&mut self.split_epsilon, 1e-6..=1e-1).logarithmic(true).text("bisection tolerance"));
+
+		if !self.search.as_ref().is_some_and(|search| search.is_current(controls, self.split_epsilon)) {
+			self.search = Some(Search::new(controls, self.split_epsilon));
+		}
+		let Some(search) = &self.search else { return };
+
+		let mut roots = Vec::new();
+		match &search.outcome {
+			Outcome::EmptyInterval => {
+				ui.label("the interval is empty");
+			}
+			Outcome::SameSign => {
+				ui.label("f(a) and f(b) have the same sign, there is no root to bracket");
+			}
+			Outcome::Found(split, secant) => {
+				ui.label(format!("after split. x: {}, f(x): {}, iters: {}", split.current.x, split.current.y, split.iterations));
+				ui.label(format!("after secant. x: {}, f(x): {}, iters: {}", secant.current.x, secant.current.y, secant.iterations));
+
+				roots.push([split.current.x, split.current.y]);
+				roots.push([secant.current.x, secant.current.y]);
+			}
+			Outcome::Failed(error) => {
+				ui.colored_label(egui::Color32::RED, error.to_string());
+			}
+		}
+
+		let f = controls.function();
+		Plot::new("roots_plot").show(ui, |plot_ui| {
+			let amount = 1000;
+			let step = (controls.b - controls.a) / amount as f64;
+			let points: Vec<_> = (0..=amount).map(|i| {
+				let x = controls.a + step * i as f64;
+				[x, f(x)]
+			}).collect();
+
+			plot_ui.line(Line::new(PlotPoints::new(points)));
+			plot_ui.points(Points::new(roots).radius(5.0));
+		});
+	}
+}
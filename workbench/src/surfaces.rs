@@ -0,0 +1,188 @@
+use egui::{Color32, ColorImage, TextureHandle, TextureOptions, Ui};
+use egui_plot::{Plot, PlotImage, PlotPoint, Points};
+use nalgebra::DMatrix;
+use numerics::expr::Expr;
+use numerics::spline::{BicubicSpline, Boundary};
+use serde::{Deserialize, Serialize};
+
+/// Pixels of the heatmap along each side.
+const RESOLUTION: usize = 256;
+
+/// Bicubic spline through a function of `x` and `y` sampled on a grid, shown as a heatmap.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurfaceView {
+	source: String,
+	x_range: [f64; 2],
+	y_range: [f64; 2],
+	/// Nodes of the grid along `x` and `y`.
+	nodes: [usize; 2],
+	show_nodes: bool,
+
+	#[serde(skip)]
+	error: Option<String>,
+	/// Rebuilt whenever the settings change.
+	#[serde(skip)]
+	surface: Option<Surface>,
+}
+
+struct Surface {
+	spline: BicubicSpline,
+	texture: TextureHandle,
+	/// Smallest and largest value of the heatmap.
+	range: [f64; 2],
+	/// Largest difference from the function over the pixels.
+	error: f64,
+}
+
+impl Default for SurfaceView {
+	fn default() -> Self {
+		Self {
+			source: "sin(x) * cos(y)".to_string(),
+			x_range: [0.0, 3.0],
+			y_range: [0.0, 3.0],
+			nodes: [6, 6],
+			show_nodes: true,
+
+			error: None,
+			surface: None,
+		}
+	}
+}
+
+impl SurfaceView {
+	pub fn ui(&mut self, ui: &mut Ui) {
+		let mut changed = false;
+
+		ui.horizontal(|ui| {
+			ui.label("f(x, y) =");
+			changed |= ui.text_edit_singleline(&mut self.source).changed();
+		});
+		ui.horizontal(|ui| {
+			for (name, [a, b]) in [("x", &mut self.x_range), ("y", &mut self.y_range)] {
+				changed |= ui.add(egui::DragValue::new(a).speed(0.01).prefix(format!("{name} from "))).changed();
+				changed |= ui.add(egui::DragValue::new(b).speed(0.01).prefix("to ")).changed();
+			}
+		});
+		ui.horizontal(|ui| {
+			changed |= ui.add(egui::Slider::new(&mut self.nodes[0], 2..=30).text("nodes along x")).changed();
+			changed |= ui.add(egui::Slider::new(&mut self.nodes[1], 2..=30).text("nodes along y")).changed();
+			ui.checkbox(&mut self.show_nodes, "show nodes");
+		});
+
+		if changed || (self.surface.is_none() && self.error.is_none()) {
+			self.generate(ui.ctx());
+		}
+		if let Some(error) = &self.error {
+			ui.colored_label(Color32::RED, error);
+			return;
+		}
+		let Some(surface) = &self.surface else { return };
+
+		let [low, high] = surface.range;
+		ui.label(format!("from {low:.4} (dark) to {high:.4} (bright), largest error {:.2e}", surface.error));
+
+		let [a, b] = self.x_range;
+		let [c, d] = self.y_range;
+		let response = Plot::new("surface_plot").data_aspect(1.0).show(ui, |plot_ui| {
+			let center = PlotPoint::new((a + b) / 2.0, (c + d) / 2.0);
+			let size = egui::vec2((b - a) as f32, (d - c) as f32);
+			plot_ui.image(PlotImage::new(&surface.texture, center, size));
+
+			if self.show_nodes {
+				let nodes: Vec<[f64; 2]> = surface.spline.nodes_x().iter()
+					.flat_map(|&x| surface.spline.nodes_y().iter().map(move |&y| [x, y]))
+					.collect();
+				plot_ui.points(Points::new(nodes).radius(2.0).color(Color32::WHITE));
+			}
+
+			plot_ui.pointer_coordinate()
+		});
+
+		if let Some(PlotPoint { x, y }) = response.inner {
+			let spline = &surface.spline;
+			ui.label(format!(
+				"f({x:.3}, {y:.3}) = {:.6}, ∂f/∂x = {:.6}, ∂f/∂y = {:.6}",
+				spline.solve(x, y), spline.partial_x(x, y), spline.partial_y(x, y),
+			));
+		}
+	}
+
+	fn generate(&mut self, ctx: &egui::Context) {
+		self.surface = None;
+		self.error = None;
+
+		let expr = match Expr::parse(&self.source, &["x", "y"]) {
+			Ok(expr) => expr,
+			Err(error) => {
+				self.error = Some(error.to_string());
+				return;
+			}
+		};
+		let f = |x: f64, y: f64| expr.eval(&[x, y]);
+
+		let [a, b] = self.x_range;
+		let [c, d] = self.y_range;
+		if a >= b || c >= d {
+			self.error = Some("the ranges must be non-empty".to_string());
+			return;
+		}
+
+		let grid = |[a, b]: [f64; 2], amount: usize| -> Vec<f64> {
+			(0..amount).map(|i| a + (b - a) * i as f64 / (amount - 1) as f64).collect()
+		};
+		let (x, y) = (grid(self.x_range, self.nodes[0]), grid(self.y_range, self.nodes[1]));
+		let z = DMatrix::from_fn(x.len(), y.len(), |i, j| f(x[i], y[j]));
+
+		let boundary = if self.nodes.iter().all(|&n| n >= 4) { Boundary::NotAKnot } else { Boundary::Natural };
+		let spline = match BicubicSpline::interpolating(&x, &y, &z, boundary) {
+			Ok(spline) => spline,
+			Err(error) => {
+				self.error = Some(error.to_string());
+				return;
+			}
+		};
+
+		// pixel centres, with the rows of the image going down from the top
+		let step = |[a, b]: [f64; 2]| (b - a) / RESOLUTION as f64;
+		let pixels_x: Vec<f64> = (0..RESOLUTION).map(|i| a + step(self.x_range) * (i as f64 + 0.5)).collect();
+		let pixels_y: Vec<f64> = (0..RESOLUTION).map(|i| d - step(self.y_range) * (i as f64 + 0.5)).collect();
+		let values = spline.eval_grid(&pixels_x, &pixels_y);
+
+		let low = values.min();
+		let high = values.max();
+		let error = (0..RESOLUTION * RESOLUTION)
+			.map(|index| (values[index] - f(pixels_x[index % RESOLUTION], pixels_y[index / RESOLUTION])).abs())
+			.fold(0.0, f64::max);
+
+		let pixels: Vec<Color32> = (0..RESOLUTION * RESOLUTION).map(|index| {
+			let (row, column) = (index / RESOLUTION, index % RESOLUTION);
+			color((values[(column, row)] - low) / (high - low).max(f64::MIN_POSITIVE))
+		}).collect();
+		let image = ColorImage { size: [RESOLUTION, RESOLUTION], pixels };
+		let texture = ctx.load_texture("surface", image, TextureOptions::LINEAR);
+
+		self.surface = Some(Surface { spline, texture, range: [low, high], error });
+	}
+}
+
+/// Colour of `t` in `[0, 1]` on a dark blue to yellow scale.
+fn color(t: f64) -> Color32 {
+	const STOPS: [[f64; 3]; 5] = [
+		[68.0, 1.0, 84.0],
+		[59.0, 82.0, 139.0],
+		[33.0, 145.0, 140.0],
+		[94.0, 201.0, 98.0],
+		[253.0, 231.0, 37.0],
+	];
+
+	let position = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+	let index = (position as usize).min(STOPS.len() - 2);
+	let fraction = position - index as f64;
+	let [r, g, b] = [0, 1, 2].map(|channel| {
+		let (from, to) = (STOPS[index][channel], STOPS[index + 1][channel]);
+		(from + (to - from) * fraction).round() as u8
+	});
+
+	Color32::from_rgb(r, g, b)
+}